@@ -1,3 +1,25 @@
+//! Reentrancy guards for contract entry points.
+//!
+//! # Threat model
+//!
+//! The Soroban host already refuses to invoke a contract that is on the
+//! current call stack, so a classic A→B→A callback aborts the whole
+//! transaction before any guard runs. The guards here are defence in depth
+//! for paths the host does not see as re-entry:
+//!
+//! * a guarded entry point that reaches itself again through internal
+//!   `Self::` calls within the same frame, and
+//! * future host or SDK changes that relax the re-entry restriction.
+//!
+//! Locks live in instance storage and are removed on drop. A failed
+//! invocation rolls its storage writes back, so a lock never outlives the
+//! call that took it and sequential calls in one transaction do not interfere.
+//!
+//! [`ReentrancyGuard`] takes a single contract-wide lock: any two guarded
+//! entry points exclude each other. [`FunctionReentrancyGuard`] is keyed by a
+//! function selector, so only calls to the same selector are rejected and a
+//! guarded entry point may call a differently-guarded helper.
+
 use soroban_sdk::{contracterror, symbol_short, Env, Symbol};
 
 const REENTRANCY_LOCK: Symbol = symbol_short!("REN_LOCK");
@@ -43,3 +65,131 @@ impl<'a> Drop for ReentrancyGuard<'a> {
         self.env.storage().instance().remove(&REENTRANCY_LOCK);
     }
 }
+
+/// A scope guard like [`ReentrancyGuard`], but locking only `selector`.
+///
+/// Use one selector per entry point (typically its short name). Re-entering
+/// the same selector while the guard is alive panics; other selectors are
+/// unaffected.
+pub struct FunctionReentrancyGuard<'a> {
+    env: &'a Env,
+    selector: Symbol,
+}
+
+impl<'a> FunctionReentrancyGuard<'a> {
+    /// Creates a new guard for `selector`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with `ReentrancyError::ReentrantCall` if `selector` is already
+    /// locked.
+    pub fn new(env: &'a Env, selector: Symbol) -> Self {
+        let key = (REENTRANCY_LOCK, selector.clone());
+        if env.storage().instance().has(&key) {
+            env.panic_with_error(ReentrancyError::ReentrantCall);
+        }
+
+        env.storage().instance().set(&key, &true);
+
+        Self { env, selector }
+    }
+}
+
+impl<'a> Drop for FunctionReentrancyGuard<'a> {
+    fn drop(&mut self) {
+        self.env
+            .storage()
+            .instance()
+            .remove(&(REENTRANCY_LOCK, self.selector.clone()));
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, Env, Error};
+
+    const WORK: Symbol = symbol_short!("work");
+
+    fn work(env: &Env) {
+        let _guard = FunctionReentrancyGuard::new(env, WORK);
+    }
+
+    #[contract]
+    pub struct GuardedContract;
+
+    #[contractimpl]
+    impl GuardedContract {
+        /// Re-enters `work` while already holding its lock.
+        pub fn reenter(env: Env) {
+            let _guard = FunctionReentrancyGuard::new(&env, WORK);
+            work(&env);
+        }
+
+        /// Calls `work` twice, one after the other.
+        pub fn sequential(env: Env) {
+            work(&env);
+            work(&env);
+        }
+
+        /// Holds a different selector while calling `work`.
+        pub fn nested_other(env: Env) {
+            let _guard = FunctionReentrancyGuard::new(&env, symbol_short!("outer"));
+            work(&env);
+        }
+
+        /// Holds the contract-wide lock while calling `work`.
+        pub fn nested_global(env: Env) {
+            let _guard = ReentrancyGuard::new(&env);
+            work(&env);
+        }
+
+        pub fn is_locked(env: Env) -> bool {
+            env.storage().instance().has(&(REENTRANCY_LOCK, WORK))
+        }
+    }
+
+    fn setup() -> (Env, GuardedContractClient<'static>) {
+        let env = Env::default();
+        let contract_id = env.register(GuardedContract, ());
+        let client = GuardedContractClient::new(&env, &contract_id);
+        (env, client)
+    }
+
+    #[test]
+    fn reentering_same_selector_aborts() {
+        let (_env, client) = setup();
+        assert_eq!(
+            client.try_reenter(),
+            Err(Ok(Error::from_contract_error(
+                ReentrancyError::ReentrantCall as u32
+            )))
+        );
+        assert!(!client.is_locked());
+    }
+
+    #[test]
+    fn sequential_calls_in_one_invocation_succeed() {
+        let (_env, client) = setup();
+        client.sequential();
+        assert!(!client.is_locked());
+    }
+
+    #[test]
+    fn independent_invocations_succeed() {
+        let (_env, client) = setup();
+        client.sequential();
+        client.sequential();
+        client.nested_other();
+    }
+
+    #[test]
+    fn other_selectors_and_global_lock_do_not_conflict() {
+        let (_env, client) = setup();
+        client.nested_other();
+        client.nested_global();
+        assert!(!client.is_locked());
+    }
+}
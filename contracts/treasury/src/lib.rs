@@ -12,10 +12,35 @@ use soroban_sdk::{
 const CONFIG: Symbol = symbol_short!("CONFIG");
 const PROPOSAL_CTR: Symbol = symbol_short!("PR_CTR");
 const PROPOSAL: Symbol = symbol_short!("PROPOSAL");
+// Payout kind and progress of a proposal, kept apart from the stored
+// proposal so proposals written before streaming payouts still decode.
+const PAYOUT: Symbol = symbol_short!("PR_PAY");
+// Token of a proposal not paid in the treasury token, kept apart from the
+// stored proposal for the same reason.
+const PROPOSAL_TOKEN: Symbol = symbol_short!("PR_TOKEN");
 const ALLOCATION: Symbol = symbol_short!("ALLOC");
 // Stores the registered Governor contract address that may authorise spends
 // without going through the normal multisig path.
 const GOVERNOR: Symbol = symbol_short!("GOVERNOR");
+// Addresses proposals may pay out to. Empty means any recipient is allowed.
+const RECIPIENTS: Symbol = symbol_short!("RCPT_ALW");
+// Cap on executed spend per rolling window, and the spends counted against
+// it. Stored bare for the treasury token and as `(key, token)` for others.
+const SPEND_LIMIT: Symbol = symbol_short!("SPND_LIM");
+const SPEND_LOG: Symbol = symbol_short!("SPND_LOG");
+// Proposal categories accepted by `create_proposal`. Unset or empty means any.
+const CATEGORIES: Symbol = symbol_short!("CATS");
+// Recipients that may not be paid until unfrozen, e.g. after a key compromise.
+const FROZEN: Symbol = symbol_short!("FROZEN");
+// Tokens other than `TreasuryConfig.token` that proposals may pay out in.
+const TOKENS: Symbol = symbol_short!("TOKENS");
+
+/// Longest proposal description accepted, in bytes.
+pub const MAX_DESCRIPTION_LEN: u32 = 256;
+
+/// Most proposal ids a single paged scan (`expire_proposals`,
+/// `get_unsigned_proposals`) visits.
+pub const MAX_SCAN: u32 = 50;
 
 // ── Types ──────────────────────────────────────────────────────────────────────
 
@@ -25,6 +50,7 @@ pub struct TreasuryConfig {
     /// Address that may update configuration and sign proposals.
     pub admin: Address,
     /// ERC-20–like token contract address that represents treasury funds.
+    /// Always supported; further tokens are added with `add_supported_token`.
     pub token: Address,
     /// Set of signer addresses authorised to create/approve/execute proposals.
     pub signers: Vec<Address>,
@@ -40,11 +66,34 @@ pub enum ProposalStatus {
     Expired,
 }
 
+/// Vesting schedule for a streaming proposal: once executed, `per_period`
+/// of the proposal `amount` vests every `period_seconds` until it is all
+/// released.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamTerms {
+    pub per_period: i128,
+    pub period_seconds: u64,
+}
+
+/// How an executed proposal pays out.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalKind {
+    /// The full amount is transferred on execution.
+    LumpSum,
+    /// The amount vests over time and the recipient pulls it with
+    /// `claim_stream`.
+    Streaming(StreamTerms),
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Proposal {
     pub id: u64,
     pub proposer: Address,
+    /// Token the proposal pays out in.
+    pub token: Address,
     pub to: Address,
     pub amount: i128,
     pub category: Symbol,
@@ -53,6 +102,48 @@ pub struct Proposal {
     pub status: ProposalStatus,
     pub created_at: u64,
     pub expires_at: u64,
+    pub kind: ProposalKind,
+    pub executed_at: Option<u64>,
+    /// Amount already released to `to`; equals `amount` once a lump sum is
+    /// paid or a stream is fully claimed.
+    pub claimed: i128,
+}
+
+/// The part of a `Proposal` stored under `(PROPOSAL, id)`. Its layout must
+/// not change, or proposals already on the ledger stop decoding; new
+/// per-proposal data goes under its own key.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct StoredProposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub category: Symbol,
+    pub description: String,
+    pub approvals: Vec<Address>,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+/// Stored under `(PAYOUT, id)`. Proposals without an entry are lump sums,
+/// fully claimed once executed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PayoutState {
+    pub kind: ProposalKind,
+    pub executed_at: Option<u64>,
+    pub claimed: i128,
+}
+
+/// Published for each proposal swept to `Expired` by `expire_proposals`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalExpiredEvent {
+    pub id: u64,
+    pub expires_at: u64,
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -62,6 +153,15 @@ pub struct AllocationSummary {
     pub total_spent: i128,
 }
 
+/// No more than `amount` of a token may be paid out within any
+/// `window_seconds` period. Each supported token has its own limit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingLimit {
+    pub amount: i128,
+    pub window_seconds: u64,
+}
+
 #[soroban_sdk::contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
@@ -81,6 +181,26 @@ pub enum ContractError {
     // Returned when a caller other than the registered Governor contract
     // attempts to use the `governor_spend` entry-point.
     NotAuthorizedCaller = 13,
+    // Returned when the recipient allow-list is set and `to` is not on it.
+    RecipientNotAllowed = 14,
+    // Returned when executing a proposal would exceed the rolling spending limit.
+    SpendingLimitExceeded = 15,
+    InvalidSpendingLimit = 16,
+    // Returned when a proposal's category is empty, or the category list is
+    // set and a proposal uses another one.
+    InvalidCategory = 17,
+    InvalidStreamTerms = 18,
+    // Returned by `claim_stream` for lump-sum proposals.
+    NotAStream = 19,
+    // Returned by `claim_stream` when nothing new has vested.
+    NothingToClaim = 20,
+    // Returned when paying out to a recipient the admin has frozen.
+    RecipientFrozen = 21,
+    // Returned when a proposal uses a token that is not supported.
+    UnsupportedToken = 22,
+    // Returned when a proposal description is empty or longer than
+    // `MAX_DESCRIPTION_LEN`.
+    InvalidDescription = 23,
 }
 
 // ── Helpers ────────────────────────────────────────────────────────────────────
@@ -101,6 +221,129 @@ fn load_config(env: &Env) -> Result<TreasuryConfig, ContractError> {
         .ok_or(ContractError::NotInitialized)
 }
 
+fn load_allowed_recipients(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&RECIPIENTS)
+        .unwrap_or(Vec::new(env))
+}
+
+fn load_frozen_recipients(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&FROZEN)
+        .unwrap_or(Vec::new(env))
+}
+
+fn load_extra_tokens(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&TOKENS)
+        .unwrap_or(Vec::new(env))
+}
+
+fn require_supported_token(
+    env: &Env,
+    cfg: &TreasuryConfig,
+    token: &Address,
+) -> Result<(), ContractError> {
+    if *token != cfg.token && !load_extra_tokens(env).contains(token) {
+        return Err(ContractError::UnsupportedToken);
+    }
+    Ok(())
+}
+
+fn require_allowed_recipient(env: &Env, to: &Address) -> Result<(), ContractError> {
+    let allowed = load_allowed_recipients(env);
+    if !allowed.is_empty() && !allowed.contains(to) {
+        return Err(ContractError::RecipientNotAllowed);
+    }
+    Ok(())
+}
+
+fn require_not_frozen(env: &Env, to: &Address) -> Result<(), ContractError> {
+    if load_frozen_recipients(env).contains(to) {
+        return Err(ContractError::RecipientFrozen);
+    }
+    Ok(())
+}
+
+fn require_admin(env: &Env, caller: &Address) -> Result<(), ContractError> {
+    caller.require_auth();
+    let cfg = load_config(env)?;
+    if *caller != cfg.admin {
+        return Err(ContractError::NotAuthorizedCaller);
+    }
+    Ok(())
+}
+
+/// Spends of `(timestamp, amount)` still inside the window ending at `now`.
+fn recent_spends(
+    env: &Env,
+    log: Vec<(u64, i128)>,
+    now: u64,
+    window_seconds: u64,
+) -> Vec<(u64, i128)> {
+    let mut recent = Vec::new(env);
+    for (ts, amount) in log.iter() {
+        if ts.saturating_add(window_seconds) > now {
+            recent.push_back((ts, amount));
+        }
+    }
+    recent
+}
+
+fn load_spending_limit(env: &Env, cfg: &TreasuryConfig, token: &Address) -> Option<SpendingLimit> {
+    if *token == cfg.token {
+        env.storage().instance().get(&SPEND_LIMIT)
+    } else {
+        env.storage().instance().get(&(SPEND_LIMIT, token.clone()))
+    }
+}
+
+/// Count `amount` of `token` against that token's rolling spending limit.
+///
+/// Amounts in different tokens aren't comparable, so each token is limited
+/// separately. Once the treasury token has a limit, a token without its own
+/// limit cannot be spent at all, so adding a token never opens a way
+/// around the cap.
+fn charge_spending_limit(
+    env: &Env,
+    cfg: &TreasuryConfig,
+    token: &Address,
+    amount: i128,
+) -> Result<(), ContractError> {
+    let Some(limit) = load_spending_limit(env, cfg, token) else {
+        if *token != cfg.token && load_spending_limit(env, cfg, &cfg.token).is_some() {
+            return Err(ContractError::SpendingLimitExceeded);
+        }
+        return Ok(());
+    };
+    let now = env.ledger().timestamp();
+    let is_treasury_token = *token == cfg.token;
+    let log_key = (SPEND_LOG, token.clone());
+    let log: Option<Vec<(u64, i128)>> = if is_treasury_token {
+        env.storage().instance().get(&SPEND_LOG)
+    } else {
+        env.storage().instance().get(&log_key)
+    };
+    let mut recent = recent_spends(env, log.unwrap_or(Vec::new(env)), now, limit.window_seconds);
+    let mut spent: i128 = 0;
+    for (_, logged) in recent.iter() {
+        spent = spent.saturating_add(logged);
+    }
+    if spent.saturating_add(amount) > limit.amount {
+        return Err(ContractError::SpendingLimitExceeded);
+    }
+    recent.push_back((now, amount));
+    if is_treasury_token {
+        env.storage().instance().set(&SPEND_LOG, &recent);
+    } else {
+        env.storage().instance().set(&log_key, &recent);
+    }
+    Ok(())
+}
+
 fn next_proposal_id(env: &Env) -> u64 {
     let current: u64 = env.storage().instance().get(&PROPOSAL_CTR).unwrap_or(0);
     let next = current.saturating_add(1);
@@ -112,8 +355,126 @@ fn proposal_key(id: u64) -> (Symbol, u64) {
     (PROPOSAL, id)
 }
 
-fn allocation_key(category: &Symbol) -> (Symbol, Symbol) {
-    (ALLOCATION, category.clone())
+fn payout_key(id: u64) -> (Symbol, u64) {
+    (PAYOUT, id)
+}
+
+fn proposal_token_key(id: u64) -> (Symbol, u64) {
+    (PROPOSAL_TOKEN, id)
+}
+
+/// What a proposal without a `(PAYOUT, id)` entry is treated as.
+fn default_payout(stored: &StoredProposal) -> PayoutState {
+    PayoutState {
+        kind: ProposalKind::LumpSum,
+        executed_at: None,
+        claimed: if stored.status == ProposalStatus::Executed {
+            stored.amount
+        } else {
+            0
+        },
+    }
+}
+
+fn load_proposal(env: &Env, id: u64) -> Option<Proposal> {
+    let stored: StoredProposal = env.storage().persistent().get(&proposal_key(id))?;
+    let payout: PayoutState = env
+        .storage()
+        .persistent()
+        .get(&payout_key(id))
+        .unwrap_or_else(|| default_payout(&stored));
+    let token = match env.storage().persistent().get(&proposal_token_key(id)) {
+        Some(token) => token,
+        None => load_config(env).ok()?.token,
+    };
+    Some(Proposal {
+        id: stored.id,
+        proposer: stored.proposer,
+        token,
+        to: stored.to,
+        amount: stored.amount,
+        category: stored.category,
+        description: stored.description,
+        approvals: stored.approvals,
+        status: stored.status,
+        created_at: stored.created_at,
+        expires_at: stored.expires_at,
+        kind: payout.kind,
+        executed_at: payout.executed_at,
+        claimed: payout.claimed,
+    })
+}
+
+/// Store `proposal`, writing its payout state only when it differs from
+/// the lump-sum default and its token only when it isn't the treasury token.
+fn save_proposal(env: &Env, proposal: &Proposal) {
+    let stored = StoredProposal {
+        id: proposal.id,
+        proposer: proposal.proposer.clone(),
+        to: proposal.to.clone(),
+        amount: proposal.amount,
+        category: proposal.category.clone(),
+        description: proposal.description.clone(),
+        approvals: proposal.approvals.clone(),
+        status: proposal.status.clone(),
+        created_at: proposal.created_at,
+        expires_at: proposal.expires_at,
+    };
+    let payout = PayoutState {
+        kind: proposal.kind.clone(),
+        executed_at: proposal.executed_at,
+        claimed: proposal.claimed,
+    };
+    let key = payout_key(proposal.id);
+    if payout != default_payout(&stored) {
+        env.storage().persistent().set(&key, &payout);
+    } else if env.storage().persistent().has(&key) {
+        env.storage().persistent().remove(&key);
+    }
+    let treasury_token = load_config(env).ok().map(|cfg| cfg.token);
+    if treasury_token.as_ref() != Some(&proposal.token) {
+        env.storage()
+            .persistent()
+            .set(&proposal_token_key(proposal.id), &proposal.token);
+    }
+    env.storage()
+        .persistent()
+        .set(&proposal_key(proposal.id), &stored);
+}
+
+fn add_allocation(
+    env: &Env,
+    cfg: &TreasuryConfig,
+    token: &Address,
+    category: &Symbol,
+    amount: i128,
+) {
+    let spent = allocation_spent(env, cfg, token, category).saturating_add(amount);
+    // Treasury-token totals keep the key they had before other tokens were
+    // supported, so existing totals carry on.
+    if *token == cfg.token {
+        env.storage()
+            .instance()
+            .set(&(ALLOCATION, category.clone()), &spent);
+    } else {
+        env.storage()
+            .instance()
+            .set(&(ALLOCATION, token.clone(), category.clone()), &spent);
+    }
+}
+
+fn allocation_spent(env: &Env, cfg: &TreasuryConfig, token: &Address, category: &Symbol) -> i128 {
+    if *token == cfg.token {
+        env.storage()
+            .instance()
+            .get(&(ALLOCATION, category.clone()))
+            .unwrap_or(0)
+    } else {
+        env.storage()
+            .instance()
+            .get(&(ALLOCATION, token.clone(), category.clone()))
+            .unwrap_or(0)
+    }
 }
 
 fn has_approval(_env: &Env, proposal: &Proposal, signer: &Address) -> bool {
@@ -176,11 +537,7 @@ impl TreasuryContract {
     /// through the multisig path — the governance vote itself serves as the
     /// multi-party approval.  Only the treasury admin may set this.
     pub fn set_governor(env: Env, caller: Address, governor: Address) -> Result<(), ContractError> {
-        caller.require_auth();
-        let cfg = load_config(&env)?;
-        if caller != cfg.admin {
-            return Err(ContractError::NotAuthorizedCaller);
-        }
+        require_admin(&env, &caller)?;
         env.storage().instance().set(&GOVERNOR, &governor);
         Ok(())
     }
@@ -197,7 +554,9 @@ impl TreasuryContract {
     ///
     /// This bypasses the normal multisig path because the governance proposal
     /// itself serves as the multi-party approval mechanism.  Spend amounts are
-    /// tracked under the `"GOVERN"` allocation category for reporting.
+    /// tracked under the `"GOVERN"` allocation category for reporting, and
+    /// are subject to the same recipient allow-list and rolling spending
+    /// limit as executed proposals.
     pub fn governor_spend(
         env: Env,
         caller: Address,
@@ -219,8 +578,11 @@ impl TreasuryContract {
         if amount <= 0 {
             return Err(ContractError::PositiveAmountRequired);
         }
+        require_allowed_recipient(&env, &to)?;
+        require_not_frozen(&env, &to)?;
 
         let cfg = load_config(&env)?;
+        charge_spending_limit(&env, &cfg, &cfg.token, amount)?;
         token::Client::new(&env, &cfg.token).transfer(
             &env.current_contract_address(),
             &to,
@@ -228,17 +590,216 @@ impl TreasuryContract {
         );
 
         // Track governance-initiated spends under their own allocation category.
-        let key = allocation_key(&symbol_short!("GOVERN"));
-        let mut spent: i128 = env.storage().instance().get(&key).unwrap_or(0);
-        spent = spent.saturating_add(amount);
-        env.storage().instance().set(&key, &spent);
+        add_allocation(&env, &cfg, &cfg.token, &symbol_short!("GOVERN"), amount);
 
         Ok(())
     }
 
+    // ── Recipient allow-list ──────────────────────────────────────────────────
+
+    /// Allow proposals to pay out to `addr`.
+    ///
+    /// While the allow-list is empty any recipient is accepted; once it holds
+    /// at least one address, proposals may only be created for and executed
+    /// to listed recipients.
+    pub fn add_allowed_recipient(
+        env: Env,
+        admin: Address,
+        addr: Address,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        let mut allowed = load_allowed_recipients(&env);
+        if !allowed.contains(&addr) {
+            allowed.push_back(addr);
+            env.storage().instance().set(&RECIPIENTS, &allowed);
+        }
+        Ok(())
+    }
+
+    /// Remove `addr` from the recipient allow-list. Pending proposals to it
+    /// can no longer be executed.
+    pub fn remove_allowed_recipient(
+        env: Env,
+        admin: Address,
+        addr: Address,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        let mut allowed = load_allowed_recipients(&env);
+        if let Some(idx) = allowed.first_index_of(&addr) {
+            allowed.remove(idx);
+            env.storage().instance().set(&RECIPIENTS, &allowed);
+        }
+        Ok(())
+    }
+
+    pub fn get_allowed_recipients(env: Env) -> Vec<Address> {
+        load_allowed_recipients(&env)
+    }
+
+    /// Block all payouts to `addr` (proposal execution, stream claims and
+    /// governor spends) until `unfreeze_recipient` is called. Pending
+    /// proposals and their approvals are left intact.
+    pub fn freeze_recipient(env: Env, admin: Address, addr: Address) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        let mut frozen = load_frozen_recipients(&env);
+        if !frozen.contains(&addr) {
+            frozen.push_back(addr);
+            env.storage().instance().set(&FROZEN, &frozen);
+        }
+        Ok(())
+    }
+
+    /// Allow payouts to `addr` again.
+    pub fn unfreeze_recipient(
+        env: Env,
+        admin: Address,
+        addr: Address,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        let mut frozen = load_frozen_recipients(&env);
+        if let Some(idx) = frozen.first_index_of(&addr) {
+            frozen.remove(idx);
+            env.storage().instance().set(&FROZEN, &frozen);
+        }
+        Ok(())
+    }
+
+    pub fn is_recipient_frozen(env: Env, addr: Address) -> bool {
+        load_frozen_recipients(&env).contains(&addr)
+    }
+
+    // ── Supported tokens ──────────────────────────────────────────────────────
+
+    /// Allow proposals to pay out in `token` as well as the configured
+    /// treasury token.
+    pub fn add_supported_token(
+        env: Env,
+        admin: Address,
+        token: Address,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        let mut tokens = load_extra_tokens(&env);
+        if !tokens.contains(&token) {
+            tokens.push_back(token);
+            env.storage().instance().set(&TOKENS, &tokens);
+        }
+        Ok(())
+    }
+
+    /// Stop accepting `token`. Pending proposals in it can no longer be
+    /// executed. The configured treasury token cannot be removed.
+    pub fn remove_supported_token(
+        env: Env,
+        admin: Address,
+        token: Address,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        let mut tokens = load_extra_tokens(&env);
+        if let Some(idx) = tokens.first_index_of(&token) {
+            tokens.remove(idx);
+            env.storage().instance().set(&TOKENS, &tokens);
+        }
+        Ok(())
+    }
+
+    /// The configured treasury token followed by any added tokens.
+    pub fn get_supported_tokens(env: Env) -> Result<Vec<Address>, ContractError> {
+        let cfg = load_config(&env)?;
+        let mut tokens = Vec::new(&env);
+        tokens.push_back(cfg.token);
+        tokens.append(&load_extra_tokens(&env));
+        Ok(tokens)
+    }
+
+    // ── Spending limit ────────────────────────────────────────────────────────
+
+    /// Cap executed spend of the treasury token to `amount` per rolling
+    /// `window_seconds`. While this is set, other tokens can only be spent
+    /// once they have a limit of their own (`set_token_spending_limit`).
+    pub fn set_spending_limit(
+        env: Env,
+        admin: Address,
+        amount: i128,
+        window_seconds: u64,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        if amount <= 0 || window_seconds == 0 {
+            return Err(ContractError::InvalidSpendingLimit);
+        }
+        env.storage().instance().set(
+            &SPEND_LIMIT,
+            &SpendingLimit {
+                amount,
+                window_seconds,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_spending_limit(env: Env) -> Option<SpendingLimit> {
+        env.storage().instance().get(&SPEND_LIMIT)
+    }
+
+    /// Cap executed spend of `token` to `amount` per rolling
+    /// `window_seconds`. For the treasury token this is `set_spending_limit`.
+    pub fn set_token_spending_limit(
+        env: Env,
+        admin: Address,
+        token: Address,
+        amount: i128,
+        window_seconds: u64,
+    ) -> Result<(), ContractError> {
+        let cfg = load_config(&env)?;
+        if token == cfg.token {
+            return Self::set_spending_limit(env, admin, amount, window_seconds);
+        }
+        require_admin(&env, &admin)?;
+        if amount <= 0 || window_seconds == 0 {
+            return Err(ContractError::InvalidSpendingLimit);
+        }
+        env.storage().instance().set(
+            &(SPEND_LIMIT, token),
+            &SpendingLimit {
+                amount,
+                window_seconds,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_token_spending_limit(
+        env: Env,
+        token: Address,
+    ) -> Result<Option<SpendingLimit>, ContractError> {
+        let cfg = load_config(&env)?;
+        Ok(load_spending_limit(&env, &cfg, &token))
+    }
+
+    // ── Categories ────────────────────────────────────────────────────────────
+
+    /// Restrict proposals to the given categories. An empty list lifts the
+    /// restriction.
+    pub fn set_allowed_categories(
+        env: Env,
+        admin: Address,
+        categories: Vec<Symbol>,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&CATEGORIES, &categories);
+        Ok(())
+    }
+
+    pub fn get_allowed_categories(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&CATEGORIES)
+            .unwrap_or(Vec::new(&env))
+    }
+
     // ── Proposal lifecycle ────────────────────────────────────────────────────
 
-    /// Create a new spending proposal. Only authorised signers may create.
+    /// Create a new spending proposal in the configured treasury token. Only
+    /// authorised signers may create.
     pub fn create_proposal(
         env: Env,
         proposer: Address,
@@ -247,6 +808,89 @@ impl TreasuryContract {
         category: Symbol,
         description: String,
         expires_at: u64,
+    ) -> Result<Proposal, ContractError> {
+        let token = load_config(&env)?.token;
+        Self::new_proposal(
+            &env,
+            proposer,
+            token,
+            to,
+            amount,
+            category,
+            description,
+            expires_at,
+            ProposalKind::LumpSum,
+        )
+    }
+
+    /// Create a new spending proposal paid in `token`, which must be the
+    /// treasury token or one added with `add_supported_token`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_token_proposal(
+        env: Env,
+        proposer: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+        category: Symbol,
+        description: String,
+        expires_at: u64,
+    ) -> Result<Proposal, ContractError> {
+        Self::new_proposal(
+            &env,
+            proposer,
+            token,
+            to,
+            amount,
+            category,
+            description,
+            expires_at,
+            ProposalKind::LumpSum,
+        )
+    }
+
+    /// Create a proposal that pays `total` out gradually: after execution
+    /// `terms.per_period` vests every `terms.period_seconds` and the
+    /// recipient claims it with `claim_stream`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_streaming_proposal(
+        env: Env,
+        proposer: Address,
+        to: Address,
+        total: i128,
+        terms: StreamTerms,
+        category: Symbol,
+        description: String,
+        expires_at: u64,
+    ) -> Result<Proposal, ContractError> {
+        if terms.per_period <= 0 || terms.per_period > total || terms.period_seconds == 0 {
+            return Err(ContractError::InvalidStreamTerms);
+        }
+        let token = load_config(&env)?.token;
+        Self::new_proposal(
+            &env,
+            proposer,
+            token,
+            to,
+            total,
+            category,
+            description,
+            expires_at,
+            ProposalKind::Streaming(terms),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_proposal(
+        env: &Env,
+        proposer: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+        category: Symbol,
+        description: String,
+        expires_at: u64,
+        kind: ProposalKind,
     ) -> Result<Proposal, ContractError> {
         proposer.require_auth();
 
@@ -254,19 +898,34 @@ impl TreasuryContract {
             return Err(ContractError::PositiveAmountRequired);
         }
 
-        if !is_signer(&env, &proposer)? {
+        if !is_signer(env, &proposer)? {
             return Err(ContractError::UnauthorisedProposer);
         }
+        require_supported_token(env, &load_config(env)?, &token)?;
+
+        require_allowed_recipient(env, &to)?;
+
+        if category == Symbol::new(env, "") {
+            return Err(ContractError::InvalidCategory);
+        }
+        let categories = Self::get_allowed_categories(env.clone());
+        if !categories.is_empty() && !categories.contains(&category) {
+            return Err(ContractError::InvalidCategory);
+        }
+
+        if description.is_empty() || description.len() > MAX_DESCRIPTION_LEN {
+            return Err(ContractError::InvalidDescription);
+        }
 
         let now = env.ledger().timestamp();
         if expires_at <= now {
             return Err(ContractError::FutureExpiryRequired);
         }
 
-        let id = next_proposal_id(&env);
+        let id = next_proposal_id(env);
 
         let approvals = {
-            let mut v = Vec::new(&env);
+            let mut v = Vec::new(env);
             // Optional: auto-approve by proposer to reduce friction.
             v.push_back(proposer.clone());
             v
@@ -275,6 +934,7 @@ impl TreasuryContract {
         let proposal = Proposal {
             id,
             proposer,
+            token,
             to,
             amount,
             category,
@@ -283,29 +943,61 @@ impl TreasuryContract {
             status: ProposalStatus::Pending,
             created_at: now,
             expires_at,
+            kind,
+            executed_at: None,
+            claimed: 0,
         };
 
-        env.storage().persistent().set(&proposal_key(id), &proposal);
+        save_proposal(env, &proposal);
         Ok(proposal)
     }
 
     pub fn get_proposal(env: Env, id: u64) -> Option<Proposal> {
-        env.storage().persistent().get(&proposal_key(id))
+        load_proposal(&env, id)
+    }
+
+    /// IDs of pending, unexpired proposals that `signer` has not approved
+    /// yet, oldest first, among up to `limit` (capped at `MAX_SCAN`) ids
+    /// starting from `start_id`.
+    pub fn get_unsigned_proposals(
+        env: Env,
+        signer: Address,
+        start_id: u64,
+        limit: u32,
+    ) -> Vec<u64> {
+        let now = env.ledger().timestamp();
+        let last: u64 = env.storage().instance().get(&PROPOSAL_CTR).unwrap_or(0);
+        let start = start_id.max(1);
+        let end = start
+            .saturating_add(u64::from(limit.min(MAX_SCAN)))
+            .min(last.saturating_add(1));
+        let mut ids = Vec::new(&env);
+
+        for id in start..end {
+            let Some(proposal) = load_proposal(&env, id) else {
+                continue;
+            };
+            if proposal.status == ProposalStatus::Pending
+                && now < proposal.expires_at
+                && !has_approval(&env, &proposal, &signer)
+            {
+                ids.push_back(id);
+            }
+        }
+
+        ids
     }
 
-    /// Approve a proposal. Duplicate approvals are ignored.
-    pub fn approve_proposal(env: Env, signer: Address, id: u64) -> Result<(), ContractError> {
+    /// Approve a proposal and return its approval count. Duplicate approvals
+    /// are ignored.
+    pub fn approve_proposal(env: Env, signer: Address, id: u64) -> Result<u32, ContractError> {
         signer.require_auth();
 
         if !is_signer(&env, &signer)? {
             return Err(ContractError::UnauthorisedSigner);
         }
 
-        let mut proposal: Proposal = env
-            .storage()
-            .persistent()
-            .get(&proposal_key(id))
-            .ok_or(ContractError::ProposalNotFound)?;
+        let mut proposal = load_proposal(&env, id).ok_or(ContractError::ProposalNotFound)?;
 
         if !matches!(proposal.status, ProposalStatus::Pending) {
             return Err(ContractError::ProposalNotPending);
@@ -314,34 +1006,32 @@ impl TreasuryContract {
         let now = env.ledger().timestamp();
         if now >= proposal.expires_at {
             proposal.status = ProposalStatus::Expired;
-            env.storage().persistent().set(&proposal_key(id), &proposal);
+            save_proposal(&env, &proposal);
             return Err(ContractError::ProposalExpired);
         }
 
         if has_approval(&env, &proposal, &signer) {
             // No-op if already approved.
-            return Ok(());
+            return Ok(count_approvals(&proposal));
         }
 
         proposal.approvals.push_back(signer);
-        env.storage().persistent().set(&proposal_key(id), &proposal);
-        Ok(())
+        save_proposal(&env, &proposal);
+        Ok(count_approvals(&proposal))
     }
 
     /// Execute an approved proposal, transferring funds from the treasury to
     /// the destination address and recording allocation statistics.
-    pub fn execute_proposal(env: Env, signer: Address, id: u64) -> Result<(), ContractError> {
+    ///
+    /// Returns the proposal in its executed state.
+    pub fn execute_proposal(env: Env, signer: Address, id: u64) -> Result<Proposal, ContractError> {
         signer.require_auth();
 
         if !is_signer(&env, &signer)? {
             return Err(ContractError::UnauthorisedSigner);
         }
 
-        let mut proposal: Proposal = env
-            .storage()
-            .persistent()
-            .get(&proposal_key(id))
-            .ok_or(ContractError::ProposalNotFound)?;
+        let mut proposal = load_proposal(&env, id).ok_or(ContractError::ProposalNotFound)?;
 
         if !matches!(proposal.status, ProposalStatus::Pending) {
             return Err(ContractError::ProposalNotPending);
@@ -350,7 +1040,7 @@ impl TreasuryContract {
         let now = env.ledger().timestamp();
         if now >= proposal.expires_at {
             proposal.status = ProposalStatus::Expired;
-            env.storage().persistent().set(&proposal_key(id), &proposal);
+            save_proposal(&env, &proposal);
             return Err(ContractError::ProposalExpired);
         }
 
@@ -359,37 +1049,150 @@ impl TreasuryContract {
         if approvals < cfg.threshold {
             return Err(ContractError::InsufficientApprovals);
         }
+        require_allowed_recipient(&env, &proposal.to)?;
+        require_not_frozen(&env, &proposal.to)?;
+        require_supported_token(&env, &cfg, &proposal.token)?;
 
-        // Perform the token transfer.
-        let token_client = token::Client::new(&env, &cfg.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &proposal.to,
-            &proposal.amount,
-        );
+        charge_spending_limit(&env, &cfg, &proposal.token, proposal.amount)?;
+
+        // Lump sums are transferred now; streams start vesting and are
+        // pulled by the recipient via `claim_stream`.
+        if proposal.kind == ProposalKind::LumpSum {
+            let token_client = token::Client::new(&env, &proposal.token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &proposal.to,
+                &proposal.amount,
+            );
+            proposal.claimed = proposal.amount;
+        }
 
         // Mark as executed.
         proposal.status = ProposalStatus::Executed;
-        env.storage().persistent().set(&proposal_key(id), &proposal);
+        proposal.executed_at = Some(now);
+        save_proposal(&env, &proposal);
 
         // Update allocation tracking.
-        let key = allocation_key(&proposal.category);
-        let mut spent: i128 = env.storage().instance().get(&key).unwrap_or(0);
-        spent = spent.saturating_add(proposal.amount);
-        env.storage().instance().set(&key, &spent);
-        Ok(())
+        add_allocation(
+            &env,
+            &cfg,
+            &proposal.token,
+            &proposal.category,
+            proposal.amount,
+        );
+        Ok(proposal)
+    }
+
+    /// Release whatever has vested on an executed streaming proposal since
+    /// the last claim. Only the proposal's recipient may claim. Returns the
+    /// amount transferred.
+    pub fn claim_stream(
+        env: Env,
+        recipient: Address,
+        proposal_id: u64,
+    ) -> Result<i128, ContractError> {
+        recipient.require_auth();
+
+        let mut proposal =
+            load_proposal(&env, proposal_id).ok_or(ContractError::ProposalNotFound)?;
+        if recipient != proposal.to {
+            return Err(ContractError::NotAuthorizedCaller);
+        }
+        let ProposalKind::Streaming(terms) = proposal.kind.clone() else {
+            return Err(ContractError::NotAStream);
+        };
+        let Some(started_at) = proposal.executed_at else {
+            return Err(ContractError::NothingToClaim);
+        };
+
+        let periods = env.ledger().timestamp().saturating_sub(started_at) / terms.period_seconds;
+        let vested = terms
+            .per_period
+            .saturating_mul(i128::from(periods))
+            .min(proposal.amount);
+        let claimable = vested.saturating_sub(proposal.claimed);
+        if claimable <= 0 {
+            return Err(ContractError::NothingToClaim);
+        }
+        require_not_frozen(&env, &proposal.to)?;
+
+        token::Client::new(&env, &proposal.token).transfer(
+            &env.current_contract_address(),
+            &proposal.to,
+            &claimable,
+        );
+        proposal.claimed = proposal.claimed.saturating_add(claimable);
+        save_proposal(&env, &proposal);
+        Ok(claimable)
+    }
+
+    /// Mark pending proposals whose expiry has passed as `Expired`, looking
+    /// at up to `limit` (capped at `MAX_SCAN`) ids starting from `start_id`.
+    ///
+    /// Callable by anyone so keepers can sweep stale proposals page by page.
+    /// Returns how many proposals were expired.
+    pub fn expire_proposals(env: Env, start_id: u64, limit: u32) -> u32 {
+        let now = env.ledger().timestamp();
+        let last: u64 = env.storage().instance().get(&PROPOSAL_CTR).unwrap_or(0);
+        let start = start_id.max(1);
+        let end = start
+            .saturating_add(u64::from(limit.min(MAX_SCAN)))
+            .min(last.saturating_add(1));
+        let mut expired: u32 = 0;
+
+        for id in start..end {
+            let Some(mut proposal) = load_proposal(&env, id) else {
+                continue;
+            };
+            if proposal.status != ProposalStatus::Pending || now < proposal.expires_at {
+                continue;
+            }
+
+            proposal.status = ProposalStatus::Expired;
+            save_proposal(&env, &proposal);
+            #[allow(deprecated)]
+            env.events().publish(
+                (symbol_short!("PR_EXP"), id),
+                ProposalExpiredEvent {
+                    id,
+                    expires_at: proposal.expires_at,
+                    timestamp: now,
+                },
+            );
+            expired = expired.saturating_add(1);
+        }
+
+        expired
     }
 
     // ── Reporting helpers ─────────────────────────────────────────────────────
 
-    /// Returns how much has been spent for a given category across all
-    /// executed proposals.
+    /// Returns how much of the configured treasury token has been spent for
+    /// a given category across all executed proposals.
     pub fn get_allocation_for_category(env: Env, category: Symbol) -> AllocationSummary {
-        let key = allocation_key(&category);
-        let spent: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        let spent: i128 = env
+            .storage()
+            .instance()
+            .get(&(ALLOCATION, category.clone()))
+            .unwrap_or(0);
         AllocationSummary {
             category,
             total_spent: spent,
         }
     }
+
+    /// Returns how much of `token` has been spent for a given category
+    /// across all executed proposals.
+    pub fn get_token_allocation(
+        env: Env,
+        token: Address,
+        category: Symbol,
+    ) -> Result<AllocationSummary, ContractError> {
+        let cfg = load_config(&env)?;
+        let spent = allocation_spent(&env, &cfg, &token, &category);
+        Ok(AllocationSummary {
+            category,
+            total_spent: spent,
+        })
+    }
 }
@@ -1,34 +1,86 @@
 use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
 
+use crate::rbac::{self, TimeRestriction};
+
 // ── Storage keys ──────────────────────────────────────────────
 pub const APPT_CTR: Symbol = symbol_short!("APPT_CTR");
 const APPT_RECORD: Symbol = symbol_short!("APPT_REC");
 const APPT_PATIENT: Symbol = symbol_short!("APPT_PAT");
 const APPT_PROVIDER: Symbol = symbol_short!("APPT_PROV");
 const APPT_HISTORY: Symbol = symbol_short!("APPT_HIST");
+const APPT_PROVIDER_LIST: Symbol = symbol_short!("APPT_PLST");
+const APPT_PROVIDER_DAY: Symbol = symbol_short!("APPT_PDAY");
+const APPT_AVAILABILITY: Symbol = symbol_short!("APPT_AVL");
+const APPT_EXPIRY_CURSOR: Symbol = symbol_short!("APPT_ECUR");
+const APPT_EXPIRY_SCAN: Symbol = symbol_short!("APPT_ESCN");
+const APPT_WAITLIST: Symbol = symbol_short!("APPT_WAIT");
+const APPT_CANCEL_WINDOW: Symbol = symbol_short!("APPT_CWIN");
+const APPT_NO_SHOWS: Symbol = symbol_short!("APPT_NOSH");
+const APPT_NO_SHOW_LIMIT: Symbol = symbol_short!("APPT_NSLM");
+const APPT_MAX_RESCHEDULES: Symbol = symbol_short!("APPT_MRSC");
+const APPT_REMINDER_DUE: Symbol = symbol_short!("APPT_RDUE");
+const APPT_REMINDER_DAY: Symbol = symbol_short!("APPT_RDAY");
+const APPT_REMINDER_CURSOR: Symbol = symbol_short!("APPT_RCUR");
+
+/// Longest appointment that can be booked, in minutes.
+pub const MAX_APPOINTMENT_MINUTES: u32 = 480;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Upper bound on appointments created by one recurring booking (a year of
+/// weekly visits).
+pub const MAX_RECURRING_OCCURRENCES: u32 = 52;
+
+/// How long after its start a still-`Scheduled` appointment is left alone
+/// before the keeper marks it as a no-show.
+pub const NO_SHOW_GRACE_SECONDS: u64 = 86_400;
+
+/// Maximum number of appointments examined per keeper run. Expiring one
+/// appointment writes six entries, so a full batch stays within the
+/// per-invocation write limit.
+const EXPIRY_BATCH: u64 = 8;
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
+/// Maximum number of day buckets each reminder queue reads per sweep.
+const REMINDER_DAYS_PER_SWEEP: u64 = 7;
 
 /// Extends the time-to-live (TTL) for appointment storage keys.
 fn extend_ttl_appointment_key(env: &Env, key: &(Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Extends the time-to-live (TTL) for appointment by patient keys.
 fn extend_ttl_appointment_patient_key(env: &Env, key: &(Symbol, Address, u64)) {
+    let policy = crate::ttl::get_policy(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+/// Extends the time-to-live (TTL) for per-provider appointment id lists.
+fn extend_ttl_provider_list_key(env: &Env, key: &(Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+/// Extends the time-to-live (TTL) for per-patient appointment entries.
+fn extend_ttl_patient_key(env: &Env, key: &(Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Extends the time-to-live (TTL) for appointment by provider keys.
 fn extend_ttl_appointment_provider_key(env: &Env, key: &(Symbol, Address, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 // ── Types ─────────────────────────────────────────────────────
@@ -76,7 +128,42 @@ pub struct Appointment {
     pub updated_at: u64,
     pub verified_at: Option<u64>,
     pub verified_by: Option<Address>,
-    pub reminder_sent: bool,
+    /// Lead times (in seconds) of the reminders already sent, e.g. 86400
+    /// for the day-before reminder
+    pub reminders_sent: Vec<u64>,
+    /// Vision record produced by this visit, attached after completion
+    pub record_id: Option<u64>,
+    /// Set when the appointment was cancelled inside the cancellation window
+    pub late_cancellation: bool,
+    /// How long before `scheduled_at` to remind, overriding the sweep's
+    /// window (e.g. longer notice before surgery)
+    pub reminder_lead_seconds: Option<u64>,
+    /// How many times the appointment has been moved
+    pub reschedule_count: u32,
+}
+
+/// Weekly hours during which a provider accepts appointments (UTC)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProviderAvailability {
+    /// Days of week bitmask, as in `TimeRestriction::DaysOfWeek`
+    /// (0b0000001 = Sunday, 0b1000000 = Saturday)
+    pub day_mask: u32,
+    /// First hour of the day appointments may start
+    pub start_hour: u32,
+    /// Hour by which appointments must have ended
+    pub end_hour: u32,
+}
+
+/// A patient queued for the next suitable slot with a provider
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WaitlistEntry {
+    pub patient: Address,
+    pub appointment_type: AppointmentType,
+    /// Earliest time the patient can be seen
+    pub desired_after: u64,
+    pub joined_at: u64,
 }
 
 /// Appointment history entry for tracking changes
@@ -105,6 +192,29 @@ pub fn increment_appointment_counter(env: &Env) -> u64 {
 /// Stores an appointment record
 pub fn set_appointment(env: &Env, appointment: &Appointment) {
     let key = (APPT_RECORD, appointment.id);
+    match env.storage().persistent().get::<_, Appointment>(&key) {
+        None => {
+            add_provider_appointment_id(env, &appointment.provider, appointment.id);
+            add_provider_day_id(
+                env,
+                &appointment.provider,
+                appointment.scheduled_at,
+                appointment.id,
+            );
+            index_reminder(env, appointment);
+        }
+        Some(previous) if previous.scheduled_at != appointment.scheduled_at => {
+            remove_provider_day_id(env, &previous.provider, previous.scheduled_at, previous.id);
+            add_provider_day_id(
+                env,
+                &appointment.provider,
+                appointment.scheduled_at,
+                appointment.id,
+            );
+            index_reminder(env, appointment);
+        }
+        Some(_) => {}
+    }
     env.storage().persistent().set(&key, appointment);
     extend_ttl_appointment_key(env, &key);
 
@@ -119,6 +229,295 @@ pub fn set_appointment(env: &Env, appointment: &Appointment) {
     extend_ttl_appointment_provider_key(env, &provider_key);
 }
 
+/// Appends an appointment id to the provider's appointment list
+fn add_provider_appointment_id(env: &Env, provider: &Address, appointment_id: u64) {
+    let key = (APPT_PROVIDER_LIST, provider.clone());
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    ids.push_back(appointment_id);
+    env.storage().persistent().set(&key, &ids);
+    extend_ttl_provider_list_key(env, &key);
+}
+
+/// Gets the ids of every appointment booked with a provider, oldest first
+pub fn get_provider_appointment_ids(env: &Env, provider: &Address) -> Vec<u64> {
+    let key = (APPT_PROVIDER_LIST, provider.clone());
+    env.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env))
+}
+
+fn provider_day_key(provider: &Address, day: u64) -> (Symbol, Address, u64) {
+    (APPT_PROVIDER_DAY, provider.clone(), day)
+}
+
+/// Gets the ids of a provider's appointments starting on `day` (days since
+/// the Unix epoch, UTC)
+fn get_provider_day_ids(env: &Env, provider: &Address, day: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&provider_day_key(provider, day))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Indexes an appointment under the provider's bucket for the day it starts
+fn add_provider_day_id(env: &Env, provider: &Address, scheduled_at: u64, appointment_id: u64) {
+    let key = provider_day_key(provider, scheduled_at / SECONDS_PER_DAY);
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    ids.push_back(appointment_id);
+    env.storage().persistent().set(&key, &ids);
+    extend_ttl_appointment_provider_key(env, &key);
+}
+
+/// Drops an appointment from the provider's bucket for the day it started
+fn remove_provider_day_id(env: &Env, provider: &Address, scheduled_at: u64, appointment_id: u64) {
+    let key = provider_day_key(provider, scheduled_at / SECONDS_PER_DAY);
+    let ids = get_provider_day_ids(env, provider, scheduled_at / SECONDS_PER_DAY);
+    if let Some(index) = ids.first_index_of(appointment_id) {
+        let mut ids = ids;
+        ids.remove(index);
+        if ids.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &ids);
+        }
+    }
+}
+
+/// Day (since the Unix epoch, UTC) on which an appointment's own reminder
+/// lead time opens, if it set one.
+fn reminder_due_day(appointment: &Appointment) -> Option<u64> {
+    appointment
+        .reminder_lead_seconds
+        .map(|lead| appointment.scheduled_at.saturating_sub(lead) / SECONDS_PER_DAY)
+}
+
+/// Queues an appointment for its reminder. One with its own lead time goes
+/// under the day that lead time opens, pulling the sweep cursor back if that
+/// day is earlier; any other goes under the day it is scheduled for.
+fn index_reminder(env: &Env, appointment: &Appointment) {
+    let (key, due_day) = match reminder_due_day(appointment) {
+        Some(day) => ((APPT_REMINDER_DUE, day), Some(day)),
+        None => (
+            (
+                APPT_REMINDER_DAY,
+                appointment.scheduled_at / SECONDS_PER_DAY,
+            ),
+            None,
+        ),
+    };
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    ids.push_back(appointment.id);
+    env.storage().persistent().set(&key, &ids);
+    extend_ttl_appointment_key(env, &key);
+
+    let Some(day) = due_day else {
+        return;
+    };
+    let cursor: Option<u64> = env.storage().instance().get(&APPT_REMINDER_CURSOR);
+    if cursor.map_or(true, |c| day < c) {
+        env.storage().instance().set(&APPT_REMINDER_CURSOR, &day);
+    }
+}
+
+/// Checks whether `[start, start + duration)` overlaps any of the provider's
+/// active appointments. Cancelled and no-show appointments free their slot,
+/// and `exclude` skips the appointment being moved, if any.
+///
+/// Only the per-day buckets that an overlapping appointment could start in
+/// are read: the days covered by the slot, plus the day before when an
+/// appointment of `MAX_APPOINTMENT_MINUTES` could run over midnight into it.
+pub fn has_provider_conflict(
+    env: &Env,
+    provider: &Address,
+    start: u64,
+    duration_minutes: u32,
+    exclude: Option<u64>,
+) -> bool {
+    let end = start.saturating_add(u64::from(duration_minutes) * 60);
+    let first_day = start.saturating_sub(u64::from(MAX_APPOINTMENT_MINUTES) * 60) / SECONDS_PER_DAY;
+    let last_day = end / SECONDS_PER_DAY;
+    for day in first_day..=last_day {
+        for id in get_provider_day_ids(env, provider, day).iter() {
+            if Some(id) == exclude {
+                continue;
+            }
+            if let Some(existing) = get_appointment(env, id) {
+                if existing.status == AppointmentStatus::Cancelled
+                    || existing.status == AppointmentStatus::NoShow
+                {
+                    continue;
+                }
+                let existing_end = existing
+                    .scheduled_at
+                    .saturating_add(u64::from(existing.duration_minutes) * 60);
+                if start < existing_end && existing.scheduled_at < end {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Stores a provider's availability
+pub fn set_provider_availability(
+    env: &Env,
+    provider: &Address,
+    availability: &ProviderAvailability,
+) {
+    let key = (APPT_AVAILABILITY, provider.clone());
+    env.storage().persistent().set(&key, availability);
+    extend_ttl_provider_list_key(env, &key);
+}
+
+/// Gets a provider's availability; `None` means always available
+pub fn get_provider_availability(env: &Env, provider: &Address) -> Option<ProviderAvailability> {
+    let key = (APPT_AVAILABILITY, provider.clone());
+    env.storage().persistent().get(&key)
+}
+
+/// Checks that `[start, start + duration)` falls on an available day and
+/// within the provider's hours. Providers without availability set accept
+/// any slot.
+pub fn is_within_availability(
+    env: &Env,
+    provider: &Address,
+    start: u64,
+    duration_minutes: u32,
+) -> bool {
+    let availability = match get_provider_availability(env, provider) {
+        Some(a) => a,
+        None => return true,
+    };
+
+    if !rbac::time_restriction_allows(&TimeRestriction::DaysOfWeek(availability.day_mask), start) {
+        return false;
+    }
+
+    let start_of_day = start % 86400;
+    let end_of_slot = start_of_day.saturating_add(u64::from(duration_minutes) * 60);
+    start_of_day >= u64::from(availability.start_hour) * 3600
+        && end_of_slot <= u64::from(availability.end_hour) * 3600
+}
+
+/// Whether an appointment may run for `duration_minutes`: at least a minute
+/// and no longer than [`MAX_APPOINTMENT_MINUTES`].
+pub fn is_valid_duration(duration_minutes: u32) -> bool {
+    duration_minutes > 0 && duration_minutes <= MAX_APPOINTMENT_MINUTES
+}
+
+/// Whether the provider can take `[start, start + duration)`: the slot is
+/// within their hours and overlaps none of their active appointments.
+pub fn is_slot_available(
+    env: &Env,
+    provider: &Address,
+    start: u64,
+    duration_minutes: u32,
+    exclude: Option<u64>,
+) -> bool {
+    is_within_availability(env, provider, start, duration_minutes)
+        && !has_provider_conflict(env, provider, start, duration_minutes, exclude)
+}
+
+/// Gets the late-cancellation window in seconds (0 = disabled)
+pub fn get_cancellation_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&APPT_CANCEL_WINDOW)
+        .unwrap_or(0)
+}
+
+/// Sets the late-cancellation window in seconds
+pub fn set_cancellation_window(env: &Env, seconds: u64) {
+    env.storage().instance().set(&APPT_CANCEL_WINDOW, &seconds);
+}
+
+/// Gets how many times one appointment may be rescheduled (0 = unlimited)
+pub fn get_max_reschedules(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&APPT_MAX_RESCHEDULES)
+        .unwrap_or(0)
+}
+
+/// Sets how many times one appointment may be rescheduled
+pub fn set_max_reschedules(env: &Env, max: u32) {
+    env.storage().instance().set(&APPT_MAX_RESCHEDULES, &max);
+}
+
+/// Gets how many appointments a patient has missed since the last reset
+pub fn get_patient_no_shows(env: &Env, patient: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(APPT_NO_SHOWS, patient.clone()))
+        .unwrap_or(0)
+}
+
+/// Counts one more missed appointment against the patient
+pub fn record_patient_no_show(env: &Env, patient: &Address) {
+    let key = (APPT_NO_SHOWS, patient.clone());
+    let count = get_patient_no_shows(env, patient).saturating_add(1);
+    env.storage().persistent().set(&key, &count);
+    extend_ttl_patient_key(env, &key);
+}
+
+/// Clears a patient's no-show count
+pub fn reset_patient_no_shows(env: &Env, patient: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&(APPT_NO_SHOWS, patient.clone()));
+}
+
+/// Gets the no-show count above which self-scheduling is blocked (0 = disabled)
+pub fn get_no_show_threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&APPT_NO_SHOW_LIMIT)
+        .unwrap_or(0)
+}
+
+/// Sets the no-show count above which self-scheduling is blocked
+pub fn set_no_show_threshold(env: &Env, threshold: u32) {
+    env.storage()
+        .instance()
+        .set(&APPT_NO_SHOW_LIMIT, &threshold);
+}
+
+/// Whether bookings for `patient` must be made by the provider
+pub fn schedule_requires_approval(env: &Env, patient: &Address) -> bool {
+    let threshold = get_no_show_threshold(env);
+    threshold > 0 && get_patient_no_shows(env, patient) > threshold
+}
+
+/// Gets a provider's waitlist in the order patients joined
+pub fn get_waitlist(env: &Env, provider: &Address) -> Vec<WaitlistEntry> {
+    let key = (APPT_WAITLIST, provider.clone());
+    env.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Replaces a provider's waitlist
+pub fn set_waitlist(env: &Env, provider: &Address, waitlist: &Vec<WaitlistEntry>) {
+    let key = (APPT_WAITLIST, provider.clone());
+    env.storage().persistent().set(&key, waitlist);
+    extend_ttl_provider_list_key(env, &key);
+}
+
 /// Retrieves an appointment by ID
 pub fn get_appointment(env: &Env, appointment_id: u64) -> Option<Appointment> {
     let key = (APPT_RECORD, appointment_id);
@@ -145,15 +544,40 @@ pub fn get_patient_appointments(env: &Env, patient: &Address) -> Vec<Appointment
 /// Gets all appointments for a provider
 pub fn get_provider_appointments(env: &Env, provider: &Address) -> Vec<Appointment> {
     let mut appointments = Vec::new(env);
-    let counter: u64 = env.storage().instance().get(&APPT_CTR).unwrap_or(0);
-    let start_id = if counter > 100 { counter - 100 } else { 1 };
+    for id in get_provider_appointment_ids(env, provider).iter() {
+        if let Some(appointment) = get_appointment(env, id) {
+            appointments.push_back(appointment);
+        }
+    }
+    appointments
+}
 
-    for id in start_id..=counter {
-        let key = (APPT_RECORD, id);
-        if let Some(appointment) = env.storage().persistent().get::<_, Appointment>(&key) {
-            if appointment.provider == *provider {
-                appointments.push_back(appointment);
-            }
+/// Gets a provider's appointments currently in `status`
+pub fn get_provider_appointments_by_status(
+    env: &Env,
+    provider: &Address,
+    status: &AppointmentStatus,
+) -> Vec<Appointment> {
+    let mut appointments = Vec::new(env);
+    for appointment in get_provider_appointments(env, provider).iter() {
+        if appointment.status == *status {
+            appointments.push_back(appointment);
+        }
+    }
+    appointments
+}
+
+/// Gets a provider's appointments scheduled in `[start, end)`
+pub fn get_provider_appointments_in_range(
+    env: &Env,
+    provider: &Address,
+    start: u64,
+    end: u64,
+) -> Vec<Appointment> {
+    let mut appointments = Vec::new(env);
+    for appointment in get_provider_appointments(env, provider).iter() {
+        if appointment.scheduled_at >= start && appointment.scheduled_at < end {
+            appointments.push_back(appointment);
         }
     }
     appointments
@@ -216,38 +640,138 @@ pub fn get_appointment_history(env: &Env, appointment_id: u64) -> Vec<Appointmen
         .unwrap_or(Vec::new(env))
 }
 
-/// Gets appointments that need reminders (scheduled within reminder window)
+/// Whether an appointment is still ahead and active, so may be reminded.
+fn is_upcoming(appointment: &Appointment, now: u64) -> bool {
+    appointment.scheduled_at > now
+        && (appointment.status == AppointmentStatus::Scheduled
+            || appointment.status == AppointmentStatus::Confirmed)
+}
+
+/// Whether an upcoming appointment has not been reminded for `lead` yet.
+fn awaits_reminder(appointment: &Appointment, lead: u64, now: u64) -> bool {
+    is_upcoming(appointment, now) && !appointment.reminders_sent.contains(lead)
+}
+
+/// Takes the appointments whose own `reminder_lead_seconds` has opened from
+/// the due-day queue.
+///
+/// Buckets are drained from a cursor up to today, at most
+/// `REMINDER_DAYS_PER_SWEEP` per call, so a lead time of any length is
+/// reached however many appointments were booked since. Entries that were
+/// moved to another day, are no longer active, or are returned here leave
+/// the queue; only today's not-yet-due entries stay.
+pub fn take_due_lead_reminders(env: &Env) -> Vec<Appointment> {
+    let mut due = Vec::new(env);
+    let now = env.ledger().timestamp();
+    let today = now / SECONDS_PER_DAY;
+    let Some(cursor) = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&APPT_REMINDER_CURSOR)
+    else {
+        return due;
+    };
+    if cursor > today {
+        return due;
+    }
+    let last = today.min(cursor.saturating_add(REMINDER_DAYS_PER_SWEEP - 1));
+
+    for day in cursor..=last {
+        let key = (APPT_REMINDER_DUE, day);
+        let Some(ids) = env.storage().persistent().get::<_, Vec<u64>>(&key) else {
+            continue;
+        };
+        let mut kept = Vec::new(env);
+        for id in ids.iter() {
+            let Some(appointment) = get_appointment(env, id) else {
+                continue;
+            };
+            let (Some(lead), Some(due_day)) = (
+                appointment.reminder_lead_seconds,
+                reminder_due_day(&appointment),
+            ) else {
+                continue;
+            };
+            if due_day != day || !awaits_reminder(&appointment, lead, now) {
+                continue;
+            }
+            if appointment.scheduled_at.saturating_sub(lead) > now {
+                kept.push_back(id);
+            } else {
+                due.push_back(appointment);
+            }
+        }
+        if kept.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else if kept.len() != ids.len() {
+            env.storage().persistent().set(&key, &kept);
+        }
+    }
+
+    let next = if last >= today { today } else { last + 1 };
+    if next != cursor {
+        env.storage().instance().set(&APPT_REMINDER_CURSOR, &next);
+    }
+    due
+}
+
+/// Gets appointments without their own lead time that are scheduled within
+/// `reminder_window_seconds` and not yet reminded for it.
+///
+/// Only the scheduled-day buckets from today through the end of the window
+/// are read, at most `REMINDER_DAYS_PER_SWEEP` of them, so windows longer
+/// than that are cut short. Entries that were moved, have passed, or are no
+/// longer active are dropped from the buckets as they are found. Appointments
+/// with `reminder_lead_seconds` are served by `take_due_lead_reminders`.
 pub fn get_appointments_needing_reminders(
     env: &Env,
     reminder_window_seconds: u64,
 ) -> Vec<Appointment> {
     let mut appointments = Vec::new(env);
-    let current_time = env.ledger().timestamp();
-    let reminder_threshold = current_time + reminder_window_seconds;
-    let counter: u64 = env.storage().instance().get(&APPT_CTR).unwrap_or(0);
-    let start_id = if counter > 100 { counter - 100 } else { 1 };
+    let now = env.ledger().timestamp();
+    let today = now / SECONDS_PER_DAY;
+    let horizon = now.saturating_add(reminder_window_seconds) / SECONDS_PER_DAY;
+    let last = horizon.min(today.saturating_add(REMINDER_DAYS_PER_SWEEP - 1));
 
-    for id in start_id..=counter {
-        let key = (APPT_RECORD, id);
-        if let Some(appointment) = env.storage().persistent().get::<_, Appointment>(&key) {
-            if appointment.scheduled_at <= reminder_threshold
-                && appointment.scheduled_at > current_time
-                && !appointment.reminder_sent
-                && (appointment.status == AppointmentStatus::Scheduled
-                    || appointment.status == AppointmentStatus::Confirmed)
+    for day in today..=last {
+        let key = (APPT_REMINDER_DAY, day);
+        let Some(ids) = env.storage().persistent().get::<_, Vec<u64>>(&key) else {
+            continue;
+        };
+        let mut kept = Vec::new(env);
+        for id in ids.iter() {
+            let Some(appointment) = get_appointment(env, id) else {
+                continue;
+            };
+            if appointment.reminder_lead_seconds.is_some()
+                || appointment.scheduled_at / SECONDS_PER_DAY != day
+                || !is_upcoming(&appointment, now)
+            {
+                continue;
+            }
+            kept.push_back(id);
+            if appointment.scheduled_at <= now.saturating_add(reminder_window_seconds)
+                && !appointment.reminders_sent.contains(reminder_window_seconds)
             {
                 appointments.push_back(appointment);
             }
         }
+        if kept.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else if kept.len() != ids.len() {
+            env.storage().persistent().set(&key, &kept);
+        }
     }
     appointments
 }
 
-/// Marks an appointment's reminder as sent
-pub fn mark_reminder_sent(env: &Env, appointment_id: u64) -> Option<Appointment> {
+/// Marks the reminder for a lead-time bucket as sent for an appointment
+pub fn mark_reminder_sent(env: &Env, appointment_id: u64, bucket: u64) -> Option<Appointment> {
     let key = (APPT_RECORD, appointment_id);
     if let Some(mut appointment) = env.storage().persistent().get::<_, Appointment>(&key) {
-        appointment.reminder_sent = true;
+        if !appointment.reminders_sent.contains(bucket) {
+            appointment.reminders_sent.push_back(bucket);
+        }
         appointment.updated_at = env.ledger().timestamp();
         env.storage().persistent().set(&key, &appointment);
         extend_ttl_appointment_key(env, &key);
@@ -256,3 +780,82 @@ pub fn mark_reminder_sent(env: &Env, appointment_id: u64) -> Option<Appointment>
         None
     }
 }
+
+/// Moves stale `Scheduled` appointments (started more than
+/// `NO_SHOW_GRACE_SECONDS` ago) to `NoShow`, recording a history entry for
+/// each. Work is bounded to `EXPIRY_BATCH` ids per call.
+///
+/// A low-water cursor skips the prefix of ids that are already past the grace
+/// period. Scanning continues from where the previous call stopped and wraps
+/// back to the cursor after reaching the newest id, so a far-future
+/// appointment holding the cursor back does not hide later ids.
+pub fn expire_past_appointments(env: &Env, actor: &Address) -> u32 {
+    let counter: u64 = env.storage().instance().get(&APPT_CTR).unwrap_or(0);
+    let cursor: u64 = env
+        .storage()
+        .instance()
+        .get(&APPT_EXPIRY_CURSOR)
+        .unwrap_or(1);
+    let mut scan: u64 = env
+        .storage()
+        .instance()
+        .get(&APPT_EXPIRY_SCAN)
+        .unwrap_or(cursor);
+    if scan < cursor || scan > counter {
+        scan = cursor;
+    }
+    let now = env.ledger().timestamp();
+    let last = counter.min(scan.saturating_add(EXPIRY_BATCH - 1));
+
+    let mut expired = 0u32;
+    let mut next_cursor = cursor;
+    let mut advancing = scan == cursor;
+    for id in scan..=last {
+        let stale = match get_appointment(env, id) {
+            Some(mut appointment) => {
+                let stale = appointment
+                    .scheduled_at
+                    .saturating_add(NO_SHOW_GRACE_SECONDS)
+                    <= now;
+                if stale && appointment.status == AppointmentStatus::Scheduled {
+                    appointment.status = AppointmentStatus::NoShow;
+                    appointment.updated_at = now;
+                    set_appointment(env, &appointment);
+                    crate::provider::appointment_no_show(env, &appointment.provider);
+                    record_patient_no_show(env, &appointment.patient);
+                    add_history_entry(
+                        env,
+                        &AppointmentHistoryEntry {
+                            appointment_id: id,
+                            action: String::from_str(env, "NO_SHOW"),
+                            actor: actor.clone(),
+                            timestamp: now,
+                            previous_status: AppointmentStatus::Scheduled,
+                            new_status: AppointmentStatus::NoShow,
+                            notes: None,
+                        },
+                    );
+                    expired += 1;
+                }
+                stale
+            }
+            None => true,
+        };
+        if advancing && stale {
+            next_cursor = id + 1;
+        } else {
+            advancing = false;
+        }
+    }
+
+    let next_scan = if last >= counter {
+        next_cursor
+    } else {
+        last + 1
+    };
+    env.storage()
+        .instance()
+        .set(&APPT_EXPIRY_CURSOR, &next_cursor);
+    env.storage().instance().set(&APPT_EXPIRY_SCAN, &next_scan);
+    expired
+}
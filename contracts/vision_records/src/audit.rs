@@ -93,38 +93,45 @@ impl AuditManager {
 pub const AUDIT_CTR: Symbol = symbol_short!("AUD_CTR");
 const AUDIT_ENTRY: Symbol = symbol_short!("AUD_ENT");
 const AUDIT_RECORD: Symbol = symbol_short!("AUD_REC");
+const AUDIT_RECORD_IDS: Symbol = symbol_short!("AUD_RIDS");
+const AUDIT_RECORD_COUNT: Symbol = symbol_short!("AUD_RCNT");
+const AUDIT_RECORD_CUTOVER: Symbol = symbol_short!("AUD_RCUT");
 const AUDIT_USER: Symbol = symbol_short!("AUD_USR");
 const AUDIT_PATIENT: Symbol = symbol_short!("AUD_PAT");
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
+/// Maximum number of entry ids held in one page of a record's audit index.
+pub const AUDIT_RECORD_PAGE_SIZE: u32 = 50;
 
 /// Extends the time-to-live (TTL) for audit entry storage keys.
 fn extend_ttl_audit_key(env: &Env, key: &(Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
-/// Extends the time-to-live (TTL) for audit by record keys.
-fn extend_ttl_audit_record_key(env: &Env, key: &(Symbol, u64, u64)) {
+/// Extends the time-to-live (TTL) for pages of a record's audit index.
+fn extend_ttl_audit_page_key(env: &Env, key: &(Symbol, u64, u32)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Extends the time-to-live (TTL) for audit by user keys.
 fn extend_ttl_audit_user_key(env: &Env, key: &(Symbol, Address, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Extends the time-to-live (TTL) for audit by patient keys.
 fn extend_ttl_audit_patient_key(env: &Env, key: &(Symbol, Address, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 // ── Types ─────────────────────────────────────────────────────
@@ -188,11 +195,9 @@ pub fn add_audit_entry(env: &Env, entry: &AuditEntry) {
     env.storage().persistent().set(&key, entry);
     extend_ttl_audit_key(env, &key);
 
-    // Index by record ID for quick lookup
+    // Index by record ID in fixed-size pages of ordered entry ids
     if let Some(record_id) = entry.record_id {
-        let record_key = (AUDIT_RECORD, record_id, entry.id);
-        env.storage().persistent().set(&record_key, &true);
-        extend_ttl_audit_record_key(env, &record_key);
+        add_record_audit_id(env, record_id, entry.id);
     }
 
     // Index by actor (user) for quick lookup
@@ -212,7 +217,53 @@ pub fn get_audit_entry(env: &Env, entry_id: u64) -> Option<AuditEntry> {
     env.storage().persistent().get(&key)
 }
 
-/// Gets all audit entries for a specific record
+/// Appends an entry id to the last page of the record's audit index,
+/// opening a new page every `AUDIT_RECORD_PAGE_SIZE` ids.
+///
+/// Entries written before the paged index existed were indexed with one
+/// `AUD_REC` marker each instead. The id of the first paged entry is kept as
+/// the cutover, and readers fall back to the markers below it.
+fn add_record_audit_id(env: &Env, record_id: u64, entry_id: u64) {
+    if !env.storage().instance().has(&AUDIT_RECORD_CUTOVER) {
+        env.storage()
+            .instance()
+            .set(&AUDIT_RECORD_CUTOVER, &entry_id);
+    }
+
+    let count_key = (AUDIT_RECORD_COUNT, record_id);
+    let count: u64 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let page = (count / u64::from(AUDIT_RECORD_PAGE_SIZE)) as u32;
+    let mut ids = get_record_audit_page(env, record_id, page);
+    ids.push_back(entry_id);
+    let page_key = (AUDIT_RECORD_IDS, record_id, page);
+    env.storage().persistent().set(&page_key, &ids);
+    extend_ttl_audit_page_key(env, &page_key);
+    env.storage().persistent().set(&count_key, &(count + 1));
+    extend_ttl_audit_key(env, &count_key);
+}
+
+/// Returns one page of the entry ids audited against a record since the
+/// paged index cutover, oldest first.
+pub fn get_record_audit_page(env: &Env, record_id: u64, page: u32) -> soroban_sdk::Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&(AUDIT_RECORD_IDS, record_id, page))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+/// Returns how many pages a record's audit index spans.
+pub fn get_record_audit_page_count(env: &Env, record_id: u64) -> u32 {
+    let count: u64 = env
+        .storage()
+        .persistent()
+        .get(&(AUDIT_RECORD_COUNT, record_id))
+        .unwrap_or(0);
+    count.div_ceil(u64::from(AUDIT_RECORD_PAGE_SIZE)) as u32
+}
+
+/// Gets all audit entries for a specific record among the most recent 1000
+/// entry ids. Ids below the paged index cutover are found through their
+/// `AUD_REC` markers; later ones through the record's pages.
 pub fn get_record_audit_log(env: &Env, record_id: u64) -> soroban_sdk::Vec<AuditEntry> {
     let mut entries = soroban_sdk::Vec::new(env);
     let counter: u64 = env.storage().instance().get(&AUDIT_CTR).unwrap_or(0);
@@ -220,8 +271,13 @@ pub fn get_record_audit_log(env: &Env, record_id: u64) -> soroban_sdk::Vec<Audit
         return entries;
     }
     let start_id = if counter > 1000 { counter - 1000 } else { 1 };
+    let cutover: u64 = env
+        .storage()
+        .instance()
+        .get(&AUDIT_RECORD_CUTOVER)
+        .unwrap_or(counter + 1);
 
-    for id in start_id..=counter {
+    for id in start_id..cutover.min(counter + 1) {
         let record_key = (AUDIT_RECORD, record_id, id);
         if env
             .storage()
@@ -234,6 +290,46 @@ pub fn get_record_audit_log(env: &Env, record_id: u64) -> soroban_sdk::Vec<Audit
             }
         }
     }
+
+    // Walk back to the first page reaching into the window, then forwards.
+    let pages = get_record_audit_page_count(env, record_id);
+    let mut first = pages;
+    while first > 0 {
+        first -= 1;
+        let ids = get_record_audit_page(env, record_id, first);
+        if ids.first().is_some_and(|id| id < start_id) {
+            break;
+        }
+    }
+    for page in first..pages {
+        for id in get_record_audit_page(env, record_id, page).iter() {
+            if id >= start_id {
+                if let Some(entry) = get_audit_entry(env, id) {
+                    entries.push_back(entry);
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Gets the audit entries with the given action from one page of a record's
+/// audit index, e.g. the reads of record 42 among its first 50 entries.
+/// Only entries written since the paged index cutover are covered.
+pub fn get_record_audit_by_action(
+    env: &Env,
+    record_id: u64,
+    action: AccessAction,
+    page: u32,
+) -> soroban_sdk::Vec<AuditEntry> {
+    let mut entries = soroban_sdk::Vec::new(env);
+    for id in get_record_audit_page(env, record_id, page).iter() {
+        if let Some(entry) = get_audit_entry(env, id) {
+            if entry.action == action {
+                entries.push_back(entry);
+            }
+        }
+    }
     entries
 }
 
@@ -4,23 +4,60 @@ use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec}
 pub const EMRG_CTR: Symbol = symbol_short!("EMRG_CTR");
 const EMRG_ACCESS: Symbol = symbol_short!("EMRG_ACC");
 const EMRG_AUDIT: Symbol = symbol_short!("EMRG_AUD");
-const EMRG_PATIENT: Symbol = symbol_short!("EMRG_PAT");
+const EMRG_PAT_IDS: Symbol = symbol_short!("EMRG_PIDS");
+const EMRG_PAT_ACTIVE: Symbol = symbol_short!("EMRG_PACT");
+const EMRG_EXPIRY: Symbol = symbol_short!("EMRG_EXP");
+const EMRG_EXPIRY_CURSOR: Symbol = symbol_short!("EMRG_ECUR");
+const EMRG_ACTIVE_COUNT: Symbol = symbol_short!("EMRG_ACNT");
+const EMRG_MAX_DUR: Symbol = symbol_short!("EMRG_MAX");
+const BG_CTR: Symbol = symbol_short!("BG_CTR");
+const BG_EVENT: Symbol = symbol_short!("BG_EVT");
+const BG_PENDING: Symbol = symbol_short!("BG_PEND");
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
+/// Longest an emergency grant may last: 24 hours.
+pub const MAX_EMERGENCY_DURATION: u64 = 86400;
+
+/// Most patients one mass-casualty grant may cover; larger incidents are
+/// split across several calls.
+pub const MAX_MASS_CASUALTY_PATIENTS: u32 = 8;
+
+/// Width of the expiry buckets active grants are filed under: one hour.
+pub const EXPIRY_BUCKET_SECONDS: u64 = 3600;
+
+/// Most buckets plus grants one expiry sweep examines. Expiring a grant
+/// writes two entries, so a full batch stays within the per-invocation
+/// write limit.
+pub const MAX_EXPIRY_BATCH: u32 = 20;
+
+/// Most grant ids one `reindex_emergency_accesses` call may process.
+pub const MAX_REINDEX_PAGE: u32 = 10;
+
+/// Default cap for `Unconscious` grants: 12 hours. Other conditions default
+/// to `MAX_EMERGENCY_DURATION`.
+pub const DEFAULT_UNCONSCIOUS_DURATION: u64 = 43200;
 
 /// Extends the time-to-live (TTL) for emergency access storage keys.
 fn extend_ttl_emergency_key(env: &Env, key: &(Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+/// Extends the time-to-live (TTL) for the break-glass review queue.
+fn extend_ttl_break_glass_pending(env: &Env) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(&BG_PENDING, policy.threshold, policy.extend_to);
 }
 
-/// Extends the time-to-live (TTL) for emergency access by patient keys.
-fn extend_ttl_emergency_patient_key(env: &Env, key: &(Symbol, Address, u64)) {
+/// Extends the time-to-live (TTL) for the per-patient emergency id list.
+fn extend_ttl_emergency_ids_key(env: &Env, key: &(Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 // ── Types ─────────────────────────────────────────────────────
@@ -58,6 +95,8 @@ pub struct EmergencyAccess {
     pub expires_at: u64,
     pub status: EmergencyStatus,
     pub notified_contacts: Vec<Address>,
+    /// Set for life-threatening emergencies to bypass consent-required policies
+    pub override_consent: bool,
 }
 
 /// Immutable audit entry — written once, never deleted
@@ -68,10 +107,46 @@ pub struct EmergencyAuditEntry {
     pub actor: Address,
     pub action: String, // e.g. "GRANTED", "REVOKED", "ACCESSED", "NOTIFIED"
     pub timestamp: u64,
+    /// Record viewed, set on `ACCESSED` entries
+    pub record_id: Option<u64>,
+}
+
+/// A break-glass read: a verified provider read a record without any grant
+/// and the access awaits admin review. `approved` is set once reviewed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakGlassEvent {
+    pub id: u64,
+    pub provider: Address,
+    pub patient: Address,
+    pub record_id: u64,
+    pub justification: String,
+    pub accessed_at: u64,
+    pub approved: Option<bool>,
+    pub reviewed_by: Option<Address>,
+    pub reviewed_at: Option<u64>,
 }
 
 // ── Storage Functions ────────────────────────────────────────
 
+/// Longest grant allowed for `condition`, using the configured cap if set.
+pub fn get_max_duration(env: &Env, condition: &EmergencyCondition) -> u64 {
+    env.storage()
+        .instance()
+        .get(&(EMRG_MAX_DUR, condition.clone()))
+        .unwrap_or(match condition {
+            EmergencyCondition::Unconscious => DEFAULT_UNCONSCIOUS_DURATION,
+            _ => MAX_EMERGENCY_DURATION,
+        })
+}
+
+/// Sets the cap on grant duration for `condition`.
+pub fn set_max_duration(env: &Env, condition: &EmergencyCondition, seconds: u64) {
+    env.storage()
+        .instance()
+        .set(&(EMRG_MAX_DUR, condition.clone()), &seconds);
+}
+
 /// Increments and returns the next emergency access ID
 pub fn increment_emergency_counter(env: &Env) -> u64 {
     let current: u64 = env.storage().instance().get(&EMRG_CTR).unwrap_or(0);
@@ -86,10 +161,58 @@ pub fn set_emergency_access(env: &Env, access: &EmergencyAccess) {
     env.storage().persistent().set(&key, access);
     extend_ttl_emergency_key(env, &key);
 
-    // Also index by patient for quick lookup
-    let patient_key = (EMRG_PATIENT, access.patient.clone(), access.id);
-    env.storage().persistent().set(&patient_key, &true);
-    extend_ttl_emergency_patient_key(env, &patient_key);
+    // Also index live grants by patient for quick lookup
+    if access.status == EmergencyStatus::Active {
+        let patient_key = (EMRG_PAT_ACTIVE, access.patient.clone());
+        let mut patient_active = get_active_emergency_ids(env, &access.patient);
+        if !patient_active.contains(access.id) {
+            patient_active.push_back(access.id);
+            env.storage()
+                .persistent()
+                .set(&patient_key, &patient_active);
+        }
+        extend_ttl_emergency_ids_key(env, &patient_key);
+
+        index_expiry(env, access);
+    } else {
+        unindex_access(env, access);
+    }
+
+    let ids_key = (EMRG_PAT_IDS, access.patient.clone());
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&ids_key)
+        .unwrap_or(Vec::new(env));
+    if !ids.contains(access.id) {
+        ids.push_back(access.id);
+        env.storage().persistent().set(&ids_key, &ids);
+    }
+    extend_ttl_emergency_ids_key(env, &ids_key);
+}
+
+/// Ids of every emergency access ever granted for a patient, oldest first
+pub fn get_patient_emergency_ids(env: &Env, patient: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&(EMRG_PAT_IDS, patient.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Audit entries from all of a patient's emergency accesses, oldest first
+pub fn get_patient_audit_entries(env: &Env, patient: &Address) -> Vec<EmergencyAuditEntry> {
+    let mut merged: alloc::vec::Vec<EmergencyAuditEntry> = alloc::vec::Vec::new();
+    for id in get_patient_emergency_ids(env, patient).iter() {
+        merged.extend(get_audit_entries(env, id).iter());
+    }
+    // Stable, so entries with equal timestamps keep their per-access order.
+    merged.sort_by_key(|entry| entry.timestamp);
+
+    let mut entries = Vec::new(env);
+    for entry in merged {
+        entries.push_back(entry);
+    }
+    entries
 }
 
 /// Retrieves an emergency access grant by ID
@@ -98,22 +221,84 @@ pub fn get_emergency_access(env: &Env, access_id: u64) -> Option<EmergencyAccess
     env.storage().persistent().get(&key)
 }
 
+/// Ids of active grants expiring within `bucket`, the hour starting at
+/// `bucket * EXPIRY_BUCKET_SECONDS`
+pub fn get_expiring_ids(env: &Env, bucket: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&(EMRG_EXPIRY, bucket))
+        .unwrap_or(Vec::new(env))
+}
+
+/// How many grants are filed in the expiry buckets
+pub fn get_active_grant_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&EMRG_ACTIVE_COUNT)
+        .unwrap_or(0)
+}
+
+fn set_active_grant_count(env: &Env, count: u32) {
+    env.storage().instance().set(&EMRG_ACTIVE_COUNT, &count);
+}
+
+fn set_expiring_ids(env: &Env, bucket: u64, ids: &Vec<u64>) {
+    let key = (EMRG_EXPIRY, bucket);
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, ids);
+        extend_ttl_emergency_key(env, &key);
+    }
+}
+
+/// Files an active grant under its expiry bucket. The sweep cursor is moved
+/// back if the bucket lies behind it, e.g. when older grants are reindexed.
+fn index_expiry(env: &Env, access: &EmergencyAccess) {
+    let bucket = access.expires_at / EXPIRY_BUCKET_SECONDS;
+    let mut ids = get_expiring_ids(env, bucket);
+    if ids.contains(access.id) {
+        return;
+    }
+    ids.push_back(access.id);
+    set_expiring_ids(env, bucket, &ids);
+    set_active_grant_count(env, get_active_grant_count(env).saturating_add(1));
+
+    let cursor: Option<u64> = env.storage().instance().get(&EMRG_EXPIRY_CURSOR);
+    if cursor.map_or(true, |cursor| bucket < cursor) {
+        env.storage().instance().set(&EMRG_EXPIRY_CURSOR, &bucket);
+    }
+}
+
+fn unindex_expiry(env: &Env, access: &EmergencyAccess) {
+    let bucket = access.expires_at / EXPIRY_BUCKET_SECONDS;
+    let mut ids = get_expiring_ids(env, bucket);
+    if let Some(pos) = ids.first_index_of(access.id) {
+        ids.remove(pos);
+        set_expiring_ids(env, bucket, &ids);
+        set_active_grant_count(env, get_active_grant_count(env).saturating_sub(1));
+    }
+}
+
+/// Ids of a patient's grants still marked active, oldest first. Revoked
+/// and swept grants are dropped from this list, so it stays as short as the
+/// patient's live grants.
+pub fn get_active_emergency_ids(env: &Env, patient: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&(EMRG_PAT_ACTIVE, patient.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
 /// Checks if emergency access is currently active for a patient-requester pair
 pub fn has_active_emergency_access(
     env: &Env,
     patient: &Address,
     requester: &Address,
 ) -> Option<EmergencyAccess> {
-    // We need to iterate through potential access IDs
-    // For efficiency, we'll check recent IDs (last 100)
-    let counter: u64 = env.storage().instance().get(&EMRG_CTR).unwrap_or(0);
-    let start_id = if counter > 100 { counter - 100 } else { 1 };
-
-    for id in start_id..=counter {
-        let key = (EMRG_ACCESS, id);
-        if let Some(access) = env.storage().persistent().get::<_, EmergencyAccess>(&key) {
-            if access.patient == *patient
-                && access.requester == *requester
+    for id in get_active_emergency_ids(env, patient).iter() {
+        if let Some(access) = get_emergency_access(env, id) {
+            if access.requester == *requester
                 && access.status == EmergencyStatus::Active
                 && access.expires_at > env.ledger().timestamp()
             {
@@ -131,12 +316,50 @@ pub fn revoke_emergency_access(env: &Env, access_id: u64) -> Option<EmergencyAcc
         access.status = EmergencyStatus::Revoked;
         env.storage().persistent().set(&key, &access);
         extend_ttl_emergency_key(env, &key);
+        unindex_access(env, &access);
         Some(access)
     } else {
         None
     }
 }
 
+/// Drops an inactive grant from the patient and expiry indexes
+fn unindex_access(env: &Env, access: &EmergencyAccess) {
+    unindex_patient_access(env, access);
+    unindex_expiry(env, access);
+}
+
+fn unindex_patient_access(env: &Env, access: &EmergencyAccess) {
+    let key = (EMRG_PAT_ACTIVE, access.patient.clone());
+    let mut ids = get_active_emergency_ids(env, &access.patient);
+    if let Some(pos) = ids.first_index_of(access.id) {
+        ids.remove(pos);
+        if ids.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &ids);
+        }
+    }
+}
+
+/// Re-files up to `limit` (capped at `MAX_REINDEX_PAGE`) grants, starting
+/// at `start_id`, into the patient history, patient active list and
+/// active-grant index, e.g. to pick up grants made before those indexes
+/// existed. Returns how many ids were examined.
+pub fn reindex_emergency_accesses(env: &Env, start_id: u64, limit: u32) -> u32 {
+    let counter: u64 = env.storage().instance().get(&EMRG_CTR).unwrap_or(0);
+    let mut processed = 0u32;
+    let mut id = start_id.max(1);
+    while processed < limit.min(MAX_REINDEX_PAGE) && id <= counter {
+        if let Some(access) = get_emergency_access(env, id) {
+            set_emergency_access(env, &access);
+        }
+        processed += 1;
+        id += 1;
+    }
+    processed
+}
+
 /// Adds an audit entry for emergency access actions
 pub fn add_audit_entry(env: &Env, entry: &EmergencyAuditEntry) {
     let key = (EMRG_AUDIT, entry.access_id);
@@ -172,16 +395,25 @@ pub fn get_audit_entries(env: &Env, access_id: u64) -> Vec<EmergencyAuditEntry>
         .unwrap_or(Vec::new(env))
 }
 
+/// Distinct record IDs viewed under an emergency grant, in first-access order.
+pub fn get_records_accessed(env: &Env, access_id: u64) -> Vec<u64> {
+    let mut ids = Vec::new(env);
+    for entry in get_audit_entries(env, access_id).iter() {
+        if let Some(id) = entry.record_id {
+            if !ids.contains(id) {
+                ids.push_back(id);
+            }
+        }
+    }
+    ids
+}
+
 /// Gets all active emergency accesses for a patient
 pub fn get_patient_emergency_accesses(env: &Env, patient: &Address) -> Vec<EmergencyAccess> {
     let mut accesses = Vec::new(env);
-    let counter: u64 = env.storage().instance().get(&EMRG_CTR).unwrap_or(0);
-    let start_id = if counter > 100 { counter - 100 } else { 1 };
-
-    for id in start_id..=counter {
-        let key = (EMRG_ACCESS, id);
-        if let Some(access) = env.storage().persistent().get::<_, EmergencyAccess>(&key) {
-            if access.patient == *patient && access.status == EmergencyStatus::Active {
+    for id in get_active_emergency_ids(env, patient).iter() {
+        if let Some(access) = get_emergency_access(env, id) {
+            if access.status == EmergencyStatus::Active {
                 accesses.push_back(access);
             }
         }
@@ -189,23 +421,176 @@ pub fn get_patient_emergency_accesses(env: &Env, patient: &Address) -> Vec<Emerg
     accesses
 }
 
-/// Expires emergency accesses that have passed their expiration time
-pub fn expire_emergency_accesses(env: &Env) -> u32 {
+/// Every emergency access granted for a patient, ordered by `granted_at`.
+/// Grants past their expiry are reported as `Expired` even if the keeper
+/// has not swept them yet.
+pub fn get_patient_emergency_history(env: &Env, patient: &Address) -> Vec<EmergencyAccess> {
+    let now = env.ledger().timestamp();
+    let mut history: alloc::vec::Vec<EmergencyAccess> = alloc::vec::Vec::new();
+    for id in get_patient_emergency_ids(env, patient).iter() {
+        if let Some(mut access) = get_emergency_access(env, id) {
+            if access.status == EmergencyStatus::Active && access.expires_at <= now {
+                access.status = EmergencyStatus::Expired;
+            }
+            history.push(access);
+        }
+    }
+    history.sort_by_key(|access| access.granted_at);
+
+    let mut accesses = Vec::new(env);
+    for access in history {
+        accesses.push_back(access);
+    }
+    accesses
+}
+
+/// Expires active grants that have passed their expiration time, walking
+/// the expiry buckets oldest first from the sweep cursor up to the current
+/// hour. Each call examines at most `max_to_process` (capped at
+/// `MAX_EXPIRY_BATCH`) buckets and grants combined; the rest are left for
+/// the next call. Returns how many grants were expired.
+pub fn expire_emergency_accesses(env: &Env, max_to_process: Option<u32>) -> u32 {
+    let Some(mut bucket) = env.storage().instance().get::<_, u64>(&EMRG_EXPIRY_CURSOR) else {
+        return 0;
+    };
+    let now = env.ledger().timestamp();
+    let current = now / EXPIRY_BUCKET_SECONDS;
+    // Nothing is filed, so the empty buckets behind us need no visit.
+    if get_active_grant_count(env) == 0 {
+        env.storage()
+            .instance()
+            .set(&EMRG_EXPIRY_CURSOR, &bucket.max(current));
+        return 0;
+    }
+    let limit = max_to_process.map_or(MAX_EXPIRY_BATCH, |max| max.min(MAX_EXPIRY_BATCH));
+
+    let mut processed = 0u32;
     let mut expired_count = 0u32;
-    let counter: u64 = env.storage().instance().get(&EMRG_CTR).unwrap_or(0);
-    let start_id = if counter > 100 { counter - 100 } else { 1 };
-    let current_time = env.ledger().timestamp();
+    while processed < limit && bucket <= current {
+        let ids = get_expiring_ids(env, bucket);
+        if ids.is_empty() {
+            processed += 1;
+        }
 
-    for id in start_id..=counter {
-        let key = (EMRG_ACCESS, id);
-        if let Some(mut access) = env.storage().persistent().get::<_, EmergencyAccess>(&key) {
-            if access.status == EmergencyStatus::Active && access.expires_at <= current_time {
-                access.status = EmergencyStatus::Expired;
-                env.storage().persistent().set(&key, &access);
-                extend_ttl_emergency_key(env, &key);
-                expired_count += 1;
+        let mut kept = Vec::new(env);
+        let mut dropped = 0u32;
+        for id in ids.iter() {
+            if processed >= limit {
+                kept.push_back(id);
+                continue;
+            }
+            processed += 1;
+
+            let key = (EMRG_ACCESS, id);
+            // Ids whose grant is missing or no longer active are stale and dropped.
+            match env.storage().persistent().get::<_, EmergencyAccess>(&key) {
+                Some(mut access) if access.status == EmergencyStatus::Active => {
+                    if access.expires_at <= now {
+                        access.status = EmergencyStatus::Expired;
+                        env.storage().persistent().set(&key, &access);
+                        extend_ttl_emergency_key(env, &key);
+                        unindex_patient_access(env, &access);
+                        expired_count += 1;
+                        dropped += 1;
+                    } else {
+                        kept.push_back(id);
+                    }
+                }
+                _ => dropped += 1,
             }
         }
+
+        if dropped > 0 {
+            set_expiring_ids(env, bucket, &kept);
+            set_active_grant_count(env, get_active_grant_count(env).saturating_sub(dropped));
+        }
+        // Stop on a bucket that still holds grants: either the batch ran out
+        // or it is the current hour and the rest expire later.
+        if !kept.is_empty() || bucket == current {
+            break;
+        }
+        bucket += 1;
     }
+
+    env.storage().instance().set(&EMRG_EXPIRY_CURSOR, &bucket);
     expired_count
 }
+
+/// Records a new break-glass access and queues it for review
+pub fn create_break_glass_event(
+    env: &Env,
+    provider: &Address,
+    patient: &Address,
+    record_id: u64,
+    justification: &String,
+) -> BreakGlassEvent {
+    let id: u64 = env.storage().instance().get(&BG_CTR).unwrap_or(0) + 1;
+    env.storage().instance().set(&BG_CTR, &id);
+
+    let event = BreakGlassEvent {
+        id,
+        provider: provider.clone(),
+        patient: patient.clone(),
+        record_id,
+        justification: justification.clone(),
+        accessed_at: env.ledger().timestamp(),
+        approved: None,
+        reviewed_by: None,
+        reviewed_at: None,
+    };
+    set_break_glass_event(env, &event);
+
+    let mut pending = get_pending_break_glass_ids(env);
+    pending.push_back(id);
+    set_pending_break_glass_ids(env, &pending);
+    event
+}
+
+/// Retrieves a break-glass event by ID
+pub fn get_break_glass_event(env: &Env, event_id: u64) -> Option<BreakGlassEvent> {
+    env.storage().persistent().get(&(BG_EVENT, event_id))
+}
+
+fn set_break_glass_event(env: &Env, event: &BreakGlassEvent) {
+    let key = (BG_EVENT, event.id);
+    env.storage().persistent().set(&key, event);
+    extend_ttl_emergency_key(env, &key);
+}
+
+/// Ids of break-glass events still awaiting review, oldest first
+pub fn get_pending_break_glass_ids(env: &Env) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&BG_PENDING)
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_pending_break_glass_ids(env: &Env, pending: &Vec<u64>) {
+    env.storage().persistent().set(&BG_PENDING, pending);
+    extend_ttl_break_glass_pending(env);
+}
+
+/// Records the review outcome and drops the event from the pending queue.
+/// Returns `None` if the event does not exist or was already reviewed.
+pub fn review_break_glass_event(
+    env: &Env,
+    event_id: u64,
+    reviewer: &Address,
+    approved: bool,
+) -> Option<BreakGlassEvent> {
+    let mut event = get_break_glass_event(env, event_id)?;
+    if event.approved.is_some() {
+        return None;
+    }
+    event.approved = Some(approved);
+    event.reviewed_by = Some(reviewer.clone());
+    event.reviewed_at = Some(env.ledger().timestamp());
+    set_break_glass_event(env, &event);
+
+    let mut pending = get_pending_break_glass_ids(env);
+    if let Some(index) = pending.first_index_of(event_id) {
+        pending.remove(index);
+    }
+    set_pending_break_glass_ids(env, &pending);
+    Some(event)
+}
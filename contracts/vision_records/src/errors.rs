@@ -5,16 +5,14 @@ pub const ERROR_LOG_KEY: Symbol = symbol_short!("ERR_LOG");
 pub const ERROR_COUNT_KEY: Symbol = symbol_short!("ERR_CNT");
 pub const MAX_ERROR_LOG_SIZE: u32 = 100;
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
-
 /// Extends the time-to-live (TTL) for instance storage.
 /// Instance storage TTL applies to all keys in the instance storage.
 /// This ensures the data remains accessible for the extended period.
 fn extend_ttl_instance(env: &Env) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .instance()
-        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(policy.threshold, policy.extend_to);
 }
 
 /// Error categories for classifying different types of errors
@@ -128,6 +126,19 @@ pub enum ContractError {
     LineageCycleDetected = 44,
     UserAlreadyExists = 45,
     InvalidPhase = 46,
+    RefillLimitReached = 47,
+    SlotUnavailable = 48,
+    AccessRequestNotFound = 49,
+    /// The caller held an access grant for the record, but it has lapsed.
+    AccessExpired = 50,
+    /// The patient has missed too many appointments to self-schedule.
+    TooManyNoShows = 51,
+    /// The appointment has been moved the maximum number of times.
+    TooManyReschedules = 52,
+    /// No delegation exists between the given delegator and delegatee.
+    DelegationNotFound = 53,
+    /// The patient already has the maximum number of unanswered access requests.
+    TooManyPendingRequests = 54,
 }
 
 impl ContractError {
@@ -159,6 +170,7 @@ impl ContractError {
             | ContractError::AccessDenied
             | ContractError::InsufficientPermissions
             | ContractError::ExpiredAccess
+            | ContractError::AccessExpired
             | ContractError::ConsentRequired
             | ContractError::ConsentExpired
             | ContractError::LineageAccessDenied => ErrorCategory::Authorization,
@@ -167,6 +179,8 @@ impl ContractError {
             | ContractError::ProviderNotFound
             | ContractError::EmergencyAccessNotFound
             | ContractError::AppointmentNotFound
+            | ContractError::AccessRequestNotFound
+            | ContractError::DelegationNotFound
             | ContractError::LineageNodeNotFound
             | ContractError::LineageAncestorMissing => ErrorCategory::NotFound,
             ContractError::ProviderAlreadyRegistered
@@ -174,7 +188,12 @@ impl ContractError {
             | ContractError::DuplicateRecord
             | ContractError::DelegationExpired
             | ContractError::NonceAlreadyUsed
-            | ContractError::LineageCycleDetected => ErrorCategory::StateConflict,
+            | ContractError::LineageCycleDetected
+            | ContractError::RefillLimitReached
+            | ContractError::SlotUnavailable
+            | ContractError::TooManyNoShows
+            | ContractError::TooManyReschedules
+            | ContractError::TooManyPendingRequests => ErrorCategory::StateConflict,
             ContractError::LineageTampered => ErrorCategory::StateConflict,
             ContractError::ConflictNotFound => ErrorCategory::NotFound,
             ContractError::StorageError => ErrorCategory::Storage,
@@ -214,14 +233,22 @@ impl ContractError {
             | ContractError::AccessDenied
             | ContractError::InsufficientPermissions
             | ContractError::ExpiredAccess
+            | ContractError::AccessExpired
             | ContractError::ConsentRequired
             | ContractError::ConsentExpired
             | ContractError::ProviderAlreadyRegistered
             | ContractError::DelegationExpired
             | ContractError::RateLimitExceeded
-            | ContractError::NonceAlreadyUsed => ErrorSeverity::Medium,
+            | ContractError::NonceAlreadyUsed
+            | ContractError::RefillLimitReached
+            | ContractError::TooManyNoShows
+            | ContractError::TooManyReschedules
+            | ContractError::TooManyPendingRequests => ErrorSeverity::Medium,
+            ContractError::SlotUnavailable => ErrorSeverity::Low,
             ContractError::EmergencyAccessNotFound
             | ContractError::AppointmentNotFound
+            | ContractError::AccessRequestNotFound
+            | ContractError::DelegationNotFound
             | ContractError::AppointmentNotVerified => ErrorSeverity::Low,
             ContractError::VersionConflict | ContractError::ConflictQueued => ErrorSeverity::Medium,
             ContractError::ConflictNotFound => ErrorSeverity::Low,
@@ -262,6 +289,15 @@ impl ContractError {
             ContractError::AccessDenied => "Access denied to the requested resource",
             ContractError::Paused => "Contract operations are currently paused",
             ContractError::InvalidPhase => "Invalid phase for operation",
+            ContractError::RefillLimitReached => "Prescription has no refills remaining",
+            ContractError::SlotUnavailable => "Requested appointment slot is unavailable",
+            ContractError::TooManyReschedules => "Appointment has reached its reschedule limit",
+            ContractError::TooManyPendingRequests => {
+                "Patient has too many unanswered access requests"
+            }
+            ContractError::TooManyNoShows => {
+                "Patient has too many no-shows; the provider must schedule"
+            }
             ContractError::ProviderNotFound => "Provider not found in the system",
             ContractError::ProviderAlreadyRegistered => "Provider is already registered",
             ContractError::InvalidVerificationStatus => "Invalid verification status provided",
@@ -270,6 +306,7 @@ impl ContractError {
             ContractError::StorageError => "Storage operation failed",
             ContractError::RateLimitExceeded => "Rate limit exceeded, please retry later",
             ContractError::ExpiredAccess => "Access grant has expired",
+            ContractError::AccessExpired => "Previously granted access has expired",
             ContractError::InvalidRole => "Invalid role specified",
             ContractError::InvalidPermission => "Invalid permission specified",
             ContractError::DelegationExpired => "Role delegation has expired",
@@ -285,6 +322,8 @@ impl ContractError {
             ContractError::ConsentExpired => "Patient consent has expired",
             ContractError::EmergencyAccessNotFound => "Emergency access request not found",
             ContractError::AppointmentNotFound => "Appointment not found",
+            ContractError::AccessRequestNotFound => "Access request not found",
+            ContractError::DelegationNotFound => "Delegation not found",
             ContractError::AppointmentNotVerified => "Appointment is not verified",
             ContractError::InvalidEmergencyCondition => "Invalid emergency condition provided",
             ContractError::InvalidAttestation => "Invalid emergency attestation provided",
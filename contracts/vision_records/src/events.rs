@@ -3,10 +3,11 @@
 use crate::appointment::AppointmentType;
 use crate::audit::{AccessAction, AccessResult, AuditEntry};
 use crate::circuit_breaker::PauseScope;
-use crate::emergency::EmergencyCondition;
+use crate::emergency::{BreakGlassEvent, EmergencyCondition};
 use crate::errors::{ErrorCategory, ErrorContext, ErrorSeverity};
-use crate::{AccessLevel, RecordType, Role, VerificationStatus};
-use soroban_sdk::{symbol_short, Address, Env, String};
+use crate::prescription::LensType;
+use crate::{AccessLevel, ContractVersion, Permission, RecordType, Role, VerificationStatus};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Vec};
 
 /// Event published when the contract is initialized.
 #[soroban_sdk::contracttype]
@@ -16,6 +17,26 @@ pub struct InitializedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when the contract WASM is upgraded.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradedEvent {
+    pub admin: Address,
+    pub new_wasm_hash: BytesN<32>,
+    pub version: u32,
+    pub timestamp: u64,
+}
+
+/// Event published when stored data is migrated to a new contract version.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigratedEvent {
+    pub admin: Address,
+    pub from_version: ContractVersion,
+    pub to_version: ContractVersion,
+    pub timestamp: u64,
+}
+
 /// Event published when an admin transfer is proposed.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -53,6 +74,78 @@ pub struct UserRegisteredEvent {
     pub timestamp: u64,
 }
 
+/// Event published when an existing user's role is changed.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleChangedEvent {
+    pub user: Address,
+    pub old_role: Role,
+    pub new_role: Role,
+    pub changed_by: Address,
+    pub timestamp: u64,
+}
+
+/// Event published when a custom permission is granted to a user.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermissionGrantedEvent {
+    pub user: Address,
+    pub permission: Permission,
+    pub granted_by: Address,
+    pub timestamp: u64,
+}
+
+/// Event published when a custom permission is revoked from a user.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermissionRevokedEvent {
+    pub user: Address,
+    pub permission: Permission,
+    pub revoked_by: Address,
+    pub timestamp: u64,
+}
+
+/// Event published when all of a user's permissions are revoked at once.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllPermissionsRevokedEvent {
+    pub user: Address,
+    pub revoked: Vec<Permission>,
+    pub revoked_by: Address,
+    pub timestamp: u64,
+}
+
+/// Event published when a user's role assignment expiry is moved.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssignmentRenewedEvent {
+    pub user: Address,
+    pub role: Role,
+    pub expires_at: u64,
+    pub renewed_by: Address,
+    pub timestamp: u64,
+}
+
+/// Event published when a role is delegated to another user.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleDelegatedEvent {
+    pub delegator: Address,
+    pub delegatee: Address,
+    pub role: Role,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
+/// Event published when a delegator withdraws a role delegation.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleDelegationRevokedEvent {
+    pub delegator: Address,
+    pub delegatee: Address,
+    pub timestamp: u64,
+}
+
 /// Event published when a new vision record is added.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -64,6 +157,40 @@ pub struct RecordAddedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when a vision record's data hash is replaced.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordUpdatedEvent {
+    pub record_id: u64,
+    pub patient: Address,
+    pub updated_by: Address,
+    pub version: u32,
+    pub timestamp: u64,
+}
+
+/// Event published when a provider asks a patient for access.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccessRequestedEvent {
+    pub request_id: u64,
+    pub provider: Address,
+    pub patient: Address,
+    pub level: AccessLevel,
+    pub duration_seconds: u64,
+    pub timestamp: u64,
+}
+
+/// Event published when a patient approves or denies an access request.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccessRequestRespondedEvent {
+    pub request_id: u64,
+    pub provider: Address,
+    pub patient: Address,
+    pub approved: bool,
+    pub timestamp: u64,
+}
+
 /// Event published when access is granted to a record.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -119,6 +246,16 @@ pub struct AccessExpiredEvent {
     pub purged_at: u64,
 }
 
+/// Event published when the bounds on access-grant durations change.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GrantDurationLimitsUpdatedEvent {
+    pub min_seconds: u64,
+    pub max_seconds: u64,
+    pub updated_by: Address,
+    pub timestamp: u64,
+}
+
 /// Event published when a batch of records is added.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -137,6 +274,15 @@ pub struct BatchAccessGrantedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when a batch of users is registered.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchUsersRegisteredEvent {
+    pub caller: Address,
+    pub count: u32,
+    pub timestamp: u64,
+}
+
 /// Event published when circuit breaker is enabled.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -204,6 +350,33 @@ pub fn publish_initialized(env: &Env, admin: Address) {
     env.events().publish(topics, data);
 }
 
+pub fn publish_upgraded(env: &Env, admin: Address, new_wasm_hash: BytesN<32>, version: u32) {
+    let topics = (symbol_short!("UPGRADE"), admin.clone());
+    let data = UpgradedEvent {
+        admin,
+        new_wasm_hash,
+        version,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_migrated(
+    env: &Env,
+    admin: Address,
+    from_version: ContractVersion,
+    to_version: ContractVersion,
+) {
+    let topics = (symbol_short!("MIGRATE"), admin.clone());
+    let data = MigratedEvent {
+        admin,
+        from_version,
+        to_version,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes an event when a new user is registered.
 /// This event includes the user address, role, name, and registration timestamp.
 pub fn publish_user_registered(env: &Env, user: Address, role: Role, name: String) {
@@ -217,6 +390,126 @@ pub fn publish_user_registered(env: &Env, user: Address, role: Role, name: Strin
     env.events().publish(topics, data);
 }
 
+pub fn publish_role_changed(
+    env: &Env,
+    user: Address,
+    old_role: Role,
+    new_role: Role,
+    changed_by: Address,
+) {
+    let topics = (symbol_short!("ROLE_CHG"), user.clone());
+    let data = RoleChangedEvent {
+        user,
+        old_role,
+        new_role,
+        changed_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_permission_granted(
+    env: &Env,
+    user: Address,
+    permission: Permission,
+    granted_by: Address,
+) {
+    let topics = (symbol_short!("PERM_GRT"), user.clone());
+    let data = PermissionGrantedEvent {
+        user,
+        permission,
+        granted_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_permission_revoked(
+    env: &Env,
+    user: Address,
+    permission: Permission,
+    revoked_by: Address,
+) {
+    let topics = (symbol_short!("PERM_RVK"), user.clone());
+    let data = PermissionRevokedEvent {
+        user,
+        permission,
+        revoked_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_all_permissions_revoked(
+    env: &Env,
+    user: Address,
+    revoked: Vec<Permission>,
+    revoked_by: Address,
+) {
+    let topics = (symbol_short!("PERM_CLR"), user.clone());
+    let data = AllPermissionsRevokedEvent {
+        user,
+        revoked,
+        revoked_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_assignment_renewed(
+    env: &Env,
+    user: Address,
+    role: Role,
+    expires_at: u64,
+    renewed_by: Address,
+) {
+    let topics = (symbol_short!("ROLE_RNW"), user.clone());
+    let data = AssignmentRenewedEvent {
+        user,
+        role,
+        expires_at,
+        renewed_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_role_delegated(
+    env: &Env,
+    delegator: Address,
+    delegatee: Address,
+    role: Role,
+    expires_at: u64,
+) {
+    let topics = (
+        symbol_short!("ROLE_DLG"),
+        delegator.clone(),
+        delegatee.clone(),
+    );
+    let data = RoleDelegatedEvent {
+        delegator,
+        delegatee,
+        role,
+        expires_at,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+pub fn publish_role_delegation_revoked(env: &Env, delegator: Address, delegatee: Address) {
+    let topics = (
+        symbol_short!("ROLE_RVK"),
+        delegator.clone(),
+        delegatee.clone(),
+    );
+    let data = RoleDelegationRevokedEvent {
+        delegator,
+        delegatee,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes an event when a new vision record is added.
 /// This event includes the record ID, patient, provider, record type, and timestamp.
 pub fn publish_record_added(
@@ -237,6 +530,65 @@ pub fn publish_record_added(
     env.events().publish(topics, data);
 }
 
+/// Publishes an event when an existing vision record is updated.
+pub fn publish_record_updated(
+    env: &Env,
+    record_id: u64,
+    patient: Address,
+    updated_by: Address,
+    version: u32,
+) {
+    let topics = (symbol_short!("REC_UPD"), patient.clone(), record_id);
+    let data = RecordUpdatedEvent {
+        record_id,
+        patient,
+        updated_by,
+        version,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+/// Publishes an event when a provider requests access to a patient's records.
+pub fn publish_access_requested(
+    env: &Env,
+    request_id: u64,
+    provider: Address,
+    patient: Address,
+    level: AccessLevel,
+    duration_seconds: u64,
+) {
+    let topics = (symbol_short!("ACC_REQ"), patient.clone(), provider.clone());
+    let data = AccessRequestedEvent {
+        request_id,
+        provider,
+        patient,
+        level,
+        duration_seconds,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+/// Publishes an event when a patient responds to an access request.
+pub fn publish_access_request_responded(
+    env: &Env,
+    request_id: u64,
+    provider: Address,
+    patient: Address,
+    approved: bool,
+) {
+    let topics = (symbol_short!("ACC_RSP"), patient.clone(), provider.clone());
+    let data = AccessRequestRespondedEvent {
+        request_id,
+        provider,
+        patient,
+        approved,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes an event when access is granted to a record.
 /// This event includes patient, grantee, access level, duration, expiration, and timestamp.
 pub fn publish_access_granted(
@@ -331,6 +683,16 @@ pub fn publish_batch_records_added(env: &Env, provider: Address, count: u32) {
     env.events().publish(topics, data);
 }
 
+pub fn publish_batch_users_registered(env: &Env, caller: Address, count: u32) {
+    let topics = (symbol_short!("BATCH_U"), caller.clone());
+    let data = BatchUsersRegisteredEvent {
+        caller,
+        count,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 pub fn publish_contract_paused(env: &Env, caller: Address, scope: PauseScope) {
     let topics = (symbol_short!("PAUSE"),);
     let data = ContractPausedEvent {
@@ -377,6 +739,23 @@ pub fn publish_access_expired(env: &Env, patient: Address, grantee: Address, exp
     };
     env.events().publish(topics, data);
 }
+
+pub fn publish_grant_duration_limits_updated(
+    env: &Env,
+    min_seconds: u64,
+    max_seconds: u64,
+    updated_by: Address,
+) {
+    let topics = (symbol_short!("GRT_LIM"), updated_by.clone());
+    let data = GrantDurationLimitsUpdatedEvent {
+        min_seconds,
+        max_seconds,
+        updated_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Event published when a new provider is registered.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -400,8 +779,19 @@ pub struct ExaminationAddedEvent {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ProviderVerifiedEvent {
     pub provider: Address,
-    pub verifier: Address,
-    pub status: VerificationStatus,
+    pub old_status: VerificationStatus,
+    pub new_status: VerificationStatus,
+    pub verified_by: Address,
+    pub timestamp: u64,
+}
+
+/// Event published when a provider is deactivated or reactivated.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProviderActivationEvent {
+    pub provider: Address,
+    pub is_active: bool,
+    pub changed_by: Address,
     pub timestamp: u64,
 }
 
@@ -427,22 +817,41 @@ pub fn publish_provider_registered(env: &Env, provider: Address, name: String, p
 }
 
 /// Publishes an event when a provider's verification status is updated.
-/// This event includes the provider, verifier, new status, and verification timestamp.
+/// This event includes the provider, the prior and new status, the verifier, and timestamp.
 pub fn publish_provider_verified(
     env: &Env,
     provider: Address,
-    verifier: Address,
-    status: VerificationStatus,
+    old_status: VerificationStatus,
+    new_status: VerificationStatus,
+    verified_by: Address,
 ) {
     let topics = (
         symbol_short!("PROV_VER"),
         provider.clone(),
-        verifier.clone(),
+        verified_by.clone(),
     );
     let data = ProviderVerifiedEvent {
         provider,
-        verifier,
-        status,
+        old_status,
+        new_status,
+        verified_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+/// Publishes an event when a provider is deactivated or reactivated.
+pub fn publish_provider_activation(
+    env: &Env,
+    provider: Address,
+    is_active: bool,
+    changed_by: Address,
+) {
+    let topics = (symbol_short!("PROV_ACT"), provider.clone());
+    let data = ProviderActivationEvent {
+        provider,
+        is_active,
+        changed_by,
         timestamp: env.ledger().timestamp(),
     };
     env.events().publish(topics, data);
@@ -637,6 +1046,16 @@ pub struct EmergencyAccessGrantedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when mass-casualty emergency access is granted across patients.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MassCasualtyAccessGrantedEvent {
+    pub requester: Address,
+    pub count: u32,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
 /// Event published when emergency access is revoked.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -693,6 +1112,23 @@ pub fn publish_emergency_access_granted(
     env.events().publish(topics, data);
 }
 
+/// Publishes an event when mass-casualty emergency access is granted.
+pub fn publish_mass_casualty_access_granted(
+    env: &Env,
+    requester: Address,
+    count: u32,
+    expires_at: u64,
+) {
+    let topics = (symbol_short!("EMRG_MCI"), requester.clone());
+    let data = MassCasualtyAccessGrantedEvent {
+        requester,
+        count,
+        expires_at,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes an event when emergency access is revoked.
 pub fn publish_emergency_access_revoked(
     env: &Env,
@@ -727,6 +1163,22 @@ pub fn publish_emergency_contact_notified(
     env.events().publish(topics, data);
 }
 
+/// Publishes an event when a provider uses the break-glass read path.
+pub fn publish_break_glass(env: &Env, event: &BreakGlassEvent) {
+    let topics = (
+        symbol_short!("BRK_GLASS"),
+        event.patient.clone(),
+        event.provider.clone(),
+    );
+    env.events().publish(topics, event.clone());
+}
+
+/// Publishes an event when a break-glass access is reviewed.
+pub fn publish_break_glass_reviewed(env: &Env, event: &BreakGlassEvent) {
+    let topics = (symbol_short!("BRK_RVW"), event.id);
+    env.events().publish(topics, event.clone());
+}
+
 /// Publishes an event when emergency access is used to access records.
 pub fn publish_emergency_access_used(
     env: &Env,
@@ -784,6 +1236,19 @@ pub struct AppointmentCancelledEvent {
     pub timestamp: u64,
 }
 
+/// Event published when an appointment is cancelled inside the
+/// cancellation window.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AppointmentLateCancelledEvent {
+    pub appointment_id: u64,
+    pub patient: Address,
+    pub provider: Address,
+    pub cancelled_by: Address,
+    pub scheduled_at: u64,
+    pub timestamp: u64,
+}
+
 /// Event published when an appointment is rescheduled.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -830,6 +1295,17 @@ pub struct AppointmentVerifiedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when a waitlisted patient is booked into a freed slot.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WaitlistPromotedEvent {
+    pub appointment_id: u64,
+    pub patient: Address,
+    pub provider: Address,
+    pub scheduled_at: u64,
+    pub timestamp: u64,
+}
+
 /// Publishes an event when an appointment is scheduled.
 pub fn publish_appointment_scheduled(
     env: &Env,
@@ -889,6 +1365,31 @@ pub fn publish_appointment_cancelled(
     env.events().publish(topics, data);
 }
 
+/// Publishes an event when an appointment is cancelled late.
+pub fn publish_appointment_late_cancelled(
+    env: &Env,
+    appointment_id: u64,
+    patient: Address,
+    provider: Address,
+    cancelled_by: Address,
+    scheduled_at: u64,
+) {
+    let topics = (
+        symbol_short!("APPT_LCNL"),
+        patient.clone(),
+        provider.clone(),
+    );
+    let data = AppointmentLateCancelledEvent {
+        appointment_id,
+        patient,
+        provider,
+        cancelled_by,
+        scheduled_at,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes an event when an appointment is rescheduled.
 pub fn publish_appointment_rescheduled(
     env: &Env,
@@ -973,6 +1474,25 @@ pub fn publish_appointment_verified(
     env.events().publish(topics, data);
 }
 
+/// Publishes an event when a waitlisted patient is promoted to an appointment.
+pub fn publish_waitlist_promoted(
+    env: &Env,
+    appointment_id: u64,
+    patient: Address,
+    provider: Address,
+    scheduled_at: u64,
+) {
+    let topics = (symbol_short!("WAIT_PRM"), patient.clone(), provider.clone());
+    let data = WaitlistPromotedEvent {
+        appointment_id,
+        patient,
+        provider,
+        scheduled_at,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Event published when an audit log entry is created.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -1019,6 +1539,16 @@ pub struct RateLimitExceededEvent {
     pub timestamp: u64,
 }
 
+/// Event published when the storage TTL policy changes.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlPolicyUpdatedEvent {
+    pub threshold: u32,
+    pub extend_to: u32,
+    pub updated_by: Address,
+    pub timestamp: u64,
+}
+
 /// Event published when rate limit configuration is updated.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -1040,6 +1570,16 @@ pub struct RateLimitBypassUpdatedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when an admin clears a user's rate limit counter.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimitResetEvent {
+    pub address: Address,
+    pub operation: String,
+    pub reset_by: Address,
+    pub timestamp: u64,
+}
+
 /// Publishes a rate limit exceeded event.
 pub fn publish_rate_limit_exceeded(
     env: &Env,
@@ -1080,6 +1620,18 @@ pub fn publish_rate_limit_config_updated(
     env.events().publish(topics, data);
 }
 
+/// Publishes a storage TTL policy updated event.
+pub fn publish_ttl_policy_updated(env: &Env, threshold: u32, extend_to: u32, updated_by: Address) {
+    let topics = (symbol_short!("TTL_POL"),);
+    let data = TtlPolicyUpdatedEvent {
+        threshold,
+        extend_to,
+        updated_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes a rate limit bypass updated event.
 pub fn publish_rate_limit_bypass_updated(
     env: &Env,
@@ -1097,6 +1649,22 @@ pub fn publish_rate_limit_bypass_updated(
     env.events().publish(topics, data);
 }
 
+/// Publishes a rate limit reset event.
+pub fn publish_rate_limit_reset(env: &Env, address: Address, operation: String, reset_by: Address) {
+    let topics = (
+        symbol_short!("RL_RESET"),
+        address.clone(),
+        operation.clone(),
+    );
+    let data = RateLimitResetEvent {
+        address,
+        operation,
+        reset_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Event published when an access policy is created.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -1106,6 +1674,16 @@ pub struct PolicyCreatedEvent {
     pub timestamp: u64,
 }
 
+/// Event published when an access policy is enabled or disabled.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolicyEnabledEvent {
+    pub policy_id: String,
+    pub enabled: bool,
+    pub updated_by: Address,
+    pub timestamp: u64,
+}
+
 /// Event published when a user credential is set.
 #[soroban_sdk::contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -1137,6 +1715,17 @@ pub fn publish_policy_created(env: &Env, policy_id: String, created_by: Address)
     env.events().publish(topics, data);
 }
 
+pub fn publish_policy_enabled(env: &Env, policy_id: String, enabled: bool, updated_by: Address) {
+    let topics = (symbol_short!("POL_ENA"),);
+    let data = PolicyEnabledEvent {
+        policy_id,
+        enabled,
+        updated_by,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
 /// Publishes an event when a user credential is set.
 pub fn publish_credential_set(
     env: &Env,
@@ -1170,3 +1759,110 @@ pub fn publish_sensitivity_set(
     };
     env.events().publish(topics, data);
 }
+
+/// Event published when a prescription is issued. Optical values are left
+/// out; subscribers fetch them through the access-checked getters.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrescriptionIssuedEvent {
+    pub rx_id: u64,
+    pub patient: Address,
+    pub provider: Address,
+    pub lens_type: LensType,
+    pub timestamp: u64,
+}
+
+/// Publishes an event when a prescription is issued.
+pub fn publish_prescription_issued(
+    env: &Env,
+    rx_id: u64,
+    patient: Address,
+    provider: Address,
+    lens_type: LensType,
+) {
+    let topics = (symbol_short!("RX_ISSUE"), patient.clone(), provider.clone());
+    let data = PrescriptionIssuedEvent {
+        rx_id,
+        patient,
+        provider,
+        lens_type,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+/// Event published when a prescription is verified.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrescriptionVerifiedEvent {
+    pub rx_id: u64,
+    pub verifier: Address,
+    pub timestamp: u64,
+}
+
+/// Publishes an event when a prescription is verified.
+pub fn publish_prescription_verified(env: &Env, rx_id: u64, verifier: Address) {
+    let topics = (symbol_short!("RX_VERIF"), rx_id);
+    let data = PrescriptionVerifiedEvent {
+        rx_id,
+        verifier,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+/// Event published when a record is read by someone other than the patient,
+/// for patients who opted in to access notifications.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordAccessedEvent {
+    pub patient: Address,
+    pub record_id: u64,
+    pub accessor: Address,
+    pub timestamp: u64,
+}
+
+/// Publishes a record access notification for the patient.
+pub fn publish_record_accessed(env: &Env, patient: Address, record_id: u64, accessor: Address) {
+    let topics = (symbol_short!("REC_READ"), patient.clone());
+    let data = RecordAccessedEvent {
+        patient,
+        record_id,
+        accessor,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
+
+/// Event published when a prescription is dispensed.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DispenseEvent {
+    pub rx_id: u64,
+    pub patient: Address,
+    pub dispenser: Address,
+    pub refills_used: u32,
+    pub refills_allowed: u32,
+    pub timestamp: u64,
+}
+
+/// Publishes an event when a prescription is dispensed.
+pub fn publish_dispense(
+    env: &Env,
+    rx_id: u64,
+    patient: Address,
+    dispenser: Address,
+    refills_used: u32,
+    refills_allowed: u32,
+) {
+    let topics = (symbol_short!("RX_DISP"), patient.clone(), dispenser.clone());
+    let data = DispenseEvent {
+        rx_id,
+        patient,
+        dispenser,
+        refills_used,
+        refills_allowed,
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish(topics, data);
+}
@@ -6,13 +6,11 @@ use teye_common::state_machine::{
     self, EntityKind, LifecycleState, TransitionContext, TransitionRecord,
 };
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
-
 fn extend_ttl_exam_key(env: &Env, key: &(Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 #[contracttype]
@@ -19,12 +19,13 @@ pub mod prescription;
 pub mod provider;
 pub mod rate_limit;
 pub mod rbac;
+pub mod ttl;
 pub mod validation;
 
 use key_manager::{DerivedKey, KeyManagerContractClient};
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, String,
-    Symbol, Vec,
+    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, Map, String,
+    Symbol, TryFromVal, Val, Vec,
 };
 
 use teye_common::lineage::{self, RelationshipKind};
@@ -42,13 +43,21 @@ use teye_common::{
 pub use errors::ContractError;
 
 /// Re-export provider types needed by other modules (e.g. events).
-pub use provider::VerificationStatus;
+pub use provider::{
+    Certification, License, Location, Provider, ProviderStats, ProviderStatus,
+    RecordTypeRequirement, VerificationStatus,
+};
 
 /// Re-export error helpers used throughout the contract.
 pub use errors::{create_error_context, log_error};
 
 /// Re-export types from submodules used directly in the contract impl.
+pub use appointment::{
+    Appointment, AppointmentHistoryEntry, AppointmentStatus, AppointmentType, ProviderAvailability,
+    WaitlistEntry,
+};
 pub use audit::{AccessAction, AccessResult};
+pub use emergency::{BreakGlassEvent, EmergencyAccess, EmergencyAuditEntry, EmergencyCondition};
 pub use examination::{
     EyeExamination, IntraocularPressure, OptFundusPhotography, OptRetinalImaging, OptVisualField,
     SlitLampFindings, VisualAcuity,
@@ -57,51 +66,246 @@ pub use patient_profile::{
     EmergencyContact, InsuranceInfo, OptionalEmergencyContact, OptionalInsuranceInfo,
     PatientProfile,
 };
-pub use prescription::{LensType, OptionalContactLensData, Prescription, PrescriptionData};
+pub use prescription::{
+    DispenseRecord, LensType, OptionalContactLensData, Prescription, PrescriptionData,
+};
 
 /// Storage keys for the contract
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PENDING_ADMIN: Symbol = symbol_short!("PEND_ADM");
 const INITIALIZED: Symbol = symbol_short!("INIT");
+const VERSION: Symbol = symbol_short!("VERSION");
+const SEMVER: Symbol = symbol_short!("SEMVER");
 const RATE_CFG: Symbol = symbol_short!("RL_IN_CFG");
 const RATE_TRACK: Symbol = symbol_short!("RL_IN_TRK");
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
-
 const ENC_CUR: Symbol = symbol_short!("ENC_CUR");
 const ENC_KEY: Symbol = symbol_short!("ENC_KEY");
 const KEY_MGR: Symbol = symbol_short!("KEY_MGR");
 const KEY_MGR_KEY: Symbol = symbol_short!("KEY_MGRK");
+const GRANT_DURATION: Symbol = symbol_short!("GRT_DUR");
+
+/// Version of the code in this WASM. `migrate` brings stored data up to it.
+const CONTRACT_VERSION: ContractVersion = ContractVersion {
+    major: 1,
+    minor: 1,
+    patch: 0,
+};
+
+/// Version assumed for deployments that predate the stored version keys.
+const LEGACY_VERSION: ContractVersion = ContractVersion {
+    major: 1,
+    minor: 0,
+    patch: 0,
+};
 
 /// Extends the time-to-live (TTL) for a storage key containing an Address.
 /// This ensures the data remains accessible for the extended period.
 fn extend_ttl_address_key(env: &Env, key: &(Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Extends the time-to-live (TTL) for a storage key containing a u64 value.
 /// This ensures the data remains accessible for the extended period.
 fn extend_ttl_u64_key(env: &Env, key: &(Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Extends the time-to-live (TTL) for an access grant storage key.
 /// This ensures access grant data remains accessible for the extended period.
 fn extend_ttl_access_key(env: &Env, key: &(Symbol, Address, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 fn extend_ttl_record_access_key(env: &Env, key: &(Symbol, u64, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+/// Adds `grantee` to the patient's grantee index if not already present.
+fn track_grantee(env: &Env, patient: &Address, grantee: &Address) {
+    let list_key = (symbol_short!("ACC_LST"), patient.clone());
+    let mut grantees: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&list_key)
+        .unwrap_or(Vec::new(env));
+    if !grantees.contains(grantee) {
+        grantees.push_back(grantee.clone());
+        env.storage().persistent().set(&list_key, &grantees);
+    }
+    extend_ttl_address_key(env, &list_key);
+}
+
+/// Removes `grantee` from the patient's grantee index.
+fn untrack_grantee(env: &Env, patient: &Address, grantee: &Address) {
+    let list_key = (symbol_short!("ACC_LST"), patient.clone());
+    let mut grantees: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&list_key)
+        .unwrap_or(Vec::new(env));
+    if let Some(pos) = grantees.first_index_of(grantee) {
+        grantees.remove(pos);
+        env.storage().persistent().set(&list_key, &grantees);
+    }
+}
+
+/// Removes `record_id` from the provider's record index.
+fn untrack_provider_record(env: &Env, provider: &Address, record_id: u64) {
+    let key = (symbol_short!("PRV_REC"), provider.clone());
+    let mut records: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    if let Some(pos) = records.first_index_of(record_id) {
+        records.remove(pos);
+        env.storage().persistent().set(&key, &records);
+        extend_ttl_address_key(env, &key);
+    }
+}
+
+/// Inserts `record_id` into the patient's record index, keeping it strictly
+/// ascending. Ids already present are left alone.
+fn track_patient_record(env: &Env, patient: &Address, record_id: u64) {
+    let key = (symbol_short!("PAT_REC"), patient.clone());
+    let mut records: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    if let Err(pos) = records.binary_search(record_id) {
+        records.insert(pos, record_id);
+        env.storage().persistent().set(&key, &records);
+    }
+    debug_assert!(is_strictly_ascending(&records));
+    extend_ttl_address_key(env, &key);
+}
+
+/// Most record ids `rebuild_record_type_counts` reads in one call.
+pub const MAX_RECOUNT_PAGE: u32 = 50;
+
+/// Furthest ahead a user may push their own role assignment's expiry.
+pub const MAX_SELF_RENEWAL_SECONDS: u64 = 90 * 86400;
+
+/// Most unanswered access requests a patient can have at once.
+pub const MAX_PENDING_ACCESS_REQUESTS: u32 = 20;
+
+fn record_type_counts_key(patient: &Address) -> (Symbol, Address) {
+    (symbol_short!("REC_TCNT"), patient.clone())
+}
+
+/// Bumps the patient's count of records of `record_type`.
+fn count_patient_record(env: &Env, patient: &Address, record_type: &RecordType) {
+    let key = record_type_counts_key(patient);
+    let mut counts: Map<RecordType, u32> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Map::new(env));
+    let count = counts.get(record_type.clone()).unwrap_or(0);
+    counts.set(record_type.clone(), count.saturating_add(1));
+    env.storage().persistent().set(&key, &counts);
+    extend_ttl_address_key(env, &key);
+}
+
+/// Invariant for id indexes: every id appears once, in ascending order.
+pub(crate) fn is_strictly_ascending(ids: &Vec<u64>) -> bool {
+    let mut prev: Option<u64> = None;
+    for id in ids.iter() {
+        if prev.is_some_and(|p| p >= id) {
+            return false;
+        }
+        prev = Some(id);
+    }
+    true
+}
+
+/// Appends `record_id` to the provider's record index.
+fn track_provider_record(env: &Env, provider: &Address, record_id: u64) {
+    let key = (symbol_short!("PRV_REC"), provider.clone());
+    let mut records: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    records.push_back(record_id);
+    env.storage().persistent().set(&key, &records);
+    extend_ttl_address_key(env, &key);
+}
+
+/// Loads the master key bytes for `version`, or an empty key if unknown.
+fn load_master_bytes(env: &Env, version: &Option<String>) -> StdVec<u8> {
+    if let Some(ver) = version {
+        if let Some(sv) = env
+            .storage()
+            .persistent()
+            .get::<(Symbol, String), String>(&(ENC_KEY, ver.clone()))
+        {
+            let hex = sv.to_string();
+            if let Some(bytes) = teye_common::hex_to_bytes(&hex) {
+                return bytes;
+            }
+        }
+    }
+    StdVec::new()
+}
+
+/// Encrypts a data hash under the current key, returning it with the key version used.
+fn encrypt_data_hash(env: &Env, data_hash: &String) -> (String, Option<String>) {
+    let current_version: Option<String> = env.storage().instance().get(&ENC_CUR);
+    let km = KeyManager::new(load_master_bytes(env, &current_version));
+    let plaintext: StdString = data_hash.to_string();
+    let ciphertext = km.encrypt(None, &plaintext);
+    (String::from_str(env, &ciphertext), current_version)
+}
+
+/// Decrypts a stored data hash, leaving it unchanged if it cannot be decrypted.
+fn decrypt_data_hash(env: &Env, stored: &String, key_version: &Option<String>) -> String {
+    // Prefer the stored key version, fall back to current instance version
+    let key_ver = key_version
+        .clone()
+        .or_else(|| env.storage().instance().get(&ENC_CUR));
+    let master_bytes = load_master_bytes(env, &key_ver);
+    if !master_bytes.is_empty() || key_version.is_none() {
+        let km = KeyManager::new(master_bytes);
+        if let Some(plain) = km.decrypt(None, &stored.to_string()) {
+            return String::from_str(env, &plain);
+        }
+    }
+    stored.clone()
+}
+
+/// Appends the record's current data hash to its version history and returns
+/// the new number of versions.
+fn append_record_version(env: &Env, record: &VisionRecord, updated_by: &Address) -> u32 {
+    let key = (symbol_short!("REC_VER"), record.id);
+    let mut versions: Vec<RecordVersion> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    versions.push_back(RecordVersion {
+        data_hash: record.data_hash.clone(),
+        key_version: record.key_version.clone(),
+        updated_at: record.updated_at,
+        updated_by: updated_by.clone(),
+    });
+    env.storage().persistent().set(&key, &versions);
+    extend_ttl_u64_key(env, &key);
+    versions.len()
 }
 
 fn rate_limit_action_hash(
@@ -146,10 +350,81 @@ fn has_active_consent(env: &Env, patient: &Address, grantee: &Address) -> bool {
     }
 }
 
+fn access_notify_key(patient: &Address) -> (Symbol, Address) {
+    (symbol_short!("ACC_NOTIF"), patient.clone())
+}
+
+fn default_grant_duration_key(patient: &Address) -> (Symbol, Address) {
+    (symbol_short!("GRT_DFLT"), patient.clone())
+}
+
+fn grant_scope_key(patient: &Address, grantee: &Address) -> (Symbol, Address, Address) {
+    (symbol_short!("ACC_TYPES"), patient.clone(), grantee.clone())
+}
+
+/// Purpose of a patient-level grant. Kept beside the `AccessGrant` rather
+/// than in it so grants stored before purposes existed still decode; only
+/// non-`Treatment` purposes are stored.
+fn grant_purpose_key(patient: &Address, grantee: &Address) -> (Symbol, Address, Address) {
+    (symbol_short!("ACC_PURP"), patient.clone(), grantee.clone())
+}
+
+fn grant_purpose(env: &Env, patient: &Address, grantee: &Address) -> ConsentType {
+    env.storage()
+        .persistent()
+        .get(&grant_purpose_key(patient, grantee))
+        .unwrap_or(ConsentType::Treatment)
+}
+
+fn set_grant_purpose(env: &Env, patient: &Address, grantee: &Address, purpose: ConsentType) {
+    let key = grant_purpose_key(patient, grantee);
+    if purpose == ConsentType::Treatment {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &purpose);
+        extend_ttl_access_key(env, &key);
+    }
+}
+
+/// Whether the grantee's patient-level grant covers records of
+/// `record_type`. Grants without a stored scope cover every type.
+fn grant_scope_allows(
+    env: &Env,
+    patient: &Address,
+    grantee: &Address,
+    record_type: &RecordType,
+) -> bool {
+    match env
+        .storage()
+        .persistent()
+        .get::<_, Vec<RecordType>>(&grant_scope_key(patient, grantee))
+    {
+        Some(types) => types.is_empty() || types.contains(record_type),
+        None => true,
+    }
+}
+
+/// Like `has_active_consent`, but the consent must also be for `purpose`.
+fn has_active_consent_for(
+    env: &Env,
+    patient: &Address,
+    grantee: &Address,
+    purpose: &ConsentType,
+) -> bool {
+    let key = consent_key(patient, grantee);
+    if let Some(consent) = env.storage().persistent().get::<_, ConsentGrant>(&key) {
+        !consent.revoked
+            && consent.expires_at > env.ledger().timestamp()
+            && consent.consent_type == *purpose
+    } else {
+        false
+    }
+}
+
 pub use rbac::{
     create_access_policy, evaluate_access_policies, set_record_sensitivity, set_user_credential,
-    AccessPolicy, CredentialType, Permission, PolicyContext, Role, SensitivityLevel,
-    TimeRestriction,
+    AccessPolicy, CredentialType, Delegation, Permission, PolicyContext, Role, ScopedDelegation,
+    SensitivityLevel, TimeRestriction,
 };
 
 #[contracttype]
@@ -222,12 +497,41 @@ pub struct PrepareGrantAccess {
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct PrepareAddPrescription {
+    pub patient: Address,
+    pub provider: Address,
+    pub prescription_data: prescription::PrescriptionData,
+    pub refills_allowed: u32,
+    pub timestamp: u64,
+}
+
+/// `PrepareAddPrescription` as written before refill tracking was added.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyPrepareAddPrescription {
     pub patient: Address,
     pub provider: Address,
     pub prescription_data: prescription::PrescriptionData,
     pub timestamp: u64,
 }
 
+/// Loads a pending two-phase prescription, accepting entries prepared before
+/// the upgrade; those carry no refill count and are allowed a single fill.
+fn load_prepared_prescription(env: &Env, key: &(Symbol, u64)) -> Option<PrepareAddPrescription> {
+    let raw: Val = env.storage().temporary().get(key)?;
+    let fields = Map::<Symbol, Val>::try_from_val(env, &raw).ok()?;
+    if fields.contains_key(Symbol::new(env, "refills_allowed")) {
+        return PrepareAddPrescription::try_from_val(env, &raw).ok();
+    }
+    let legacy = LegacyPrepareAddPrescription::try_from_val(env, &raw).ok()?;
+    Some(PrepareAddPrescription {
+        patient: legacy.patient,
+        provider: legacy.provider,
+        prescription_data: legacy.prescription_data,
+        refills_allowed: 1,
+        timestamp: legacy.timestamp,
+    })
+}
+
 /// Vision record types
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -266,6 +570,28 @@ pub struct User {
     pub is_active: bool,
 }
 
+/// Semantic version of the contract code, compared field by field.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub struct ContractVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+/// Everything that shapes what a user can do: their registration, the
+/// role currently in force, ACL groups and delegations received.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct UserProfile {
+    pub user: User,
+    /// `Role::None` when the assignment has expired
+    pub active_role: Role,
+    pub groups: Vec<String>,
+    pub incoming_delegations: Vec<Delegation>,
+    pub incoming_scoped_delegations: Vec<ScopedDelegation>,
+}
+
 /// Vision record structure
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -280,6 +606,16 @@ pub struct VisionRecord {
     pub updated_at: u64,
 }
 
+/// Outcome of one id in a `get_records_checked` batch
+#[contracttype]
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)] // contracttype enums can't box their payloads
+pub enum RecordFetchResult {
+    Found(VisionRecord),
+    NotFound(u64),
+    AccessDenied(u64),
+}
+
 /// Access grant structure
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -291,6 +627,29 @@ pub struct AccessGrant {
     pub expires_at: u64,
 }
 
+/// Lifecycle of a provider-initiated access request
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccessRequestStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+/// A provider's request for access, awaiting the patient's decision
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AccessRequest {
+    pub id: u64,
+    pub provider: Address,
+    pub patient: Address,
+    pub level: AccessLevel,
+    pub duration_seconds: u64,
+    pub status: AccessRequestStatus,
+    pub requested_at: u64,
+    pub responded_at: Option<u64>,
+}
+
 /// Consent grant structure for patient-to-provider consent tracking
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -321,6 +680,34 @@ pub struct BatchGrantInput {
     pub duration_seconds: u64,
 }
 
+/// Caller's effective access to a patient together with the visible record ids
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AccessSummary {
+    pub level: AccessLevel,
+    pub record_ids: Vec<u64>,
+    pub record_count: u32,
+}
+
+/// Who can read a record: individual accessors with their level, and the
+/// roles whose base permissions include `ReadAnyRecord`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordAccessors {
+    pub users: Vec<(Address, AccessLevel)>,
+    pub roles: Vec<Role>,
+}
+
+/// A snapshot of a record's content hash, kept each time the record is written
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RecordVersion {
+    pub data_hash: String,
+    pub key_version: Option<String>,
+    pub updated_at: u64,
+    pub updated_by: Address,
+}
+
 #[contract]
 #[allow(clippy::too_many_arguments)]
 pub struct VisionRecordsContract;
@@ -396,7 +783,20 @@ impl VisionRecordsContract {
         version.to_string().parse::<u32>().ok()
     }
 
-    fn enforce_rate_limit(env: &Env, caller: &Address) -> Result<(), ContractError> {
+    /// Enforce the rate limit for `operation`. When a per-operation
+    /// sliding-window limit applies to the caller it is the only limiter
+    /// checked; otherwise the legacy fixed-window limit (`RL_IN_CFG`) is used.
+    fn enforce_rate_limit(
+        env: &Env,
+        caller: &Address,
+        operation: &str,
+    ) -> Result<(), ContractError> {
+        if rate_limit::get_effective_config(env, caller, &String::from_str(env, operation))
+            .is_some()
+        {
+            return Self::enforce_operation_rate_limit(env, caller, operation);
+        }
+
         let cfg: Option<(u64, u64)> = env.storage().instance().get(&RATE_CFG);
         let (max_requests_per_window, window_duration_seconds) = match cfg {
             Some(c) => c,
@@ -430,6 +830,30 @@ impl VisionRecordsContract {
         Ok(())
     }
 
+    /// Enforce the per-operation sliding-window limit configured for
+    /// `operation`, publishing an event when the caller is throttled.
+    fn enforce_operation_rate_limit(
+        env: &Env,
+        caller: &Address,
+        operation: &str,
+    ) -> Result<(), ContractError> {
+        let operation = String::from_str(env, operation);
+        let (allowed, current_count, max_requests, reset_at) =
+            rate_limit::check_rate_limit(env, caller, &operation);
+        if !allowed {
+            events::publish_rate_limit_exceeded(
+                env,
+                caller.clone(),
+                operation,
+                current_count,
+                max_requests,
+                reset_at,
+            );
+            return Err(ContractError::RateLimitExceeded);
+        }
+        Ok(())
+    }
+
     /// Initialize the contract with an admin address
     pub fn initialize(env: Env, admin: Address) -> Result<(), ContractError> {
         if env.storage().instance().has(&INITIALIZED) {
@@ -454,6 +878,10 @@ impl VisionRecordsContract {
 
         env.storage().instance().set(&ADMIN, &admin);
         env.storage().instance().set(&INITIALIZED, &true);
+        env.storage()
+            .instance()
+            .set(&VERSION, &CONTRACT_VERSION.major);
+        env.storage().instance().set(&SEMVER, &CONTRACT_VERSION);
         rbac::assign_role(&env, admin.clone(), Role::Admin, 0);
 
         // Bootstrap the admin with the Admin role so they can register other users
@@ -630,8 +1058,119 @@ impl VisionRecordsContract {
         multisig::get_proposal(&env, proposal_id)
     }
 
+    // ── Upgrades ─────────────────────────────────────────────────────────────
+
+    /// Returns the major contract version recorded in storage.
+    ///
+    /// Deprecated: use `contract_version`, which includes minor and patch.
+    pub fn version(env: Env) -> u32 {
+        Self::contract_version(env).major
+    }
+
+    /// Returns the contract version recorded in storage. Deployments from
+    /// before semantic versioning report their stored `u32` as the major
+    /// version, and deployments with no stored version at all report 1.0.0
+    /// so that `migrate` still runs for them.
+    pub fn contract_version(env: Env) -> ContractVersion {
+        if let Some(version) = env.storage().instance().get(&SEMVER) {
+            return version;
+        }
+        match env.storage().instance().get::<_, u32>(&VERSION) {
+            Some(major) => ContractVersion {
+                major,
+                minor: 0,
+                patch: 0,
+            },
+            None => LEGACY_VERSION,
+        }
+    }
+
+    /// Replace the contract WASM, keeping all stored state.
+    ///
+    /// Callers should follow up with `migrate` once the new code is live.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(&env, &admin, "upgrade", "permission:SystemAdmin");
+        }
+
+        let version = Self::version(env.clone());
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::publish_upgraded(&env, admin, new_wasm_hash, version);
+
+        Ok(())
+    }
+
+    /// Run any pending data migrations and bump the stored version.
+    ///
+    /// Returns the version after migration; a no-op when already current.
+    pub fn migrate(env: Env, admin: Address) -> Result<ContractVersion, ContractError> {
+        admin.require_auth();
+
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(&env, &admin, "migrate", "permission:SystemAdmin");
+        }
+
+        let from_version = Self::contract_version(env.clone());
+        if from_version >= CONTRACT_VERSION {
+            return Ok(from_version);
+        }
+
+        // Per-version data migrations are applied here as the schema evolves.
+
+        env.storage()
+            .instance()
+            .set(&VERSION, &CONTRACT_VERSION.major);
+        env.storage().instance().set(&SEMVER, &CONTRACT_VERSION);
+        events::publish_migrated(&env, admin, from_version, CONTRACT_VERSION);
+
+        Ok(CONTRACT_VERSION)
+    }
+
     // ── Admin configuration ──────────────────────────────────────────────────
 
+    /// Set the storage TTL policy used when extending persistent and instance entries.
+    ///
+    /// Requires at least `ContractAdmin` tier. `extend_to` must exceed
+    /// `threshold` and stay within the network's maximum TTL.
+    pub fn set_ttl_policy(
+        env: Env,
+        caller: Address,
+        threshold: u32,
+        extend_to: u32,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if threshold == 0 || extend_to <= threshold || extend_to > env.storage().max_ttl() {
+            return Err(ContractError::InvalidInput);
+        }
+
+        ttl::set_policy(
+            &env,
+            &ttl::TtlPolicy {
+                threshold,
+                extend_to,
+            },
+        );
+        events::publish_ttl_policy_updated(&env, threshold, extend_to, caller);
+
+        Ok(())
+    }
+
+    /// Returns the storage TTL policy currently in effect.
+    pub fn get_ttl_policy(env: Env) -> ttl::TtlPolicy {
+        ttl::get_policy(&env)
+    }
+
     /// Configure per-address rate limiting for this contract.
     ///
     /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
@@ -763,53 +1302,215 @@ impl VisionRecordsContract {
         env.storage().instance().get(&RATE_CFG)
     }
 
-    /// Enables or disables whitelist enforcement globally.
-    ///
-    /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
-    pub fn set_whitelist_enabled(
+    /// Configure a sliding-window rate limit for a single operation
+    /// (e.g. "add_record"). Requires at least `ContractAdmin` tier.
+    pub fn set_operation_rate_limit(
         env: Env,
         caller: Address,
-        enabled: bool,
+        operation: String,
+        max_requests: u32,
+        window_seconds: u64,
     ) -> Result<(), ContractError> {
         caller.require_auth();
         if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
-            return Self::unauthorized(
-                &env,
-                &caller,
-                "set_whitelist_enabled",
-                "admin_tier:ContractAdmin",
-            );
+            return Err(ContractError::Unauthorized);
         }
-        whitelist::set_whitelist_enabled(&env, enabled);
-        Ok(())
-    }
 
-    /// Adds an address to the whitelist.
-    ///
-    /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
-    pub fn add_to_whitelist(env: Env, caller: Address, user: Address) -> Result<(), ContractError> {
-        caller.require_auth();
-        if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
-            return Self::unauthorized(
-                &env,
-                &caller,
-                "add_to_whitelist",
-                "admin_tier:ContractAdmin",
-            );
+        if max_requests == 0 || window_seconds == 0 {
+            return Err(ContractError::InvalidInput);
         }
-        whitelist::add_to_whitelist(&env, &user);
+
+        rate_limit::set_rate_limit_config(
+            &env,
+            &rate_limit::RateLimitConfig {
+                max_requests,
+                window_seconds,
+                operation: operation.clone(),
+            },
+        );
+
+        events::publish_rate_limit_config_updated(
+            &env,
+            operation,
+            max_requests,
+            window_seconds,
+            caller,
+        );
+
         Ok(())
     }
 
-    /// Removes an address from the whitelist.
-    ///
-    /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
-    pub fn remove_from_whitelist(
+    /// Return the rate limit configured for an operation, if any.
+    pub fn get_operation_rate_limit(
         env: Env,
-        caller: Address,
+        operation: String,
+    ) -> Option<rate_limit::RateLimitConfig> {
+        rate_limit::get_rate_limit_config(&env, &operation)
+    }
+
+    /// Give `user` its own budget for `operation`, consulted before the
+    /// operation's global limit. Requires at least `ContractAdmin` tier.
+    pub fn set_user_rate_limit(
+        env: Env,
+        admin: Address,
         user: Address,
+        operation: String,
+        max_requests: u32,
+        window_seconds: u64,
     ) -> Result<(), ContractError> {
-        caller.require_auth();
+        admin.require_auth();
+        if !admin_tiers::require_tier(&env, &admin, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if max_requests == 0 || window_seconds == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        rate_limit::set_user_rate_limit(
+            &env,
+            &user,
+            &rate_limit::RateLimitConfig {
+                max_requests,
+                window_seconds,
+                operation,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Return the per-user rate limit override for an operation, if any.
+    pub fn get_user_rate_limit(
+        env: Env,
+        user: Address,
+        operation: String,
+    ) -> Option<rate_limit::RateLimitConfig> {
+        rate_limit::get_user_rate_limit(&env, &user, &operation)
+    }
+
+    /// Clear `user`'s request history for `operation` so its next request
+    /// starts a fresh window. Requires `SystemAdmin`.
+    pub fn reset_rate_limit(
+        env: Env,
+        admin: Address,
+        user: Address,
+        operation: String,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(&env, &admin, "reset_rate_limit", "permission:SystemAdmin");
+        }
+
+        rate_limit::reset_rate_limit(&env, &user, &operation);
+        events::publish_rate_limit_reset(&env, user, operation, admin);
+
+        Ok(())
+    }
+
+    /// Return how much of its budget `user` has used for `operation` in the
+    /// current sliding window.
+    pub fn get_rate_limit_status(
+        env: Env,
+        user: Address,
+        operation: String,
+    ) -> Option<rate_limit::RateLimitStatus> {
+        rate_limit::get_rate_limit_status(&env, &user, &operation)
+    }
+
+    /// Exempt an address (e.g. a verified provider) from per-operation rate
+    /// limits. Requires at least `ContractAdmin` tier.
+    pub fn set_rate_limit_bypass(
+        env: Env,
+        caller: Address,
+        address: Address,
+        bypass: bool,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        rate_limit::set_rate_limit_bypass(&env, &address, bypass);
+        events::publish_rate_limit_bypass_updated(&env, address, bypass, caller);
+
+        Ok(())
+    }
+
+    /// Exempt every holder of the given roles from per-operation rate
+    /// limits, replacing any previous set. Requires at least `ContractAdmin`.
+    pub fn set_rate_limit_exempt_roles(
+        env: Env,
+        admin: Address,
+        roles: Vec<Role>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !admin_tiers::require_tier(&env, &admin, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        rate_limit::set_exempt_roles(&env, &roles);
+
+        Ok(())
+    }
+
+    /// Return the roles exempt from per-operation rate limits.
+    pub fn get_rate_limit_exempt_roles(env: Env) -> Vec<Role> {
+        rate_limit::get_exempt_roles(&env)
+    }
+
+    /// Whether an address is exempt from per-operation rate limits.
+    pub fn has_rate_limit_bypass(env: Env, address: Address) -> bool {
+        rate_limit::has_rate_limit_bypass(&env, &address)
+    }
+
+    /// Enables or disables whitelist enforcement globally.
+    ///
+    /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
+    pub fn set_whitelist_enabled(
+        env: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "set_whitelist_enabled",
+                "admin_tier:ContractAdmin",
+            );
+        }
+        whitelist::set_whitelist_enabled(&env, enabled);
+        Ok(())
+    }
+
+    /// Adds an address to the whitelist.
+    ///
+    /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
+    pub fn add_to_whitelist(env: Env, caller: Address, user: Address) -> Result<(), ContractError> {
+        caller.require_auth();
+        if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "add_to_whitelist",
+                "admin_tier:ContractAdmin",
+            );
+        }
+        whitelist::add_to_whitelist(&env, &user);
+        Ok(())
+    }
+
+    /// Removes an address from the whitelist.
+    ///
+    /// Requires at least `ContractAdmin` tier, or legacy admin/SystemAdmin.
+    pub fn remove_from_whitelist(
+        env: Env,
+        caller: Address,
+        user: Address,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
         if !admin_tiers::require_tier(&env, &caller, &AdminTier::ContractAdmin) {
             return Self::unauthorized(
                 &env,
@@ -896,6 +1597,102 @@ impl VisionRecordsContract {
         Ok(())
     }
 
+    /// Register several users in one call.
+    ///
+    /// The batch is atomic: every entry is validated, and no address may already
+    /// be registered or appear twice, before any user is written.
+    pub fn register_users(
+        env: Env,
+        caller: Address,
+        users: Vec<(Address, Role, String)>,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(
+            &env,
+            &circuit_breaker::PauseScope::Function(symbol_short!("REG_USR")),
+        )?;
+        caller.require_auth();
+
+        if users.is_empty() {
+            return Err(ContractError::InvalidInput);
+        }
+
+        if !whitelist::check_whitelist_access(&env, &caller) {
+            return Self::unauthorized(&env, &caller, "register_users", "whitelisted_caller");
+        }
+
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(&env, &caller, "register_users", "permission:ManageUsers");
+        }
+
+        let mut seen: Vec<Address> = Vec::new(&env);
+        for (user, _, name) in users.iter() {
+            validation::validate_name(&name)?;
+            let key = (symbol_short!("USER"), user.clone());
+            if seen.contains(&user) || env.storage().persistent().has(&key) {
+                return Err(ContractError::UserAlreadyExists);
+            }
+            seen.push_back(user);
+        }
+
+        let now = env.ledger().timestamp();
+        for (user, role, name) in users.iter() {
+            let user_data = User {
+                address: user.clone(),
+                role,
+                name: name.clone(),
+                registered_at: now,
+                is_active: true,
+            };
+
+            let key = (symbol_short!("USER"), user.clone());
+            env.storage().persistent().set(&key, &user_data);
+            extend_ttl_address_key(&env, &key);
+            rbac::assign_role(&env, user.clone(), role, 0);
+
+            events::publish_user_registered(&env, user, role, name);
+        }
+
+        events::publish_batch_users_registered(&env, caller, users.len());
+
+        Ok(())
+    }
+
+    /// Change the role of an already registered user.
+    ///
+    /// Updates both the stored `User` and the RBAC assignment while keeping
+    /// the original `registered_at`, and the assignment's custom grants,
+    /// revokes and expiry.
+    pub fn change_user_role(
+        env: Env,
+        caller: Address,
+        user: Address,
+        new_role: Role,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(&env, &caller, "change_user_role", "permission:ManageUsers");
+        }
+
+        let key = (symbol_short!("USER"), user.clone());
+        let mut user_data: User = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::UserNotFound)?;
+
+        let old_role = user_data.role;
+        user_data.role = new_role;
+        env.storage().persistent().set(&key, &user_data);
+        extend_ttl_address_key(&env, &key);
+        rbac::change_role(&env, user.clone(), new_role);
+
+        events::publish_role_changed(&env, user, old_role, new_role, caller);
+
+        Ok(())
+    }
+
     /// Get user information
     pub fn get_user(env: Env, user: Address) -> Result<User, ContractError> {
         let key = (symbol_short!("USER"), user.clone());
@@ -922,6 +1719,23 @@ impl VisionRecordsContract {
         }
     }
 
+    /// Get a user together with their effective role, ACL groups and the
+    /// active delegations they have received.
+    pub fn get_user_profile(env: Env, user: Address) -> Result<UserProfile, ContractError> {
+        let user_data = Self::get_user(env.clone(), user.clone())?;
+        let active_role = rbac::get_active_assignment(&env, &user)
+            .map(|assignment| assignment.role)
+            .unwrap_or(Role::None);
+
+        Ok(UserProfile {
+            user: user_data,
+            active_role,
+            groups: Self::get_user_groups(env.clone(), user.clone()),
+            incoming_delegations: rbac::get_incoming_delegations(&env, &user),
+            incoming_scoped_delegations: rbac::get_incoming_scoped_delegations(&env, &user),
+        })
+    }
+
     /// Add a vision record
     #[allow(clippy::arithmetic_side_effects)]
     pub fn add_record(
@@ -943,7 +1757,7 @@ impl VisionRecordsContract {
             return Self::unauthorized(&env, &caller, "add_record", "whitelisted_caller");
         }
 
-        Self::enforce_rate_limit(&env, &caller)?;
+        Self::enforce_rate_limit(&env, &caller, "add_record")?;
 
         validation::validate_data_hash(&data_hash)?;
 
@@ -992,32 +1806,17 @@ impl VisionRecordsContract {
             );
         }
 
+        if !provider::can_author_record_type(&env, &provider, &record_type) {
+            return Self::access_denied(&env, &caller, "add_record", "record_type_requirement");
+        }
+
         // Generate record ID
         let counter_key = symbol_short!("REC_CTR");
         let record_id: u64 = env.storage().instance().get(&counter_key).unwrap_or(0) + 1;
         env.storage().instance().set(&counter_key, &record_id);
 
-        // Determine current encryption key version (if any) and load master bytes
-        let current_version: Option<String> = env.storage().instance().get(&ENC_CUR);
-        let mut master_bytes: StdVec<u8> = StdVec::new();
-        if let Some(ver) = current_version.clone() {
-            if let Some(sv) = env
-                .storage()
-                .persistent()
-                .get::<(Symbol, String), String>(&(ENC_KEY, ver.clone()))
-            {
-                let hex = sv.to_string();
-                if let Some(bytes) = teye_common::hex_to_bytes(&hex) {
-                    master_bytes = bytes;
-                }
-            }
-        }
-        // Build KeyManager and encrypt the provided data_hash
-        let km = KeyManager::new(master_bytes);
-        let plaintext: StdString = data_hash.to_string();
-        let ciphertext = km.encrypt(None, &plaintext);
-        let stored_hash = String::from_str(&env, &ciphertext);
-        let key_version = current_version;
+        // Encrypt the provided data_hash under the current key version (if any)
+        let (stored_hash, key_version) = encrypt_data_hash(&env, &data_hash);
 
         let record = VisionRecord {
             id: record_id,
@@ -1034,21 +1833,16 @@ impl VisionRecordsContract {
         env.storage().persistent().set(&key, &record);
         extend_ttl_u64_key(&env, &key);
         teye_common::concurrency::init_record_version(&env, record_id, 0);
+        append_record_version(&env, &record, &caller);
 
         // Meter: write operation for the provider.
         Self::meter_op(&env, &provider, MeteringOpType::Write);
 
         // Add to patient's record list
-        let patient_key = (symbol_short!("PAT_REC"), patient.clone());
-        let mut patient_records: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&patient_key)
-            .unwrap_or(Vec::new(&env));
-        patient_records.push_back(record_id);
-        env.storage()
-            .persistent()
-            .set(&patient_key, &patient_records);
+        track_patient_record(&env, &patient, record_id);
+        count_patient_record(&env, &patient, &record_type);
+        track_provider_record(&env, &provider, record_id);
+        provider::record_created(&env, &provider);
 
         // Initialize OCC version tracking
         teye_common::concurrency::init_record_version(&env, record_id, 1);
@@ -1074,6 +1868,7 @@ impl VisionRecordsContract {
         provider: Address,
         records: Vec<BatchRecordInput>,
     ) -> Result<Vec<u64>, ContractError> {
+        let _guard = teye_common::ReentrancyGuard::new(&env);
         circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
         provider.require_auth();
 
@@ -1097,6 +1892,17 @@ impl VisionRecordsContract {
             );
         }
 
+        for input in records.iter() {
+            if !provider::can_author_record_type(&env, &provider, &input.record_type) {
+                return Self::access_denied(
+                    &env,
+                    &provider,
+                    "add_records",
+                    "record_type_requirement",
+                );
+            }
+        }
+
         let counter_key = symbol_short!("REC_CTR");
         let mut current_id: u64 = env.storage().instance().get(&counter_key).unwrap_or(0);
         let mut record_ids = Vec::new(&env);
@@ -1154,17 +1960,12 @@ impl VisionRecordsContract {
             let key = (symbol_short!("RECORD"), current_id);
             env.storage().persistent().set(&key, &record);
             teye_common::concurrency::init_record_version(&env, current_id, 0);
+            append_record_version(&env, &record, &provider);
 
-            let patient_key = (symbol_short!("PAT_REC"), input.patient.clone());
-            let mut patient_records: Vec<u64> = env
-                .storage()
-                .persistent()
-                .get(&patient_key)
-                .unwrap_or(Vec::new(&env));
-            patient_records.push_back(current_id);
-            env.storage()
-                .persistent()
-                .set(&patient_key, &patient_records);
+            track_patient_record(&env, &input.patient, current_id);
+            count_patient_record(&env, &input.patient, &input.record_type);
+            track_provider_record(&env, &provider, current_id);
+            provider::record_created(&env, &provider);
 
             events::publish_record_added(
                 &env,
@@ -1197,12 +1998,78 @@ impl VisionRecordsContract {
     }
 
     /// Get a vision record by ID.
+    ///
+    /// Consent and patient-level grants only count when they were given for
+    /// `Treatment`; use `get_record_for_purpose` for other purposes. Readers
+    /// other than the patient and author must use `get_record_with_reason`
+    /// for `Confidential` and `Restricted` records.
     pub fn get_record(
         env: Env,
         caller: Address,
         record_id: u64,
     ) -> Result<VisionRecord, ContractError> {
         caller.require_auth();
+        Self::read_record(env, caller, record_id, ConsentType::Treatment, None)
+    }
+
+    /// Get a vision record for a declared purpose.
+    ///
+    /// Same rules as `get_record`, except consent and patient-level grants
+    /// must have been given for `purpose`.
+    pub fn get_record_for_purpose(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        purpose: ConsentType,
+    ) -> Result<VisionRecord, ContractError> {
+        caller.require_auth();
+        Self::read_record(env, caller, record_id, purpose, None)
+    }
+
+    /// Get a vision record, stating why it is being read.
+    ///
+    /// Same rules as `get_record`. `Confidential` and `Restricted` records
+    /// require a non-empty reason, and every other read path refuses them
+    /// to anyone but the patient and author; whenever a reason is given it
+    /// is stored on the audit entry for the read.
+    pub fn get_record_with_reason(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        reason: String,
+    ) -> Result<VisionRecord, ContractError> {
+        caller.require_auth();
+
+        let sensitive = matches!(
+            rbac::get_record_sensitivity(&env, &record_id),
+            SensitivityLevel::Confidential | SensitivityLevel::Restricted
+        );
+        if sensitive || !reason.is_empty() {
+            validation::validate_access_reason(&reason)?;
+        }
+        let reason = if reason.is_empty() {
+            None
+        } else {
+            Some(reason)
+        };
+
+        Self::read_record(env, caller, record_id, ConsentType::Treatment, reason)
+    }
+
+    /// Access-checked, audited record read. Callers must have already
+    /// required `caller`'s auth.
+    ///
+    /// A denial writes its audit entry before returning `Err`, and a failed
+    /// invocation rolls back all of its writes and events. The denial is
+    /// therefore only persisted when the caller swallows the error, as
+    /// `get_records_checked` does.
+    fn read_record(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        purpose: ConsentType,
+        access_reason: Option<String>,
+    ) -> Result<VisionRecord, ContractError> {
         let key = (symbol_short!("RECORD"), record_id);
         match env.storage().persistent().get::<_, VisionRecord>(&key) {
             Some(record) => {
@@ -1215,21 +2082,45 @@ impl VisionRecordsContract {
                     // Check if caller has broad read permissions, active consent, or explicit grant
                     rbac::has_permission(&env, &caller, &Permission::ReadAnyRecord)
                         || rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
-                        || has_active_consent(&env, &record.patient, &caller)
-                        || {
-                            let access_level = Self::check_access(
-                                env.clone(),
-                                record.patient.clone(),
-                                caller.clone(),
-                            );
-                            access_level != AccessLevel::None
-                        }
+                        || (grant_scope_allows(&env, &record.patient, &caller, &record.record_type)
+                            && (has_active_consent_for(&env, &record.patient, &caller, &purpose)
+                                || Self::grant_level_for_purpose(
+                                    &env,
+                                    &record.patient,
+                                    &caller,
+                                    &purpose,
+                                ) != AccessLevel::None))
                         || Self::check_record_access(env.clone(), record_id, caller.clone())
                             != AccessLevel::None
+                        || rbac::policy_grants_access(
+                            &env,
+                            &caller,
+                            Some(record_id),
+                            Some(record.patient.clone()),
+                        )
                 };
 
                 if !has_access {
+                    // A lapsed grant is reported distinctly so clients can
+                    // prompt for renewal instead of a fresh request.
+                    if Self::held_expired_grant(&env, &record.patient, record_id, &caller) {
+                        let audit_entry = audit::create_audit_entry(
+                            &env,
+                            caller.clone(),
+                            record.patient.clone(),
+                            Some(record_id),
+                            AccessAction::Read,
+                            AccessResult::Expired,
+                            Some(String::from_str(&env, "grant expired")),
+                        );
+                        audit::add_audit_entry(&env, &audit_entry);
+                        events::publish_audit_log_entry(&env, &audit_entry);
+
+                        return Err(ContractError::AccessExpired);
+                    }
+
                     // Log failed access attempt
+                    let reason = Self::read_denial_reason(&env, &record, &caller);
                     let audit_entry = audit::create_audit_entry(
                         &env,
                         caller.clone(),
@@ -1237,7 +2128,7 @@ impl VisionRecordsContract {
                         Some(record_id),
                         AccessAction::Read,
                         AccessResult::Denied,
-                        Some(String::from_str(&env, "Insufficient permissions")),
+                        Some(String::from_str(&env, reason)),
                     );
                     audit::add_audit_entry(&env, &audit_entry);
                     events::publish_audit_log_entry(&env, &audit_entry);
@@ -1245,6 +2136,62 @@ impl VisionRecordsContract {
                     return Self::unauthorized(&env, &caller, "get_record", "record_read_access");
                 }
 
+                // Restricted records additionally require a professional
+                // credential from anyone other than the patient or author.
+                if caller != record.patient
+                    && caller != record.provider
+                    && rbac::get_record_sensitivity(&env, &record_id)
+                        == SensitivityLevel::Restricted
+                    && !matches!(
+                        rbac::get_user_credential(&env, &caller),
+                        CredentialType::MedicalLicense | CredentialType::AdminCredentials
+                    )
+                {
+                    let audit_entry = audit::create_audit_entry(
+                        &env,
+                        caller.clone(),
+                        record.patient.clone(),
+                        Some(record_id),
+                        AccessAction::Read,
+                        AccessResult::Denied,
+                        Some(String::from_str(&env, "sensitivity restricted")),
+                    );
+                    audit::add_audit_entry(&env, &audit_entry);
+                    events::publish_audit_log_entry(&env, &audit_entry);
+
+                    return Self::access_denied(
+                        &env,
+                        &caller,
+                        "get_record",
+                        "credential:MedicalLicense_or_AdminCredentials",
+                    );
+                }
+
+                // Confidential and Restricted records may only be read by
+                // others with a stated reason; see `get_record_with_reason`.
+                if caller != record.patient
+                    && caller != record.provider
+                    && access_reason.is_none()
+                    && matches!(
+                        rbac::get_record_sensitivity(&env, &record_id),
+                        SensitivityLevel::Confidential | SensitivityLevel::Restricted
+                    )
+                {
+                    let audit_entry = audit::create_audit_entry(
+                        &env,
+                        caller.clone(),
+                        record.patient.clone(),
+                        Some(record_id),
+                        AccessAction::Read,
+                        AccessResult::Denied,
+                        Some(String::from_str(&env, "access reason required")),
+                    );
+                    audit::add_audit_entry(&env, &audit_entry);
+                    events::publish_audit_log_entry(&env, &audit_entry);
+
+                    return Self::access_denied(&env, &caller, "get_record", "access_reason");
+                }
+
                 // Log successful access
                 let audit_entry = audit::create_audit_entry(
                     &env,
@@ -1253,42 +2200,29 @@ impl VisionRecordsContract {
                     Some(record_id),
                     AccessAction::Read,
                     AccessResult::Success,
-                    None,
+                    access_reason,
                 );
                 audit::add_audit_entry(&env, &audit_entry);
                 events::publish_audit_log_entry(&env, &audit_entry);
 
-                // Meter: read operation for the caller.
-                Self::meter_op(&env, &caller, MeteringOpType::Read);
-
-                // Decrypt data_hash for authorized caller before returning
-                let mut out_record = record.clone();
-                // Prefer record's key_version, fall back to current instance version
-                let key_ver = out_record
-                    .key_version
-                    .clone()
-                    .or_else(|| env.storage().instance().get(&ENC_CUR));
-                let mut master_bytes: StdVec<u8> = StdVec::new();
-                if let Some(ver) = key_ver {
-                    if let Some(sv) = env
-                        .storage()
-                        .persistent()
-                        .get::<(Symbol, String), String>(&(ENC_KEY, ver.clone()))
-                    {
-                        let hex = sv.to_string();
-                        if let Some(bytes) = teye_common::hex_to_bytes(&hex) {
-                            master_bytes = bytes;
-                        }
-                    }
+                if caller != record.patient
+                    && Self::get_access_notifications(env.clone(), record.patient.clone())
+                {
+                    events::publish_record_accessed(
+                        &env,
+                        record.patient.clone(),
+                        record_id,
+                        caller.clone(),
+                    );
                 }
 
-                if !master_bytes.is_empty() || out_record.key_version.is_none() {
-                    let km = KeyManager::new(master_bytes);
-                    let ciphertext = out_record.data_hash.to_string();
-                    if let Some(plain) = km.decrypt(None, &ciphertext) {
-                        out_record.data_hash = String::from_str(&env, &plain);
-                    }
-                }
+                // Meter: read operation for the caller.
+                Self::meter_op(&env, &caller, MeteringOpType::Read);
+
+                // Decrypt data_hash for authorized caller before returning
+                let mut out_record = record.clone();
+                out_record.data_hash =
+                    decrypt_data_hash(&env, &record.data_hash, &record.key_version);
 
                 Ok(out_record)
             }
@@ -1327,6 +2261,204 @@ impl VisionRecordsContract {
         }
     }
 
+    /// Replace the data hash of an existing record, keeping the previous
+    /// hash in the record's version history.
+    ///
+    /// Goes through the same OCC check as `update_examination_versioned`:
+    /// a stale `expected_version` is queued as a conflict and the record is
+    /// left unchanged.
+    pub fn update_record(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        expected_version: u64,
+        node_id: u32,
+        data_hash: String,
+    ) -> Result<UpdateOutcome, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        validation::validate_data_hash(&data_hash)?;
+        Self::enforce_rate_limit(&env, &caller, "update_record")?;
+
+        let key = (symbol_short!("RECORD"), record_id);
+        let mut record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::RecordNotFound)?;
+
+        let has_perm = if caller == record.provider {
+            rbac::has_permission(&env, &caller, &Permission::WriteRecord)
+        } else {
+            rbac::has_delegated_permission(
+                &env,
+                &record.provider,
+                &caller,
+                &Permission::WriteRecord,
+            )
+        };
+
+        if !has_perm && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "update_record",
+                "permission:WriteRecord_or_SystemAdmin",
+            );
+        }
+
+        let (stored_hash, key_version) = encrypt_data_hash(&env, &data_hash);
+        let mut changes = Vec::new(&env);
+        changes.push_back(FieldChange {
+            field_name: String::from_str(&env, "data_hash"),
+            old_hash: record.data_hash.clone(),
+            new_hash: stored_hash.clone(),
+        });
+        let outcome = teye_common::concurrency::compare_and_swap(
+            &env,
+            record_id,
+            expected_version,
+            node_id,
+            &caller,
+            &changes,
+        );
+        if let UpdateOutcome::Conflicted(_) = outcome {
+            return Ok(outcome);
+        }
+
+        record.data_hash = stored_hash;
+        record.key_version = key_version;
+        record.updated_at = env.ledger().timestamp();
+        env.storage().persistent().set(&key, &record);
+        extend_ttl_u64_key(&env, &key);
+        teye_common::concurrency::save_field_snapshot(&env, record_id, &changes);
+
+        let version = append_record_version(&env, &record, &caller);
+
+        let audit_entry = audit::create_audit_entry(
+            &env,
+            caller.clone(),
+            record.patient.clone(),
+            Some(record_id),
+            AccessAction::Write,
+            AccessResult::Success,
+            None,
+        );
+        audit::add_audit_entry(&env, &audit_entry);
+        events::publish_audit_log_entry(&env, &audit_entry);
+
+        Self::meter_op(&env, &caller, MeteringOpType::Write);
+        events::publish_record_updated(&env, record_id, record.patient, caller, version);
+
+        Ok(outcome)
+    }
+
+    /// Hand a record to another provider, e.g. when the patient changes
+    /// clinics.
+    ///
+    /// The new provider gains the author's update rights and the old one
+    /// loses them; any access grant the old provider holds is unaffected.
+    /// Callable by the current provider, the patient, or a system admin.
+    /// `new_provider` must be a verified provider holding `WriteRecord` who
+    /// meets any authoring requirement for the record's type.
+    pub fn transfer_record_custody(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        new_provider: Address,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        let key = (symbol_short!("RECORD"), record_id);
+        let mut record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::RecordNotFound)?;
+
+        let allowed = caller == record.provider
+            || caller == record.patient
+            || rbac::has_permission(&env, &caller, &Permission::SystemAdmin);
+        if !allowed {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "transfer_record_custody",
+                "provider_or_patient_or_SystemAdmin",
+            );
+        }
+        if new_provider == record.provider {
+            return Err(ContractError::InvalidInput);
+        }
+        // The custodian can read and update the record, so only a verified
+        // provider able to author this record type may take it over.
+        if !provider::is_verified_provider(&env, &new_provider)
+            || !rbac::has_permission(&env, &new_provider, &Permission::WriteRecord)
+        {
+            return Self::access_denied(
+                &env,
+                &caller,
+                "transfer_record_custody",
+                "new_provider:verified_with_WriteRecord",
+            );
+        }
+        if !provider::can_author_record_type(&env, &new_provider, &record.record_type) {
+            return Self::access_denied(
+                &env,
+                &caller,
+                "transfer_record_custody",
+                "record_type_requirement",
+            );
+        }
+
+        let old_provider = record.provider.clone();
+        record.provider = new_provider.clone();
+        record.updated_at = env.ledger().timestamp();
+        env.storage().persistent().set(&key, &record);
+        extend_ttl_u64_key(&env, &key);
+
+        untrack_provider_record(&env, &old_provider, record_id);
+        track_provider_record(&env, &new_provider, record_id);
+
+        let audit_entry = audit::create_audit_entry(
+            &env,
+            caller,
+            record.patient,
+            Some(record_id),
+            AccessAction::Write,
+            AccessResult::Success,
+            Some(String::from_str(&env, "CUSTODY_TRANSFER")),
+        );
+        audit::add_audit_entry(&env, &audit_entry);
+        events::publish_audit_log_entry(&env, &audit_entry);
+
+        Ok(())
+    }
+
+    /// Every data hash a record has held, oldest first.
+    /// Visible to anyone who may read the record itself.
+    pub fn get_record_versions(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+    ) -> Result<Vec<RecordVersion>, ContractError> {
+        Self::get_record(env.clone(), caller, record_id)?;
+
+        let stored: Vec<RecordVersion> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("REC_VER"), record_id))
+            .unwrap_or(Vec::new(&env));
+        let mut versions = Vec::new(&env);
+        for mut version in stored.iter() {
+            version.data_hash = decrypt_data_hash(&env, &version.data_hash, &version.key_version);
+            versions.push_back(version);
+        }
+        Ok(versions)
+    }
+
     /// Add eye examination details for an existing record
     #[allow(clippy::too_many_arguments)]
     pub fn add_eye_examination(
@@ -1592,7 +2724,8 @@ impl VisionRecordsContract {
         Ok(())
     }
 
-    /// Get all records for a patient
+    /// Get all records for a patient, as strictly ascending ids with no
+    /// duplicates.
     pub fn get_patient_records(env: Env, patient: Address) -> Vec<u64> {
         let key = (symbol_short!("PAT_REC"), patient);
         env.storage()
@@ -1601,7 +2734,143 @@ impl VisionRecordsContract {
             .unwrap_or(Vec::new(&env))
     }
 
+    /// How many records of each type a patient has, e.g. for a "3 exams,
+    /// 2 prescriptions" summary. Types with no records are omitted.
+    ///
+    /// Records created before per-type counting was introduced are only
+    /// included once `rebuild_record_type_counts` has been run for the
+    /// patient.
+    ///
+    /// Visible to the patient, holders of `ReadAnyRecord` or `SystemAdmin`,
+    /// and anyone with an access grant from the patient.
+    pub fn get_patient_record_type_counts(
+        env: Env,
+        caller: Address,
+        patient: Address,
+    ) -> Result<Vec<(RecordType, u32)>, ContractError> {
+        caller.require_auth();
+
+        let allowed = caller == patient
+            || rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+            || rbac::has_permission(&env, &caller, &Permission::ReadAnyRecord)
+            || Self::check_access(env.clone(), patient.clone(), caller.clone())
+                != AccessLevel::None;
+        if !allowed {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_patient_record_type_counts",
+                "patient_or_ReadAnyRecord_or_grant",
+            );
+        }
+
+        let counts: Map<RecordType, u32> = env
+            .storage()
+            .persistent()
+            .get(&record_type_counts_key(&patient))
+            .unwrap_or(Map::new(&env));
+        let mut out = Vec::new(&env);
+        for (record_type, count) in counts.iter() {
+            out.push_back((record_type, count));
+        }
+        Ok(out)
+    }
+
+    /// Recount a patient's records by type from their record index, e.g. to
+    /// include records created before per-type counting existed.
+    ///
+    /// Processes up to `limit` (capped at `MAX_RECOUNT_PAGE`) record ids
+    /// starting at index `offset`; a call with `offset == 0` starts the count
+    /// over. Call with increasing offsets until the returned count of
+    /// processed ids is below `limit`. Requires `SystemAdmin`.
+    pub fn rebuild_record_type_counts(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Result<u32, ContractError> {
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "rebuild_record_type_counts",
+                "permission:SystemAdmin",
+            );
+        }
+
+        let key = record_type_counts_key(&patient);
+        let mut counts: Map<RecordType, u32> = if offset == 0 {
+            Map::new(&env)
+        } else {
+            env.storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Map::new(&env))
+        };
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("PAT_REC"), patient.clone()))
+            .unwrap_or(Vec::new(&env));
+        let end = ids
+            .len()
+            .min(offset.saturating_add(limit.min(MAX_RECOUNT_PAGE)));
+        let mut processed = 0u32;
+        for i in offset..end {
+            if let Some(id) = ids.get(i) {
+                if let Some(record) = env
+                    .storage()
+                    .persistent()
+                    .get::<_, VisionRecord>(&(symbol_short!("RECORD"), id))
+                {
+                    let count = counts.get(record.record_type.clone()).unwrap_or(0);
+                    counts.set(record.record_type, count.saturating_add(1));
+                }
+            }
+            processed = processed.saturating_add(1);
+        }
+        env.storage().persistent().set(&key, &counts);
+        extend_ttl_address_key(&env, &key);
+        Ok(processed)
+    }
+
+    /// Get the caller's effective access level for a patient and, when access
+    /// is held, the patient's record ids in one call.
+    pub fn get_patient_access_summary(
+        env: Env,
+        caller: Address,
+        patient: Address,
+    ) -> AccessSummary {
+        caller.require_auth();
+
+        let level =
+            if caller == patient || rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+                AccessLevel::Full
+            } else if rbac::has_permission(&env, &caller, &Permission::ReadAnyRecord) {
+                AccessLevel::Read
+            } else {
+                Self::check_access(env.clone(), patient.clone(), caller)
+            };
+
+        let record_ids = if level == AccessLevel::None {
+            Vec::new(&env)
+        } else {
+            Self::get_patient_records(env.clone(), patient)
+        };
+
+        AccessSummary {
+            level,
+            record_count: record_ids.len(),
+            record_ids,
+        }
+    }
+
     /// Grant access to a user
+    ///
+    /// `consent_type` scopes the grant to one purpose: it only satisfies reads
+    /// made for that purpose (`get_record` reads for `Treatment`).
     #[allow(clippy::arithmetic_side_effects)]
     pub fn grant_access(
         env: Env,
@@ -1610,6 +2879,7 @@ impl VisionRecordsContract {
         grantee: Address,
         level: AccessLevel,
         duration_seconds: u64,
+        consent_type: ConsentType,
     ) -> Result<(), ContractError> {
         let _guard = teye_common::ReentrancyGuard::new(&env);
         circuit_breaker::require_not_paused(
@@ -1618,9 +2888,9 @@ impl VisionRecordsContract {
         )?;
         caller.require_auth();
 
-        Self::enforce_rate_limit(&env, &caller)?;
+        Self::enforce_rate_limit(&env, &caller, "grant_access")?;
 
-        validation::validate_duration(duration_seconds)?;
+        Self::validate_grant_duration(&env, duration_seconds)?;
 
         let has_perm = if caller == patient {
             true // Patient manages own access
@@ -1664,26 +2934,14 @@ impl VisionRecordsContract {
         let key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
         env.storage().persistent().set(&key, &grant);
         extend_ttl_access_key(&env, &key);
-
-        // Track the grantee address in the patient's grantee list for purge iteration.
-        let list_key = (symbol_short!("ACC_LST"), patient.clone());
-        let mut grantees: Vec<Address> = env
-            .storage()
+        // A fresh grant replaces any record-type scope from an earlier one.
+        env.storage()
             .persistent()
-            .get(&list_key)
-            .unwrap_or(Vec::new(&env));
-        // Avoid duplicates: only append if not already present.
-        let mut found = false;
-        for i in 0..grantees.len() {
-            if grantees.get(i) == Some(grantee.clone()) {
-                found = true;
-                break;
-            }
-        }
-        if !found {
-            grantees.push_back(grantee.clone());
-            env.storage().persistent().set(&list_key, &grantees);
-        }
+            .remove(&grant_scope_key(&patient, &grantee));
+        set_grant_purpose(&env, &patient, &grantee, consent_type);
+
+        // Track the grantee address in the patient's grantee list.
+        track_grantee(&env, &patient, &grantee);
 
         events::publish_access_granted(
             &env,
@@ -1711,1101 +2969,4017 @@ impl VisionRecordsContract {
         Ok(())
     }
 
-    /// Grant access to multiple users in a single transaction.
-    /// Patient authorizes once for the entire batch.
-    #[allow(clippy::arithmetic_side_effects)]
-    pub fn grant_access_batch(
+    /// `grant_access` for treatment using the patient's default grant
+    /// duration (see `get_default_grant_duration`).
+    pub fn grant_access_default(
         env: Env,
+        caller: Address,
         patient: Address,
-        grants: Vec<BatchGrantInput>,
+        grantee: Address,
+        level: AccessLevel,
     ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        patient.require_auth();
+        let duration_seconds = Self::get_default_grant_duration(env.clone(), patient.clone());
+        Self::grant_access(
+            env,
+            caller,
+            patient,
+            grantee,
+            level,
+            duration_seconds,
+            ConsentType::Treatment,
+        )
+    }
 
-        if grants.is_empty() {
-            return Err(ContractError::InvalidInput);
+    /// Set the duration used by `grant_access_default` for the patient's
+    /// grants. It must fall within the current grant duration limits; zero
+    /// clears it so the system default applies again.
+    pub fn set_default_grant_duration(
+        env: Env,
+        patient: Address,
+        seconds: u64,
+    ) -> Result<(), ContractError> {
+        patient.require_auth();
+        let key = default_grant_duration_key(&patient);
+        if seconds == 0 {
+            env.storage().persistent().remove(&key);
+            return Ok(());
         }
 
-        let now = env.ledger().timestamp();
-        for grant in grants.iter() {
-            let expires_at = now + grant.duration_seconds;
-            let access_grant = AccessGrant {
-                patient: patient.clone(),
-                grantee: grant.grantee.clone(),
-                level: grant.level.clone(),
-                granted_at: now,
-                expires_at,
-            };
-            let key = (
-                symbol_short!("ACCESS"),
-                patient.clone(),
-                grant.grantee.clone(),
-            );
-            env.storage().persistent().set(&key, &access_grant);
+        Self::validate_grant_duration(&env, seconds)?;
+        env.storage().persistent().set(&key, &seconds);
+        extend_ttl_address_key(&env, &key);
+        Ok(())
+    }
 
-            events::publish_access_granted(
-                &env,
-                patient.clone(),
-                grant.grantee.clone(),
-                grant.level.clone(),
-                grant.duration_seconds,
-                expires_at,
-            );
-        }
+    /// The patient's default grant duration in seconds, or
+    /// `validation::DEFAULT_GRANT_DURATION_SECONDS` if they haven't set one.
+    pub fn get_default_grant_duration(env: Env, patient: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&default_grant_duration_key(&patient))
+            .unwrap_or(validation::DEFAULT_GRANT_DURATION_SECONDS)
+    }
 
-        events::publish_batch_access_granted(&env, patient, grants.len());
+    /// `grant_access` limited to records whose type is in `record_types`,
+    /// e.g. prescriptions but not surgery notes. An empty list covers every
+    /// type. Record-level grants and role-wide read permissions are not
+    /// affected by the scope.
+    pub fn grant_typed_access(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        grantee: Address,
+        level: AccessLevel,
+        record_types: Vec<RecordType>,
+        duration_seconds: u64,
+    ) -> Result<(), ContractError> {
+        Self::grant_access(
+            env.clone(),
+            caller,
+            patient.clone(),
+            grantee.clone(),
+            level,
+            duration_seconds,
+            ConsentType::Treatment,
+        )?;
 
+        if !record_types.is_empty() {
+            let key = grant_scope_key(&patient, &grantee);
+            env.storage().persistent().set(&key, &record_types);
+            extend_ttl_access_key(&env, &key);
+        }
         Ok(())
     }
 
-    /// Check access level with ABAC policy evaluation
-    pub fn check_access(env: Env, patient: Address, grantee: Address) -> AccessLevel {
-        // First check traditional consent-based access
-        if !has_active_consent(&env, &patient, &grantee) {
-            return AccessLevel::None;
+    /// Record types a grantee's patient-level grant is limited to; empty
+    /// when the grant covers every type.
+    pub fn get_access_scope(env: Env, patient: Address, grantee: Address) -> Vec<RecordType> {
+        env.storage()
+            .persistent()
+            .get(&grant_scope_key(&patient, &grantee))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Opt in to (or out of) a `RecordAccessedEvent` each time someone
+    /// other than the patient reads one of their records. Off by default.
+    pub fn set_access_notifications(
+        env: Env,
+        patient: Address,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        patient.require_auth();
+        let key = access_notify_key(&patient);
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+            extend_ttl_address_key(&env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
         }
+        Ok(())
+    }
 
-        let key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
+    /// Whether `patient` has opted in to record access notifications.
+    pub fn get_access_notifications(env: Env, patient: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&access_notify_key(&patient))
+            .unwrap_or(false)
+    }
 
-        if let Some(grant) = env.storage().persistent().get::<_, AccessGrant>(&key) {
-            if grant.expires_at > env.ledger().timestamp() {
-                // Check if ABAC policies also allow this access
-                let abac_allowed =
-                    evaluate_access_policies(&env, &grantee, None, Some(patient.clone()));
-                if abac_allowed {
-                    return grant.level;
-                }
-            }
+    /// Bound the duration of every access grant, whether made directly,
+    /// in a batch, per record, through the two-phase flow or by approving
+    /// an access request. Both limits are inclusive and must fall within
+    /// `validation::MAX_DURATION_SECONDS`.
+    pub fn set_grant_duration_limits(
+        env: Env,
+        admin: Address,
+        min_seconds: u64,
+        max_seconds: u64,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !admin_tiers::require_tier(&env, &admin, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
         }
-        AccessLevel::None
+        if min_seconds == 0
+            || min_seconds > max_seconds
+            || max_seconds > validation::MAX_DURATION_SECONDS
+        {
+            return Err(ContractError::InvalidInput);
+        }
+
+        env.storage()
+            .instance()
+            .set(&GRANT_DURATION, &(min_seconds, max_seconds));
+        events::publish_grant_duration_limits_updated(&env, min_seconds, max_seconds, admin);
+        Ok(())
     }
 
-    /// Grant record-level access to a specific record.
-    #[allow(clippy::arithmetic_side_effects)]
-    pub fn grant_record_access(
+    /// Current `(min, max)` access-grant duration in seconds.
+    pub fn get_grant_duration_limits(env: Env) -> (u64, u64) {
+        env.storage().instance().get(&GRANT_DURATION).unwrap_or((
+            validation::MIN_DURATION_SECONDS,
+            validation::MAX_DURATION_SECONDS,
+        ))
+    }
+
+    fn validate_grant_duration(env: &Env, duration_seconds: u64) -> Result<(), ContractError> {
+        let (min, max) = Self::get_grant_duration_limits(env.clone());
+        validation::validate_duration_between(duration_seconds, min, max)
+    }
+
+    /// Ask a patient for access to their records.
+    ///
+    /// Nothing is granted until the patient approves the request with
+    /// `respond_to_access_request`. Only verified providers may ask, and a
+    /// patient holds at most `MAX_PENDING_ACCESS_REQUESTS` unanswered ones.
+    pub fn request_access(
         env: Env,
+        provider: Address,
         patient: Address,
-        grantee: Address,
-        record_id: u64,
         level: AccessLevel,
         duration_seconds: u64,
-    ) -> Result<(), ContractError> {
+    ) -> Result<u64, ContractError> {
         circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        patient.require_auth();
-        validation::validate_duration(duration_seconds)?;
+        provider.require_auth();
 
-        let record_key = (symbol_short!("RECORD"), record_id);
-        let record: VisionRecord = env
+        Self::enforce_operation_rate_limit(&env, &provider, "request_access")?;
+        Self::validate_grant_duration(&env, duration_seconds)?;
+
+        if provider == patient || level == AccessLevel::None {
+            return Err(ContractError::InvalidInput);
+        }
+        if !provider::is_verified_provider(&env, &provider) {
+            return Self::access_denied(&env, &provider, "request_access", "verified_provider");
+        }
+
+        let pending_key = (symbol_short!("AREQ_PND"), patient.clone());
+        let mut pending: Vec<u64> = env
             .storage()
             .persistent()
-            .get(&record_key)
-            .ok_or(ContractError::RecordNotFound)?;
-        if record.patient != patient {
-            return Self::unauthorized(&env, &patient, "grant_record_access", "record_owner");
+            .get(&pending_key)
+            .unwrap_or(Vec::new(&env));
+        if pending.len() >= MAX_PENDING_ACCESS_REQUESTS {
+            return Err(ContractError::TooManyPendingRequests);
         }
 
-        let now = env.ledger().timestamp();
-        let expires_at = now + duration_seconds;
-        let grant = AccessGrant {
+        let counter_key = symbol_short!("AREQ_CTR");
+        let request_id: u64 = env
+            .storage()
+            .instance()
+            .get(&counter_key)
+            .unwrap_or(0u64)
+            .saturating_add(1);
+        env.storage().instance().set(&counter_key, &request_id);
+
+        let request = AccessRequest {
+            id: request_id,
+            provider: provider.clone(),
             patient: patient.clone(),
-            grantee: grantee.clone(),
             level: level.clone(),
-            granted_at: now,
-            expires_at,
+            duration_seconds,
+            status: AccessRequestStatus::Pending,
+            requested_at: env.ledger().timestamp(),
+            responded_at: None,
         };
+        let key = (symbol_short!("ACC_REQ"), request_id);
+        env.storage().persistent().set(&key, &request);
+        extend_ttl_u64_key(&env, &key);
 
-        let key = (symbol_short!("REC_ACC"), record_id, grantee.clone());
-        env.storage().persistent().set(&key, &grant);
-        extend_ttl_record_access_key(&env, &key);
+        pending.push_back(request_id);
+        env.storage().persistent().set(&pending_key, &pending);
+        extend_ttl_address_key(&env, &pending_key);
 
-        events::publish_record_access_granted(
+        events::publish_access_requested(
             &env,
+            request_id,
+            provider,
             patient,
-            grantee,
-            record_id,
             level,
             duration_seconds,
-            expires_at,
         );
-        Ok(())
-    }
 
-    /// Check record-level access for a specific grantee.
-    pub fn check_record_access(env: Env, record_id: u64, grantee: Address) -> AccessLevel {
-        let key = (symbol_short!("REC_ACC"), record_id, grantee);
-        if let Some(grant) = env.storage().persistent().get::<_, AccessGrant>(&key) {
-            if grant.expires_at > env.ledger().timestamp() {
-                return grant.level;
-            }
-        }
-        AccessLevel::None
+        Ok(request_id)
     }
 
-    /// Revoke record-level access for a specific record.
-    pub fn revoke_record_access(
+    /// Approve or deny a pending access request.
+    ///
+    /// Approval stores a treatment-scoped access grant for the requesting
+    /// provider, starting now, exactly as if the patient had called
+    /// `grant_access`.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn respond_to_access_request(
         env: Env,
         patient: Address,
-        grantee: Address,
-        record_id: u64,
+        request_id: u64,
+        approve: bool,
     ) -> Result<(), ContractError> {
         circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
         patient.require_auth();
-        let record_key = (symbol_short!("RECORD"), record_id);
-        let record: VisionRecord = env
+
+        let key = (symbol_short!("ACC_REQ"), request_id);
+        let mut request: AccessRequest = env
             .storage()
             .persistent()
-            .get(&record_key)
-            .ok_or(ContractError::RecordNotFound)?;
-        if record.patient != patient {
-            return Self::unauthorized(&env, &patient, "revoke_record_access", "record_owner");
-        }
-
-        let key = (symbol_short!("REC_ACC"), record_id, grantee);
-        env.storage().persistent().remove(&key);
-        Ok(())
-    }
+            .get(&key)
+            .ok_or(ContractError::AccessRequestNotFound)?;
 
-    /// Grant consent for a grantee.
-    pub fn grant_consent(
-        env: Env,
-        patient: Address,
-        grantee: Address,
-        consent_type: ConsentType,
-        duration_seconds: u64,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        patient.require_auth();
-        if duration_seconds == 0 {
-            return Err(ContractError::InvalidInput);
+        if request.patient != patient {
+            return Self::unauthorized(&env, &patient, "respond_to_access_request", "patient");
+        }
+        if request.status != AccessRequestStatus::Pending {
+            return Err(ContractError::InvalidPhase);
         }
+        // The limits may have changed since the request was filed.
+        if approve {
+            Self::validate_grant_duration(&env, request.duration_seconds)?;
+        }
+
         let now = env.ledger().timestamp();
-        let consent = ConsentGrant {
-            patient: patient.clone(),
-            grantee: grantee.clone(),
-            consent_type: consent_type.clone(),
-            granted_at: now,
-            expires_at: now.saturating_add(duration_seconds),
-            revoked: false,
+        request.status = if approve {
+            AccessRequestStatus::Approved
+        } else {
+            AccessRequestStatus::Denied
         };
-        let key = consent_key(&patient, &grantee);
-        env.storage().persistent().set(&key, &consent);
-        extend_ttl_access_key(&env, &key);
-        events::publish_consent_granted(&env, patient, grantee, consent_type, consent.expires_at);
-        Ok(())
-    }
+        request.responded_at = Some(now);
+        env.storage().persistent().set(&key, &request);
+        extend_ttl_u64_key(&env, &key);
 
-    /// Revoke previously granted consent.
-    pub fn revoke_consent(
-        env: Env,
-        patient: Address,
-        grantee: Address,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        patient.require_auth();
-        let key = consent_key(&patient, &grantee);
-        if let Some(mut consent) = env.storage().persistent().get::<_, ConsentGrant>(&key) {
-            consent.revoked = true;
-            env.storage().persistent().set(&key, &consent);
+        let pending_key = (symbol_short!("AREQ_PND"), patient.clone());
+        let mut pending: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&pending_key)
+            .unwrap_or(Vec::new(&env));
+        if let Some(idx) = pending.first_index_of(request_id) {
+            pending.remove(idx);
+            env.storage().persistent().set(&pending_key, &pending);
         }
-        events::publish_consent_revoked(&env, patient, grantee);
-        Ok(())
-    }
 
-    /// Revoke access
-    pub fn revoke_access(
-        env: Env,
-        patient: Address,
-        grantee: Address,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(
-            &env,
-            &circuit_breaker::PauseScope::Function(symbol_short!("RVK_ACC")),
-        )?;
-        patient.require_auth();
+        if approve {
+            let expires_at = now + request.duration_seconds;
+            let grant = AccessGrant {
+                patient: patient.clone(),
+                grantee: request.provider.clone(),
+                level: request.level.clone(),
+                granted_at: now,
+                expires_at,
+            };
+            let grant_key = (
+                symbol_short!("ACCESS"),
+                patient.clone(),
+                request.provider.clone(),
+            );
+            env.storage().persistent().set(&grant_key, &grant);
+            extend_ttl_access_key(&env, &grant_key);
+            env.storage()
+                .persistent()
+                .remove(&grant_scope_key(&patient, &request.provider));
+            env.storage()
+                .persistent()
+                .remove(&grant_purpose_key(&patient, &request.provider));
+            track_grantee(&env, &patient, &request.provider);
 
-        let key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
-        env.storage().persistent().remove(&key);
+            events::publish_access_granted(
+                &env,
+                patient.clone(),
+                request.provider.clone(),
+                request.level.clone(),
+                request.duration_seconds,
+                expires_at,
+            );
+        }
 
-        // Log successful access revoke
-        let audit_entry = audit::create_audit_entry(
+        events::publish_access_request_responded(
             &env,
-            patient.clone(),
-            patient.clone(),
-            None,
-            AccessAction::RevokeAccess,
-            AccessResult::Success,
-            None,
+            request_id,
+            request.provider,
+            patient,
+            approve,
         );
-        audit::add_audit_entry(&env, &audit_entry);
-        events::publish_audit_log_entry(&env, &audit_entry);
 
         Ok(())
     }
 
-    /// Update emergency contact information
-    pub fn update_emergency_contact(
+    /// Access requests still awaiting the patient's decision, oldest first.
+    pub fn get_pending_access_requests(env: Env, patient: Address) -> Vec<AccessRequest> {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("AREQ_PND"), patient))
+            .unwrap_or(Vec::new(&env));
+        let mut requests = Vec::new(&env);
+        for id in ids.iter() {
+            if let Some(request) = env
+                .storage()
+                .persistent()
+                .get::<_, AccessRequest>(&(symbol_short!("ACC_REQ"), id))
+            {
+                requests.push_back(request);
+            }
+        }
+        requests
+    }
+
+    /// Grant access to multiple users in a single transaction.
+    /// Patient authorizes once for the entire batch.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn grant_access_batch(
         env: Env,
-        caller: Address,
         patient: Address,
-        contact: Option<EmergencyContact>,
+        grants: Vec<BatchGrantInput>,
     ) -> Result<(), ContractError> {
+        let _guard = teye_common::ReentrancyGuard::new(&env);
         circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        caller.require_auth();
+        patient.require_auth();
 
-        // Only profile owner can update
-        if caller != patient {
-            return Self::unauthorized(&env, &caller, "update_emergency_contact", "profile_owner");
+        Self::enforce_operation_rate_limit(&env, &patient, "grant_access_batch")?;
+
+        if grants.is_empty() {
+            return Err(ContractError::InvalidInput);
+        }
+        for grant in grants.iter() {
+            Self::validate_grant_duration(&env, grant.duration_seconds)?;
         }
 
-        let profile_key = (symbol_short!("PAT_PROF"), patient.clone());
-        let mut profile: PatientProfile = env
-            .storage()
-            .persistent()
-            .get(&profile_key)
-            .ok_or(ContractError::UserNotFound)?;
+        let now = env.ledger().timestamp();
+        for grant in grants.iter() {
+            let expires_at = now + grant.duration_seconds;
+            let access_grant = AccessGrant {
+                patient: patient.clone(),
+                grantee: grant.grantee.clone(),
+                level: grant.level.clone(),
+                granted_at: now,
+                expires_at,
+            };
+            let key = (
+                symbol_short!("ACCESS"),
+                patient.clone(),
+                grant.grantee.clone(),
+            );
+            env.storage().persistent().set(&key, &access_grant);
+            extend_ttl_access_key(&env, &key);
+            env.storage()
+                .persistent()
+                .remove(&grant_scope_key(&patient, &grant.grantee));
+            env.storage()
+                .persistent()
+                .remove(&grant_purpose_key(&patient, &grant.grantee));
+            track_grantee(&env, &patient, &grant.grantee);
 
-        profile.emergency_contact = match contact {
-            Some(c) => OptionalEmergencyContact::Some(c),
-            None => OptionalEmergencyContact::None,
-        };
-        profile.updated_at = env.ledger().timestamp();
+            events::publish_access_granted(
+                &env,
+                patient.clone(),
+                grant.grantee.clone(),
+                grant.level.clone(),
+                grant.duration_seconds,
+                expires_at,
+            );
+        }
 
-        env.storage().persistent().set(&profile_key, &profile);
-        events::publish_profile_updated(&env, patient);
+        events::publish_batch_access_granted(&env, patient, grants.len());
 
         Ok(())
     }
 
-    /// Update insurance information (hashed values only)
-    pub fn update_insurance(
-        env: Env,
-        caller: Address,
-        patient: Address,
-        insurance_info: Option<InsuranceInfo>,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        caller.require_auth();
+    /// Check access level with ABAC policy evaluation
+    pub fn check_access(env: Env, patient: Address, grantee: Address) -> AccessLevel {
+        // First check traditional consent-based access
+        if !has_active_consent(&env, &patient, &grantee) {
+            return AccessLevel::None;
+        }
 
-        // Only profile owner can update
-        if caller != patient {
-            return Self::unauthorized(&env, &caller, "update_insurance", "profile_owner");
+        let key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
+
+        if let Some(grant) = env.storage().persistent().get::<_, AccessGrant>(&key) {
+            if grant.expires_at > env.ledger().timestamp() {
+                // Check if ABAC policies also allow this access
+                let abac_allowed =
+                    evaluate_access_policies(&env, &grantee, None, Some(patient.clone()));
+                if abac_allowed {
+                    return grant.level;
+                }
+            }
         }
+        AccessLevel::None
+    }
 
-        let profile_key = (symbol_short!("PAT_PROF"), patient.clone());
-        let mut profile: PatientProfile = env
-            .storage()
-            .persistent()
-            .get(&profile_key)
-            .ok_or(ContractError::UserNotFound)?;
+    /// `check_access`, restricted to grants made for `purpose`.
+    fn grant_level_for_purpose(
+        env: &Env,
+        patient: &Address,
+        grantee: &Address,
+        purpose: &ConsentType,
+    ) -> AccessLevel {
+        if grant_purpose(env, patient, grantee) == *purpose {
+            Self::check_access(env.clone(), patient.clone(), grantee.clone())
+        } else {
+            AccessLevel::None
+        }
+    }
 
-        profile.insurance_info = match insurance_info {
-            Some(info) => OptionalInsuranceInfo::Some(info),
-            None => OptionalInsuranceInfo::None,
-        };
-        profile.updated_at = env.ledger().timestamp();
+    /// Why a read by `caller` that failed every access path was denied,
+    /// for the audit entry. Only called once no live grant applied; see
+    /// `read_record` for when the entry survives.
+    fn read_denial_reason(env: &Env, record: &VisionRecord, caller: &Address) -> &'static str {
+        if !grant_scope_allows(env, &record.patient, caller, &record.record_type) {
+            "record type outside grant scope"
+        } else if Self::check_access(env.clone(), record.patient.clone(), caller.clone())
+            != AccessLevel::None
+        {
+            "no consent for purpose"
+        } else {
+            "no grant"
+        }
+    }
 
-        env.storage().persistent().set(&profile_key, &profile);
-        events::publish_profile_updated(&env, patient);
+    /// Whether `grantee` holds a patient- or record-level grant that has
+    /// passed its expiry. Revoked grants are removed, so they don't count.
+    fn held_expired_grant(env: &Env, patient: &Address, record_id: u64, grantee: &Address) -> bool {
+        let now = env.ledger().timestamp();
+        let patient_key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
+        let record_key = (symbol_short!("REC_ACC"), record_id, grantee.clone());
+        let lapsed = |grant: Option<AccessGrant>| grant.is_some_and(|g| g.expires_at <= now);
+        lapsed(env.storage().persistent().get(&patient_key))
+            || lapsed(env.storage().persistent().get(&record_key))
+    }
 
-        Ok(())
+    /// Check access for several grantees at once.
+    ///
+    /// Levels are returned in input order using the same rules as `check_access`.
+    pub fn check_access_batch(
+        env: Env,
+        patient: Address,
+        grantees: Vec<Address>,
+    ) -> Vec<AccessLevel> {
+        let mut levels = Vec::new(&env);
+        for grantee in grantees.iter() {
+            levels.push_back(Self::check_access(env.clone(), patient.clone(), grantee));
+        }
+        levels
     }
 
-    /// Add medical history reference (IPFS hash or record ID)
-    pub fn add_medical_history_reference(
+    /// Get the stored access grant for a grantee, including its expiry.
+    ///
+    /// Unlike `check_access`, the grant is returned even if it has lapsed.
+    pub fn get_access_grant(env: Env, patient: Address, grantee: Address) -> Option<AccessGrant> {
+        let key = (symbol_short!("ACCESS"), patient, grantee);
+        env.storage().persistent().get(&key)
+    }
+
+    /// The purpose a patient-level grant was given for. Grants made without
+    /// one, including those stored before purposes existed, are `Treatment`.
+    pub fn get_access_grant_purpose(env: Env, patient: Address, grantee: Address) -> ConsentType {
+        grant_purpose(&env, &patient, &grantee)
+    }
+
+    /// List the currently active access grants for a patient.
+    ///
+    /// Expired or removed grants are pruned from the index as they are found.
+    pub fn get_patient_grantees(
         env: Env,
         caller: Address,
         patient: Address,
-        reference: String,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+    ) -> Result<Vec<AccessGrant>, ContractError> {
         caller.require_auth();
 
-        // Only profile owner can update
-        if caller != patient {
+        if caller != patient && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
             return Self::unauthorized(
                 &env,
                 &caller,
-                "add_medical_history_reference",
-                "profile_owner",
+                "get_patient_grantees",
+                "patient_or_SystemAdmin",
             );
         }
 
-        let profile_key = (symbol_short!("PAT_PROF"), patient.clone());
-        let mut profile: PatientProfile = env
+        let list_key = (symbol_short!("ACC_LST"), patient.clone());
+        let grantees: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&profile_key)
-            .ok_or(ContractError::UserNotFound)?;
-
-        profile.medical_history_refs.push_back(reference);
-        profile.updated_at = env.ledger().timestamp();
-
-        env.storage().persistent().set(&profile_key, &profile);
-        events::publish_profile_updated(&env, patient);
+            .get(&list_key)
+            .unwrap_or(Vec::new(&env));
 
-        Ok(())
-    }
+        let now = env.ledger().timestamp();
+        let mut active = Vec::new(&env);
+        let mut kept = Vec::new(&env);
+        for grantee in grantees.iter() {
+            let key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
+            if let Some(grant) = env.storage().persistent().get::<_, AccessGrant>(&key) {
+                if grant.expires_at > now {
+                    active.push_back(grant);
+                    kept.push_back(grantee);
+                }
+            }
+        }
 
-    /// Get patient profile
-    pub fn get_profile(env: Env, patient: Address) -> Result<PatientProfile, ContractError> {
-        let profile_key = (symbol_short!("PAT_PROF"), patient);
-        env.storage()
-            .persistent()
-            .get(&profile_key)
-            .ok_or(ContractError::UserNotFound)
-    }
+        if kept.len() != grantees.len() {
+            env.storage().persistent().set(&list_key, &kept);
+        }
 
-    /// Check if patient profile exists
-    pub fn profile_exists(env: Env, patient: Address) -> bool {
-        let profile_key = (symbol_short!("PAT_PROF"), patient);
-        env.storage().persistent().has(&profile_key)
+        Ok(active)
     }
 
-    /// Grants a custom permission to a user.
-    /// Requires the caller to have ManageUsers permission.
-    pub fn grant_custom_permission(
+    /// Who can currently read a record. `users` lists the patient, the
+    /// provider, active patient-level grantees and active emergency
+    /// responders with their access level. Role-based access is reported
+    /// once per role in `roles` rather than per user; use
+    /// `get_record_role_accessors` to page through individual holders of
+    /// `ReadAnyRecord`, including custom grants. Record-level grants are not
+    /// indexed and are not listed.
+    ///
+    /// Visible to the patient, the provider, or a system admin.
+    pub fn get_record_accessors(
         env: Env,
         caller: Address,
-        user: Address,
-        permission: Permission,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        record_id: u64,
+    ) -> Result<RecordAccessors, ContractError> {
         caller.require_auth();
-        // Unified check: covers direct role, custom grants, and delegated roles
-        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
-            return Self::unauthorized(
-                &env,
-                &caller,
-                "grant_custom_permission",
-                "permission:ManageUsers",
-            );
+        let record =
+            Self::record_for_accessor_query(&env, &caller, record_id, "get_record_accessors")?;
+
+        let mut users: Vec<(Address, AccessLevel)> = Vec::new(&env);
+        let mut seen: Vec<Address> = Vec::new(&env);
+        let mut add = |address: Address, level: AccessLevel| {
+            if !seen.contains(&address) {
+                seen.push_back(address.clone());
+                users.push_back((address, level));
+            }
+        };
+
+        add(record.patient.clone(), AccessLevel::Full);
+        add(record.provider.clone(), AccessLevel::Write);
+
+        let grantees: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("ACC_LST"), record.patient.clone()))
+            .unwrap_or(Vec::new(&env));
+        for grantee in grantees.iter() {
+            let level = Self::check_access(env.clone(), record.patient.clone(), grantee.clone());
+            if level != AccessLevel::None
+                && grant_scope_allows(&env, &record.patient, &grantee, &record.record_type)
+            {
+                add(grantee, level);
+            }
         }
-        rbac::grant_custom_permission(&env, user, permission)
-            .map_err(|_| ContractError::UserNotFound)?;
-        Ok(())
-    }
 
-    /// Revokes a custom permission from a user.
-    /// Requires the caller to have ManageUsers permission.
-    pub fn revoke_custom_permission(
-        env: Env,
-        caller: Address,
-        user: Address,
-        permission: Permission,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        caller.require_auth();
-        // Unified check: covers direct role, custom grants, and delegated roles
-        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
-            return Self::unauthorized(
-                &env,
-                &caller,
-                "revoke_custom_permission",
-                "permission:ManageUsers",
-            );
+        for access in emergency::get_patient_emergency_accesses(&env, &record.patient).iter() {
+            add(access.requester, AccessLevel::Read);
         }
-        rbac::revoke_custom_permission(&env, user, permission)
-            .map_err(|_| ContractError::UserNotFound)?;
-        Ok(())
-    }
 
-    /// Delegates a role to another user with an expiration timestamp.
-    /// The delegator must authenticate the transaction.
-    pub fn delegate_role(
-        env: Env,
-        delegator: Address,
-        delegatee: Address,
-        role: Role,
-        expires_at: u64,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        delegator.require_auth();
-        rbac::delegate_role(&env, delegator, delegatee, role, expires_at);
-        Ok(())
-    }
+        let mut roles = Vec::new(&env);
+        for role in [
+            Role::Patient,
+            Role::Staff,
+            Role::Optometrist,
+            Role::Ophthalmologist,
+            Role::Admin,
+        ] {
+            if rbac::get_base_permissions(&env, &role).contains(&Permission::ReadAnyRecord) {
+                roles.push_back(role);
+            }
+        }
 
-    /// Pauses contract operations for a given scope.
-    pub fn pause_contract(
-        env: Env,
-        caller: Address,
-        scope: circuit_breaker::PauseScope,
-    ) -> Result<(), ContractError> {
-        caller.require_auth();
-        circuit_breaker::pause_contract(&env, &caller, scope)
+        Ok(RecordAccessors { users, roles })
     }
 
-    /// Resumes contract operations for a given scope.
-    pub fn resume_contract(
+    /// One page of users holding `role` who can read any record, whether
+    /// through their role, a custom grant, a group or a delegation. Pages
+    /// follow `rbac::get_users_by_role`.
+    ///
+    /// Visible to the patient, the provider, or a system admin.
+    pub fn get_record_role_accessors(
         env: Env,
         caller: Address,
-        scope: circuit_breaker::PauseScope,
-    ) -> Result<(), ContractError> {
+        record_id: u64,
+        role: Role,
+        page: u32,
+    ) -> Result<Vec<Address>, ContractError> {
         caller.require_auth();
-        circuit_breaker::resume_contract(&env, &caller, scope)
-    }
+        Self::record_for_accessor_query(&env, &caller, record_id, "get_record_role_accessors")?;
 
-    /// Creates an ACL group.
-    pub fn create_acl_group(
-        env: Env,
-        caller: Address,
-        group_name: String,
-        permissions: Vec<Permission>,
-    ) -> Result<(), ContractError> {
-        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
-        caller.require_auth();
-        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
-            return Self::unauthorized(&env, &caller, "create_acl_group", "permission:ManageUsers");
+        let mut users = Vec::new(&env);
+        for user in rbac::get_users_by_role(&env, &role, page).iter() {
+            if rbac::has_permission(&env, &user, &Permission::ReadAnyRecord) {
+                users.push_back(user);
+            }
         }
-        rbac::create_group(&env, group_name, permissions);
-        Ok(())
+        Ok(users)
     }
 
-    /// Adds a user to an ACL group.
-    pub fn add_user_to_group(
-        env: Env,
-        caller: Address,
+    fn record_for_accessor_query(
+        env: &Env,
+        caller: &Address,
+        record_id: u64,
+        action: &str,
+    ) -> Result<VisionRecord, ContractError> {
+        let record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("RECORD"), record_id))
+            .ok_or(ContractError::RecordNotFound)?;
+        if caller != &record.patient
+            && caller != &record.provider
+            && !rbac::has_permission(env, caller, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(env, caller, action, "patient_or_provider_or_SystemAdmin");
+        }
+        Ok(record)
+    }
+
+    /// Grant record-level access to a specific record.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn grant_record_access(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+        record_id: u64,
+        level: AccessLevel,
+        duration_seconds: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        patient.require_auth();
+        Self::validate_grant_duration(&env, duration_seconds)?;
+
+        let record_key = (symbol_short!("RECORD"), record_id);
+        let record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&record_key)
+            .ok_or(ContractError::RecordNotFound)?;
+        if record.patient != patient {
+            return Self::unauthorized(&env, &patient, "grant_record_access", "record_owner");
+        }
+
+        let now = env.ledger().timestamp();
+        let expires_at = now + duration_seconds;
+        let grant = AccessGrant {
+            patient: patient.clone(),
+            grantee: grantee.clone(),
+            level: level.clone(),
+            granted_at: now,
+            expires_at,
+        };
+
+        let key = (symbol_short!("REC_ACC"), record_id, grantee.clone());
+        env.storage().persistent().set(&key, &grant);
+        extend_ttl_record_access_key(&env, &key);
+
+        events::publish_record_access_granted(
+            &env,
+            patient,
+            grantee,
+            record_id,
+            level,
+            duration_seconds,
+            expires_at,
+        );
+        Ok(())
+    }
+
+    /// Check record-level access for a specific grantee.
+    pub fn check_record_access(env: Env, record_id: u64, grantee: Address) -> AccessLevel {
+        let key = (symbol_short!("REC_ACC"), record_id, grantee);
+        if let Some(grant) = env.storage().persistent().get::<_, AccessGrant>(&key) {
+            if grant.expires_at > env.ledger().timestamp() {
+                return grant.level;
+            }
+        }
+        AccessLevel::None
+    }
+
+    /// Revoke record-level access for a specific record.
+    pub fn revoke_record_access(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+        record_id: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        patient.require_auth();
+        let record_key = (symbol_short!("RECORD"), record_id);
+        let record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&record_key)
+            .ok_or(ContractError::RecordNotFound)?;
+        if record.patient != patient {
+            return Self::unauthorized(&env, &patient, "revoke_record_access", "record_owner");
+        }
+
+        let key = (symbol_short!("REC_ACC"), record_id, grantee);
+        env.storage().persistent().remove(&key);
+        Ok(())
+    }
+
+    /// Grant consent for a grantee.
+    pub fn grant_consent(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+        consent_type: ConsentType,
+        duration_seconds: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        patient.require_auth();
+        if duration_seconds == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+        let now = env.ledger().timestamp();
+        let consent = ConsentGrant {
+            patient: patient.clone(),
+            grantee: grantee.clone(),
+            consent_type: consent_type.clone(),
+            granted_at: now,
+            expires_at: now.saturating_add(duration_seconds),
+            revoked: false,
+        };
+        let key = consent_key(&patient, &grantee);
+        env.storage().persistent().set(&key, &consent);
+        extend_ttl_access_key(&env, &key);
+        events::publish_consent_granted(&env, patient, grantee, consent_type, consent.expires_at);
+        Ok(())
+    }
+
+    /// Revoke previously granted consent.
+    pub fn revoke_consent(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        patient.require_auth();
+        let key = consent_key(&patient, &grantee);
+        if let Some(mut consent) = env.storage().persistent().get::<_, ConsentGrant>(&key) {
+            consent.revoked = true;
+            env.storage().persistent().set(&key, &consent);
+        }
+        events::publish_consent_revoked(&env, patient, grantee);
+        Ok(())
+    }
+
+    /// Revoke access
+    pub fn revoke_access(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(
+            &env,
+            &circuit_breaker::PauseScope::Function(symbol_short!("RVK_ACC")),
+        )?;
+        patient.require_auth();
+
+        let key = (symbol_short!("ACCESS"), patient.clone(), grantee.clone());
+        env.storage().persistent().remove(&key);
+        env.storage()
+            .persistent()
+            .remove(&grant_scope_key(&patient, &grantee));
+        env.storage()
+            .persistent()
+            .remove(&grant_purpose_key(&patient, &grantee));
+        untrack_grantee(&env, &patient, &grantee);
+
+        // Log successful access revoke
+        let audit_entry = audit::create_audit_entry(
+            &env,
+            patient.clone(),
+            patient.clone(),
+            None,
+            AccessAction::RevokeAccess,
+            AccessResult::Success,
+            None,
+        );
+        audit::add_audit_entry(&env, &audit_entry);
+        events::publish_audit_log_entry(&env, &audit_entry);
+
+        Ok(())
+    }
+
+    /// Update emergency contact information
+    pub fn update_emergency_contact(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        contact: Option<EmergencyContact>,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        // Only profile owner can update
+        if caller != patient {
+            return Self::unauthorized(&env, &caller, "update_emergency_contact", "profile_owner");
+        }
+
+        let profile_key = (symbol_short!("PAT_PROF"), patient.clone());
+        let mut profile: PatientProfile = env
+            .storage()
+            .persistent()
+            .get(&profile_key)
+            .ok_or(ContractError::UserNotFound)?;
+
+        profile.emergency_contact = match contact {
+            Some(c) => OptionalEmergencyContact::Some(c),
+            None => OptionalEmergencyContact::None,
+        };
+        profile.updated_at = env.ledger().timestamp();
+
+        env.storage().persistent().set(&profile_key, &profile);
+        events::publish_profile_updated(&env, patient);
+
+        Ok(())
+    }
+
+    /// Update insurance information (hashed values only)
+    pub fn update_insurance(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        insurance_info: Option<InsuranceInfo>,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        // Only profile owner can update
+        if caller != patient {
+            return Self::unauthorized(&env, &caller, "update_insurance", "profile_owner");
+        }
+
+        let profile_key = (symbol_short!("PAT_PROF"), patient.clone());
+        let mut profile: PatientProfile = env
+            .storage()
+            .persistent()
+            .get(&profile_key)
+            .ok_or(ContractError::UserNotFound)?;
+
+        profile.insurance_info = match insurance_info {
+            Some(info) => OptionalInsuranceInfo::Some(info),
+            None => OptionalInsuranceInfo::None,
+        };
+        profile.updated_at = env.ledger().timestamp();
+
+        env.storage().persistent().set(&profile_key, &profile);
+        events::publish_profile_updated(&env, patient);
+
+        Ok(())
+    }
+
+    /// Add medical history reference (IPFS hash or record ID)
+    pub fn add_medical_history_reference(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        reference: String,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        // Only profile owner can update
+        if caller != patient {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "add_medical_history_reference",
+                "profile_owner",
+            );
+        }
+
+        let profile_key = (symbol_short!("PAT_PROF"), patient.clone());
+        let mut profile: PatientProfile = env
+            .storage()
+            .persistent()
+            .get(&profile_key)
+            .ok_or(ContractError::UserNotFound)?;
+
+        profile.medical_history_refs.push_back(reference);
+        profile.updated_at = env.ledger().timestamp();
+
+        env.storage().persistent().set(&profile_key, &profile);
+        events::publish_profile_updated(&env, patient);
+
+        Ok(())
+    }
+
+    /// Get patient profile
+    pub fn get_profile(env: Env, patient: Address) -> Result<PatientProfile, ContractError> {
+        let profile_key = (symbol_short!("PAT_PROF"), patient);
+        env.storage()
+            .persistent()
+            .get(&profile_key)
+            .ok_or(ContractError::UserNotFound)
+    }
+
+    /// Check if patient profile exists
+    pub fn profile_exists(env: Env, patient: Address) -> bool {
+        let profile_key = (symbol_short!("PAT_PROF"), patient);
+        env.storage().persistent().has(&profile_key)
+    }
+
+    /// Grants a custom permission to a user.
+    /// Requires the caller to have ManageUsers permission.
+    pub fn grant_custom_permission(
+        env: Env,
+        caller: Address,
+        user: Address,
+        permission: Permission,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        // Unified check: covers direct role, custom grants, and delegated roles
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "grant_custom_permission",
+                "permission:ManageUsers",
+            );
+        }
+        rbac::grant_custom_permission(&env, user.clone(), permission.clone())
+            .map_err(|_| ContractError::UserNotFound)?;
+        events::publish_permission_granted(&env, user, permission, caller);
+        Ok(())
+    }
+
+    /// Revokes a custom permission from a user.
+    /// Requires the caller to have ManageUsers permission.
+    pub fn revoke_custom_permission(
+        env: Env,
+        caller: Address,
+        user: Address,
+        permission: Permission,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        // Unified check: covers direct role, custom grants, and delegated roles
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "revoke_custom_permission",
+                "permission:ManageUsers",
+            );
+        }
+        rbac::revoke_custom_permission(&env, user.clone(), permission.clone())
+            .map_err(|_| ContractError::UserNotFound)?;
+        events::publish_permission_revoked(&env, user, permission, caller);
+        Ok(())
+    }
+
+    /// Revokes every role and custom permission a user holds, e.g. when
+    /// offboarding staff ahead of full deactivation.
+    pub fn revoke_all_custom_permissions(
+        env: Env,
+        caller: Address,
+        user: Address,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "revoke_all_custom_permissions",
+                "permission:ManageUsers",
+            );
+        }
+        let revoked = rbac::revoke_all_custom_permissions(&env, user.clone())
+            .map_err(|_| ContractError::UserNotFound)?;
+        events::publish_all_permissions_revoked(&env, user, revoked, caller);
+        Ok(())
+    }
+
+    /// Delegates a role to another user with an expiration timestamp.
+    /// The delegator must authenticate the transaction.
+    pub fn delegate_role(
+        env: Env,
+        delegator: Address,
+        delegatee: Address,
+        role: Role,
+        expires_at: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        delegator.require_auth();
+        rbac::delegate_role(&env, delegator.clone(), delegatee.clone(), role, expires_at);
+        events::publish_role_delegated(&env, delegator, delegatee, role, expires_at);
+        Ok(())
+    }
+
+    /// Withdraws a role delegation made with `delegate_role`.
+    /// The delegator must authenticate the transaction.
+    pub fn revoke_delegation(
+        env: Env,
+        delegator: Address,
+        delegatee: Address,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        delegator.require_auth();
+        if !rbac::revoke_delegation(&env, &delegator, &delegatee) {
+            return Err(ContractError::DelegationNotFound);
+        }
+        events::publish_role_delegation_revoked(&env, delegator, delegatee);
+        Ok(())
+    }
+
+    /// Active role delegations `delegator` has made, so they can review and
+    /// revoke them.
+    pub fn get_delegations_from(env: Env, delegator: Address) -> Vec<Delegation> {
+        rbac::get_outgoing_delegations(&env, &delegator)
+    }
+
+    /// Active role delegations `delegatee` has received, i.e. whom they can
+    /// currently act on behalf of.
+    pub fn get_delegations_to(env: Env, delegatee: Address) -> Vec<Delegation> {
+        rbac::get_incoming_delegations(&env, &delegatee)
+    }
+
+    /// Active scoped permission delegations `delegatee` has received.
+    pub fn get_scoped_delegations_to(env: Env, delegatee: Address) -> Vec<ScopedDelegation> {
+        rbac::get_incoming_scoped_delegations(&env, &delegatee)
+    }
+
+    /// Enables or disables transitive delegation, letting chains such as
+    /// A→B→C satisfy delegated-permission checks (up to
+    /// `rbac::MAX_DELEGATION_HOPS` links). Disabled by default.
+    pub fn set_transitive_delegation(
+        env: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "set_transitive_delegation",
+                "permission:SystemAdmin",
+            );
+        }
+        rbac::set_transitive_delegation(&env, enabled);
+        Ok(())
+    }
+
+    /// Pauses contract operations for a given scope.
+    pub fn pause_contract(
+        env: Env,
+        caller: Address,
+        scope: circuit_breaker::PauseScope,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        circuit_breaker::pause_contract(&env, &caller, scope)
+    }
+
+    /// Resumes contract operations for a given scope.
+    pub fn resume_contract(
+        env: Env,
+        caller: Address,
+        scope: circuit_breaker::PauseScope,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        circuit_breaker::resume_contract(&env, &caller, scope)
+    }
+
+    /// Creates an ACL group.
+    pub fn create_acl_group(
+        env: Env,
+        caller: Address,
+        group_name: String,
+        permissions: Vec<Permission>,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(&env, &caller, "create_acl_group", "permission:ManageUsers");
+        }
+        rbac::create_group(&env, group_name, permissions);
+        Ok(())
+    }
+
+    /// Adds a user to an ACL group.
+    pub fn add_user_to_group(
+        env: Env,
+        caller: Address,
+        user: Address,
+        group_name: String,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "add_user_to_group",
+                "permission:ManageUsers",
+            );
+        }
+        rbac::add_to_group(&env, user, group_name).map_err(|_| ContractError::InvalidInput)
+    }
+
+    /// Removes a user from an ACL group.
+    pub fn remove_user_from_group(
+        env: Env,
+        caller: Address,
+        user: Address,
+        group_name: String,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "remove_user_from_group",
+                "permission:ManageUsers",
+            );
+        }
+        rbac::remove_from_group(&env, user, group_name);
+        Ok(())
+    }
+
+    /// Returns all ACL groups assigned to a user.
+    pub fn get_user_groups(env: Env, user: Address) -> Vec<String> {
+        env.storage()
+            .persistent()
+            .get(&rbac::user_groups_key(&user))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Checks if a user has a specific permission.
+    /// Returns true if the user has the permission, false otherwise.
+    pub fn check_permission(env: Env, user: Address, permission: Permission) -> bool {
+        rbac::has_permission(&env, &user, &permission)
+    }
+
+    /// Lists one page of the users currently assigned `role`. Pages hold at
+    /// most `ROLE_INDEX_PAGE_SIZE` users; see `get_role_page_count`.
+    pub fn get_users_by_role(
+        env: Env,
+        caller: Address,
+        role: Role,
+        page: u32,
+    ) -> Result<Vec<Address>, ContractError> {
+        caller.require_auth();
+
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_users_by_role",
+                "permission:ManageUsers",
+            );
+        }
+
+        Ok(rbac::get_users_by_role(&env, &role, page))
+    }
+
+    /// Returns how many pages `get_users_by_role` has for `role`.
+    pub fn get_role_page_count(env: Env, role: Role) -> u32 {
+        rbac::get_role_page_count(&env, &role)
+    }
+
+    /// Returns a user's role assignment, including custom grants/revokes and
+    /// expiry, or `None` if they have none or it has expired.
+    pub fn get_role_assignment(env: Env, user: Address) -> Option<rbac::RoleAssignment> {
+        rbac::get_active_assignment(&env, &user)
+    }
+
+    /// Deletes the role assignments of any of `users` that have expired and
+    /// returns how many were removed.
+    pub fn cleanup_expired_assignments(
+        env: Env,
+        admin: Address,
+        users: Vec<Address>,
+    ) -> Result<u32, ContractError> {
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &admin,
+                "cleanup_expired_assignments",
+                "permission:ManageUsers",
+            );
+        }
+
+        let mut removed: u32 = 0;
+        for user in users.iter() {
+            if rbac::remove_expired_assignment(&env, &user) {
+                removed = removed.saturating_add(1);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Extends a user's role assignment to `expires_at` (0 for never) in one
+    /// call, without re-registering them.
+    ///
+    /// Holders of `ManageUsers` may renew anyone, including lapsed
+    /// assignments that have not been cleaned up yet. Users may renew their
+    /// own assignment while it is still active, to a finite expiry at most
+    /// `MAX_SELF_RENEWAL_SECONDS` from now.
+    pub fn renew_assignment(
+        env: Env,
+        caller: Address,
+        user: Address,
+        expires_at: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+        let now = env.ledger().timestamp();
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            if caller != user || rbac::get_active_assignment(&env, &user).is_none() {
+                return Self::unauthorized(
+                    &env,
+                    &caller,
+                    "renew_assignment",
+                    "active_self_or_permission:ManageUsers",
+                );
+            }
+            if expires_at == 0 || expires_at > now.saturating_add(MAX_SELF_RENEWAL_SECONDS) {
+                return Err(ContractError::InvalidInput);
+            }
+        }
+        if expires_at != 0 && expires_at <= now {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let role = rbac::renew_assignment(&env, &user, expires_at)
+            .map_err(|_| ContractError::UserNotFound)?;
+        events::publish_assignment_renewed(&env, user, role, expires_at, caller);
+        Ok(())
+    }
+
+    /// Checks if a user's active role ranks at or above `min_role`.
+    pub fn role_at_least(env: Env, user: Address, min_role: Role) -> bool {
+        rbac::role_at_least(&env, &user, &min_role)
+    }
+
+    /// Create an access policy with ABAC attributes
+    pub fn create_access_policy(
+        env: Env,
+        caller: Address,
+        policy_id: String,
+        name: String,
+        required_role: Role,
+        time_restriction: TimeRestriction,
+        required_credential: CredentialType,
+        min_sensitivity_level: SensitivityLevel,
+        consent_required: bool,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        // Only SystemAdmin can create policies
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let conditions = rbac::PolicyConditions {
+            required_role,
+            time_restriction,
+            required_credential,
+            min_sensitivity_level,
+            consent_required,
+        };
+
+        let policy = rbac::AccessPolicy {
+            id: policy_id.clone(),
+            name,
+            conditions,
+            enabled: true,
+        };
+
+        rbac::create_access_policy(&env, policy);
+        events::publish_policy_created(&env, policy_id, caller);
+
+        Ok(())
+    }
+
+    /// Enable or disable an existing access policy
+    pub fn set_policy_enabled(
+        env: Env,
+        caller: Address,
+        policy_id: String,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if !rbac::set_policy_enabled(&env, &policy_id, enabled) {
+            return Err(ContractError::RecordNotFound);
+        }
+        events::publish_policy_enabled(&env, policy_id, enabled, caller);
+
+        Ok(())
+    }
+
+    /// Set which access policies are evaluated on every access check.
+    /// Ids without a stored policy are skipped at evaluation time.
+    pub fn set_active_policies(
+        env: Env,
+        admin: Address,
+        ids: Vec<String>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+        if ids.len() > rbac::MAX_ACTIVE_POLICIES {
+            return Err(ContractError::InvalidInput);
+        }
+
+        rbac::set_active_policies(&env, &ids);
+        Ok(())
+    }
+
+    /// Get the policy ids evaluated on every access check
+    pub fn get_active_policies(env: Env) -> Vec<String> {
+        rbac::get_active_policies(&env)
+    }
+
+    /// Get an access policy by ID
+    pub fn get_access_policy(env: Env, policy_id: String) -> Option<AccessPolicy> {
+        rbac::get_access_policy(&env, &policy_id)
+    }
+
+    /// Set credential type for a user
+    pub fn set_user_credential(
+        env: Env,
+        caller: Address,
+        user: Address,
+        credential: CredentialType,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        // Only SystemAdmin can set credentials
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        rbac::set_user_credential(&env, user.clone(), credential);
+        events::publish_credential_set(&env, user, credential, caller);
+
+        Ok(())
+    }
+
+    /// Set sensitivity level for a record
+    pub fn set_record_sensitivity(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        sensitivity: SensitivityLevel,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        // Only record provider or SystemAdmin can set sensitivity
+        let record_key = (symbol_short!("RECORD"), record_id);
+        let record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&record_key)
+            .ok_or(ContractError::RecordNotFound)?;
+
+        let has_perm = caller == record.provider
+            || rbac::has_permission(&env, &caller, &Permission::SystemAdmin);
+        if !has_perm {
+            return Err(ContractError::Unauthorized);
+        }
+
+        rbac::set_record_sensitivity(&env, record_id, sensitivity);
+        events::publish_sensitivity_set(&env, record_id, sensitivity, caller);
+
+        Ok(())
+    }
+
+    /// Get the credential type held by a user
+    pub fn get_user_credential(env: Env, user: Address) -> CredentialType {
+        rbac::get_user_credential(&env, &user)
+    }
+
+    /// Get the sensitivity level of a record (defaults to `Standard`)
+    pub fn get_record_sensitivity(env: Env, record_id: u64) -> SensitivityLevel {
+        rbac::get_record_sensitivity(&env, &record_id)
+    }
+
+    /// Check access for a specific record with ABAC evaluation
+    /// Prepare phase for register_user operation
+    pub fn prepare_register_user(
+        env: Env,
+        caller: Address,
         user: Address,
-        group_name: String,
+        role: Role,
+        name: String,
+    ) -> Result<(), ContractError> {
+        // Validate all inputs without making state changes
+        circuit_breaker::require_not_paused(
+            &env,
+            &circuit_breaker::PauseScope::Function(symbol_short!("REG_USR")),
+        )?;
+
+        if !whitelist::check_whitelist_access(&env, &caller) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        validation::validate_name(&name)?;
+
+        // Check if user already exists
+        let key = (symbol_short!("USER"), user.clone());
+        if env.storage().persistent().get::<_, User>(&key).is_some() {
+            return Err(ContractError::UserAlreadyExists);
+        }
+
+        // Store temporary preparation data
+        let prep_key = (symbol_short!("P_REG_USR"), user.clone());
+        let prep_data = PrepareUserRegistration {
+            caller: caller.clone(),
+            user: user.clone(),
+            role,
+            name: name.clone(),
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().temporary().set(&prep_key, &prep_data);
+
+        Ok(())
+    }
+
+    /// Commit phase for register_user operation
+    pub fn commit_register_user(
+        env: Env,
+        caller: Address,
+        user: Address,
+        role: Role,
+        name: String,
+    ) -> Result<(), ContractError> {
+        // Retrieve preparation data
+        let prep_key = (symbol_short!("P_REG_USR"), user.clone());
+        let prep_data: PrepareUserRegistration = env
+            .storage()
+            .temporary()
+            .get(&prep_key)
+            .ok_or(ContractError::InvalidPhase)?;
+
+        // Verify preparation data matches commit parameters
+        if prep_data.caller != caller
+            || prep_data.user != user
+            || prep_data.role != role
+            || prep_data.name != name
+        {
+            return Err(ContractError::InvalidPhase);
+        }
+
+        // Execute the actual registration
+        let user_data = User {
+            address: user.clone(),
+            role,
+            name: name.clone(),
+            registered_at: env.ledger().timestamp(),
+            is_active: true,
+        };
+
+        let key = (symbol_short!("USER"), user.clone());
+        env.storage().persistent().set(&key, &user_data);
+        extend_ttl_address_key(&env, &key);
+        rbac::assign_role(&env, user.clone(), role, 0);
+
+        // Clean up preparation data
+        env.storage().temporary().remove(&prep_key);
+
+        events::publish_user_registered(&env, user, role, name);
+
+        Ok(())
+    }
+
+    /// Rollback for register_user operation
+    pub fn rollback_register_user(
+        env: Env,
+        user: Address,
+        _role: Role,
+        _name: String,
+    ) -> Result<(), ContractError> {
+        // Clean up preparation data
+        let prep_key = (symbol_short!("P_REG_USR"), user.clone());
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    // ======================== Two-Phase Commit Hooks ========================
+
+    /// Prepare phase for adding a vision record
+    pub fn prepare_add_record(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        provider: Address,
+        record_type: RecordType,
+        data_hash: String,
+    ) -> Result<u64, ContractError> {
+        // Validate all inputs without making state changes
+        circuit_breaker::require_not_paused(
+            &env,
+            &circuit_breaker::PauseScope::Function(symbol_short!("ADD_REC")),
+        )?;
+
+        if !whitelist::check_whitelist_access(&env, &caller) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        Self::enforce_rate_limit(&env, &caller, "add_record")?;
+        validation::validate_data_hash(&data_hash)?;
+
+        // Check permissions
+        let has_perm = if caller == provider {
+            rbac::has_permission(&env, &caller, &Permission::WriteRecord)
+        } else {
+            rbac::has_delegated_permission(&env, &provider, &caller, &Permission::WriteRecord)
+        };
+
+        if !has_perm && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if !provider::can_author_record_type(&env, &provider, &record_type) {
+            return Err(ContractError::AccessDenied);
+        }
+
+        // Generate and return the record ID that will be used
+        let counter_key = symbol_short!("REC_CTR");
+        let record_id: u64 = env
+            .storage()
+            .instance()
+            .get(&counter_key)
+            .unwrap_or(0u64)
+            .saturating_add(1u64);
+
+        // Store preparation data temporarily
+        let prep_key = (symbol_short!("P_ADD_REC"), record_id);
+        let prep_data = PrepareAddRecord {
+            caller,
+            patient,
+            provider,
+            record_type,
+            data_hash,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().temporary().set(&prep_key, &prep_data);
+
+        Ok(record_id)
+    }
+
+    /// Commit phase for adding a vision record
+    pub fn commit_add_record(env: Env, record_id: u64) -> Result<(), ContractError> {
+        // Retrieve preparation data
+        let prep_key = (symbol_short!("P_ADD_REC"), record_id);
+        let prep_data: PrepareAddRecord = env
+            .storage()
+            .temporary()
+            .get(&prep_key)
+            .ok_or(ContractError::InvalidInput)?;
+
+        // Update the counter
+        let counter_key = symbol_short!("REC_CTR");
+        env.storage().instance().set(&counter_key, &record_id);
+
+        // Create the actual record
+        let record = VisionRecord {
+            id: record_id,
+            patient: prep_data.patient.clone(),
+            provider: prep_data.provider.clone(),
+            record_type: prep_data.record_type.clone(),
+            data_hash: prep_data.data_hash.clone(),
+            key_version: None,
+            created_at: prep_data.timestamp,
+            updated_at: prep_data.timestamp,
+        };
+
+        // Store the record
+        let key = (symbol_short!("RECORD"), record_id);
+        env.storage().persistent().set(&key, &record);
+        extend_ttl_u64_key(&env, &key);
+        append_record_version(&env, &record, &prep_data.provider);
+
+        // Add to patient's record list
+        track_patient_record(&env, &prep_data.patient, record_id);
+        count_patient_record(&env, &prep_data.patient, &prep_data.record_type);
+        track_provider_record(&env, &prep_data.provider, record_id);
+        provider::record_created(&env, &prep_data.provider);
+
+        // Clean up preparation data
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    /// Rollback phase for adding a vision record
+    pub fn rollback_add_record(env: Env, record_id: u64) -> Result<(), ContractError> {
+        // Clean up preparation data
+        let prep_key = (symbol_short!("P_ADD_REC"), record_id);
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    /// Prepare phase for granting access
+    pub fn prepare_grant_access(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        grantee: Address,
+        access_level: AccessLevel,
+        expires_at: Option<u64>,
+    ) -> Result<(), ContractError> {
+        // Validate inputs without state changes
+        if caller != patient && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if access_level == AccessLevel::None {
+            return Err(ContractError::InvalidInput);
+        }
+
+        if let Some(expires_at) = expires_at {
+            let duration = expires_at.saturating_sub(env.ledger().timestamp());
+            Self::validate_grant_duration(&env, duration)?;
+        }
+
+        // Store preparation data
+        let prep_key = (symbol_short!("P_GNT_ACC"), patient.clone(), grantee.clone());
+        let prep_data = PrepareGrantAccess {
+            caller,
+            patient,
+            grantee,
+            access_level,
+            expires_at,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().temporary().set(&prep_key, &prep_data);
+
+        Ok(())
+    }
+
+    /// Commit phase for granting access
+    pub fn commit_grant_access(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+    ) -> Result<(), ContractError> {
+        // Retrieve preparation data
+        let prep_key = (symbol_short!("P_GNT_ACC"), patient.clone(), grantee.clone());
+        let prep_data: PrepareGrantAccess = env
+            .storage()
+            .temporary()
+            .get(&prep_key)
+            .ok_or(ContractError::InvalidInput)?;
+
+        // Create the access grant
+        let grant = AccessGrant {
+            patient: prep_data.patient.clone(),
+            grantee: prep_data.grantee.clone(),
+            level: prep_data.access_level,
+            granted_at: prep_data.timestamp,
+            expires_at: prep_data.expires_at.unwrap_or(0),
+        };
+
+        // Store the grant
+        let key = (
+            symbol_short!("GRANT"),
+            prep_data.patient.clone(),
+            prep_data.grantee.clone(),
+        );
+        env.storage().persistent().set(&key, &grant);
+        extend_ttl_access_key(&env, &key);
+
+        // Add to patient's grants list
+        let grants_key = (symbol_short!("GRANTS"), prep_data.patient.clone());
+        let mut grants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&grants_key)
+            .unwrap_or(Vec::new(&env));
+
+        if !grants.contains(&prep_data.grantee) {
+            grants.push_back(prep_data.grantee.clone());
+            env.storage().persistent().set(&grants_key, &grants);
+            extend_ttl_address_key(&env, &grants_key);
+        }
+
+        // Clean up preparation data
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    /// Rollback phase for granting access
+    pub fn rollback_grant_access(
+        env: Env,
+        patient: Address,
+        grantee: Address,
+    ) -> Result<(), ContractError> {
+        // Clean up preparation data
+        let prep_key = (symbol_short!("P_GNT_ACC"), patient.clone(), grantee.clone());
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    pub fn trace_record_ancestors(
+        env: Env,
+        record_id: u64,
+        max_depth: u32,
+    ) -> teye_common::lineage::TraversalResult {
+        provenance_graph::trace_ancestors(&env, record_id, max_depth)
+    }
+
+    pub fn trace_record_descendants(
+        env: Env,
+        record_id: u64,
+        max_depth: u32,
+    ) -> teye_common::lineage::TraversalResult {
+        provenance_graph::trace_descendants(&env, record_id, max_depth)
+    }
+
+    pub fn export_record_dag(env: Env, record_id: u64, max_depth: u32) -> ProvenanceExport {
+        provenance_graph::export_dag(&env, record_id, max_depth)
+    }
+
+    pub fn verify_record_provenance(
+        env: Env,
+        record_id: u64,
+        max_depth: u32,
+    ) -> teye_common::lineage::VerificationResult {
+        provenance_graph::verify_provenance(&env, record_id, max_depth)
+    }
+
+    pub fn check_lineage_based_access(
+        env: Env,
+        record_id: u64,
+        requester: Address,
+        max_depth: u32,
+    ) -> LineageAccessResult {
+        let (result, _) =
+            provenance_graph::check_lineage_access(&env, record_id, &requester, max_depth);
+        result
+    }
+
+    pub fn collect_lineage_actors(env: Env, record_id: u64, max_depth: u32) -> Vec<Address> {
+        provenance_graph::collect_lineage_actors(&env, record_id, max_depth)
+    }
+
+    pub fn transition_record_state(
+        env: Env,
+        caller: Address,
+        record_id: u64,
+        to_state: LifecycleState,
+        retention_until: u64,
+        expires_at: u64,
+        prerequisites_met: bool,
+    ) -> Result<TransitionRecord, ContractError> {
+        caller.require_auth();
+
+        let role = if rbac::role_at_least(&env, &caller, &Role::Admin) {
+            symbol_short!("ADMIN")
+        } else if rbac::role_at_least(&env, &caller, &Role::Ophthalmologist) {
+            symbol_short!("OPHT")
+        } else if rbac::role_at_least(&env, &caller, &Role::Optometrist) {
+            symbol_short!("PROV")
+        } else {
+            symbol_short!("USER")
+        };
+
+        let ctx = TransitionContext {
+            actor: caller,
+            actor_role: role,
+            now: env.ledger().timestamp(),
+            retention_until,
+            expires_at,
+            prerequisites_met,
+        };
+
+        state_machine::apply_transition(
+            &env,
+            0,
+            &EntityKind::VisionRecord,
+            record_id,
+            to_state,
+            ctx,
+        )
+        .map_err(|_| ContractError::InvalidInput)
+    }
+
+    pub fn export_state_machine_dot(env: Env, kind: String) -> String {
+        if kind == String::from_str(&env, "vision_record") {
+            return state_machine::export_dot(&env, &EntityKind::VisionRecord);
+        }
+        state_machine::export_dot(&env, &EntityKind::Prescription)
+    }
+
+    /// Prepare phase for adding a prescription
+    pub fn prepare_add_prescription(
+        env: Env,
+        patient: Address,
+        provider: Address,
+        prescription_data: prescription::PrescriptionData,
+        refills_allowed: u32,
+    ) -> Result<u64, ContractError> {
+        // Validate without state changes
+        validation::validate_prescription_data(&prescription_data)?;
+
+        // Check provider permissions
+        let caller = provider.clone();
+        let has_perm = rbac::has_permission(&env, &caller, &Permission::WriteRecord)
+            || rbac::has_delegated_permission(&env, &provider, &caller, &Permission::WriteRecord)
+            || rbac::has_permission(&env, &caller, &Permission::SystemAdmin);
+
+        if !has_perm {
+            return Err(ContractError::Unauthorized);
+        }
+
+        // Generate prescription ID
+        let rx_id = prescription::peek_prescription_id(&env);
+
+        // Store preparation data
+        let prep_key = (symbol_short!("P_ADD_RX"), rx_id);
+        let prep_data = PrepareAddPrescription {
+            patient,
+            provider,
+            prescription_data,
+            refills_allowed,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().temporary().set(&prep_key, &prep_data);
+
+        Ok(rx_id)
+    }
+
+    /// Commit phase for adding a prescription
+    pub fn commit_add_prescription(env: Env, rx_id: u64) -> Result<(), ContractError> {
+        // Retrieve preparation data
+        let prep_key = (symbol_short!("P_ADD_RX"), rx_id);
+        let prep_data =
+            load_prepared_prescription(&env, &prep_key).ok_or(ContractError::InvalidInput)?;
+
+        // Update counter
+        prescription::set_prescription_counter(&env, rx_id);
+
+        // Create the prescription
+        let prescription = prescription::Prescription {
+            id: rx_id,
+            patient: prep_data.patient.clone(),
+            provider: prep_data.provider.clone(),
+            lens_type: LensType::Glasses,
+            left_eye: prep_data.prescription_data.clone(),
+            right_eye: prep_data.prescription_data.clone(),
+            contact_data: OptionalContactLensData::None,
+            issued_at: prep_data.timestamp,
+            expires_at: prep_data.timestamp.saturating_add(31_536_000),
+            verified: false,
+            metadata_hash: String::from_str(&env, ""),
+            revoked: false,
+            revoked_reason: None,
+            supersedes: None,
+            refills_allowed: prep_data.refills_allowed,
+            refills_used: 0,
+        };
+
+        // Store the prescription
+        let key = (symbol_short!("RX_DATA"), rx_id);
+        env.storage().persistent().set(&key, &prescription);
+        extend_ttl_u64_key(&env, &key);
+
+        // Add to patient's prescription history and lineage records.
+        prescription::save_prescription(&env, &prescription, None);
+
+        // Clean up preparation data
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    /// Rollback phase for adding a prescription
+    pub fn rollback_add_prescription(env: Env, rx_id: u64) -> Result<(), ContractError> {
+        // Clean up preparation data
+        let prep_key = (symbol_short!("P_ADD_RX"), rx_id);
+        env.storage().temporary().remove(&prep_key);
+
+        Ok(())
+    }
+
+    // ── Providers ─────────────────────────────────────────────────────────────
+
+    /// Register a provider profile. Requires `ManageUsers`.
+    ///
+    /// The address must already be registered with `register_user` and hold
+    /// an active role assignment, otherwise `UserNotFound` is returned;
+    /// register the user first, then the provider profile.
+    pub fn register_provider(
+        env: Env,
+        caller: Address,
+        provider: Address,
+        name: String,
+        licenses: Vec<License>,
+        specialties: Vec<String>,
+        certifications: Vec<Certification>,
+        locations: Vec<Location>,
+    ) -> Result<u64, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "register_provider",
+                "permission:ManageUsers",
+            );
+        }
+
+        validation::validate_name(&name)?;
+
+        let user = Self::get_user(env.clone(), provider.clone())?;
+        if !user.is_active || rbac::get_active_assignment(&env, &provider).is_none() {
+            return Err(ContractError::UserNotFound);
+        }
+
+        if provider::get_provider(&env, &provider).is_some() {
+            return Err(ContractError::ProviderAlreadyRegistered);
+        }
+
+        let provider_data = Provider {
+            address: provider.clone(),
+            name: name.clone(),
+            licenses,
+            specialties: specialties.clone(),
+            certifications,
+            locations,
+            verification_status: VerificationStatus::Pending,
+            registered_at: env.ledger().timestamp(),
+            verified_at: None,
+            verified_by: None,
+            is_active: true,
+        };
+        provider::set_provider(&env, &provider_data);
+
+        for specialty in specialties.iter() {
+            provider::add_provider_to_specialty_index(&env, &specialty, &provider);
+        }
+
+        let provider_id = provider::increment_provider_counter(&env);
+        provider::add_provider_id(&env, provider_id, &provider);
+
+        events::publish_provider_registered(&env, provider, name, provider_id);
+
+        Ok(provider_id)
+    }
+
+    /// Set a provider's verification status. Requires `SystemAdmin`.
+    pub fn verify_provider(
+        env: Env,
+        caller: Address,
+        provider: Address,
+        status: VerificationStatus,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        caller.require_auth();
+
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Self::unauthorized(&env, &caller, "verify_provider", "permission:SystemAdmin");
+        }
+
+        let mut provider_data =
+            provider::get_provider(&env, &provider).ok_or(ContractError::ProviderNotFound)?;
+
+        let old_status = provider_data.verification_status.clone();
+        provider_data.verification_status = status.clone();
+        provider_data.verified_at = Some(env.ledger().timestamp());
+        provider_data.verified_by = Some(caller.clone());
+        provider::set_provider(&env, &provider_data);
+
+        events::publish_provider_verified(&env, provider, old_status, status, caller);
+
+        Ok(())
+    }
+
+    /// Deactivate a retired provider. Their profile and history are kept,
+    /// but they drop out of status and specialty searches. Requires
+    /// `SystemAdmin`.
+    pub fn deactivate_provider(
+        env: Env,
+        admin: Address,
+        provider: Address,
+    ) -> Result<(), ContractError> {
+        Self::set_provider_active(&env, &admin, &provider, false)
+    }
+
+    /// Reactivate a provider, restoring them to status and specialty
+    /// searches. Requires `SystemAdmin`.
+    pub fn activate_provider(
+        env: Env,
+        admin: Address,
+        provider: Address,
+    ) -> Result<(), ContractError> {
+        Self::set_provider_active(&env, &admin, &provider, true)
+    }
+
+    fn set_provider_active(
+        env: &Env,
+        admin: &Address,
+        provider: &Address,
+        active: bool,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(env, &circuit_breaker::PauseScope::Global)?;
+        admin.require_auth();
+
+        if !rbac::has_permission(env, admin, &Permission::SystemAdmin) {
+            let resource = if active {
+                "activate_provider"
+            } else {
+                "deactivate_provider"
+            };
+            return Self::unauthorized(env, admin, resource, "permission:SystemAdmin");
+        }
+
+        let mut provider_data =
+            provider::get_provider(env, provider).ok_or(ContractError::ProviderNotFound)?;
+        if provider_data.is_active == active {
+            return Ok(());
+        }
+
+        provider_data.is_active = active;
+        provider::set_provider(env, &provider_data);
+        for specialty in provider_data.specialties.iter() {
+            if active {
+                provider::add_provider_to_specialty_index(env, &specialty, provider);
+            } else {
+                provider::remove_provider_from_specialty_index(env, &specialty, provider);
+            }
+        }
+
+        events::publish_provider_activation(env, provider.clone(), active, admin.clone());
+        Ok(())
+    }
+
+    /// Get a provider profile
+    pub fn get_provider(env: Env, provider: Address) -> Result<Provider, ContractError> {
+        provider::get_provider(&env, &provider).ok_or(ContractError::ProviderNotFound)
+    }
+
+    /// Get a provider's verification and license standing as of now, so
+    /// callers don't have to compare license expiry dates themselves.
+    pub fn get_provider_status(
+        env: Env,
+        provider: Address,
+    ) -> Result<ProviderStatus, ContractError> {
+        provider::get_provider_status(&env, &provider).ok_or(ContractError::ProviderNotFound)
+    }
+
+    /// List providers with the given verification status
+    pub fn get_providers_by_status(env: Env, status: VerificationStatus) -> Vec<Address> {
+        provider::get_providers_by_status(&env, &status)
+    }
+
+    /// List active providers registered with the given specialty
+    pub fn get_providers_by_specialty(env: Env, specialty: String) -> Vec<Address> {
+        provider::get_providers_by_specialty(&env, &specialty)
+    }
+
+    /// Page through full provider profiles in registration order. `limit` is
+    /// capped at `provider::MAX_PROVIDER_PAGE`. Requires `ManageUsers`.
+    pub fn get_providers_paged(
+        env: Env,
+        caller: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Provider>, ContractError> {
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+            return Self::unauthorized(&env, &caller, "get_providers_paged", "ManageUsers");
+        }
+        Ok(provider::get_providers_page(&env, offset, limit))
+    }
+
+    // ── Emergency access ──────────────────────────────────────────────────────
+
+    /// Cap emergency grants for `condition` at `seconds`.
+    ///
+    /// The cap may not exceed the 24 hour ceiling on all emergency access.
+    pub fn set_emergency_max_duration(
+        env: Env,
+        admin: Address,
+        condition: EmergencyCondition,
+        seconds: u64,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !admin_tiers::require_tier(&env, &admin, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+        if seconds == 0 || seconds > emergency::MAX_EMERGENCY_DURATION {
+            return Err(ContractError::InvalidInput);
+        }
+
+        emergency::set_max_duration(&env, &condition, seconds);
+        Ok(())
+    }
+
+    /// Longest emergency grant currently allowed for `condition`.
+    pub fn get_emergency_max_duration(env: Env, condition: EmergencyCondition) -> u64 {
+        emergency::get_max_duration(&env, &condition)
+    }
+
+    /// Validates and stores an emergency grant, notifying contacts and
+    /// writing the GRANTED/NOTIFIED audit entries.
+    fn create_emergency_access(
+        env: &Env,
+        requester: &Address,
+        patient: &Address,
+        condition: EmergencyCondition,
+        attestation: &String,
+        duration_seconds: u64,
+        emergency_contacts: &Vec<Address>,
+        override_consent: bool,
+    ) -> Result<u64, ContractError> {
+        validation::validate_attestation(attestation)?;
+        if override_consent && condition != EmergencyCondition::LifeThreatening {
+            return Err(ContractError::InvalidEmergencyCondition);
+        }
+        if duration_seconds == 0 || duration_seconds > emergency::get_max_duration(env, &condition)
+        {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let now = env.ledger().timestamp();
+        let access_id = emergency::increment_emergency_counter(env);
+        let access = EmergencyAccess {
+            id: access_id,
+            patient: patient.clone(),
+            requester: requester.clone(),
+            condition: condition.clone(),
+            attestation: attestation.clone(),
+            granted_at: now,
+            expires_at: now + duration_seconds,
+            status: emergency::EmergencyStatus::Active,
+            notified_contacts: emergency_contacts.clone(),
+            override_consent,
+        };
+        emergency::set_emergency_access(env, &access);
+
+        emergency::add_audit_entry(
+            env,
+            &EmergencyAuditEntry {
+                access_id,
+                actor: requester.clone(),
+                action: String::from_str(env, "GRANTED"),
+                timestamp: now,
+                record_id: None,
+            },
+        );
+
+        for contact in emergency_contacts.iter() {
+            emergency::add_audit_entry(
+                env,
+                &EmergencyAuditEntry {
+                    access_id,
+                    actor: contact.clone(),
+                    action: String::from_str(env, "NOTIFIED"),
+                    timestamp: now,
+                    record_id: None,
+                },
+            );
+            events::publish_emergency_contact_notified(env, access_id, patient.clone(), contact);
+        }
+
+        events::publish_emergency_access_granted(
+            env,
+            access_id,
+            patient.clone(),
+            requester.clone(),
+            condition,
+            access.expires_at,
+        );
+
+        Ok(access_id)
+    }
+
+    /// Grant time-limited emergency access to a patient's records.
+    ///
+    /// Only verified providers may request emergency access; the grant is
+    /// capped at 24 hours and the listed contacts are notified. Setting
+    /// `override_consent` is limited to `LifeThreatening` conditions.
+    pub fn grant_emergency_access(
+        env: Env,
+        requester: Address,
+        patient: Address,
+        condition: EmergencyCondition,
+        attestation: String,
+        duration_seconds: u64,
+        emergency_contacts: Vec<Address>,
+        override_consent: bool,
+    ) -> Result<u64, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        requester.require_auth();
+
+        if !provider::is_verified_provider(&env, &requester) {
+            return Self::unauthorized(
+                &env,
+                &requester,
+                "grant_emergency_access",
+                "verified_provider",
+            );
+        }
+
+        Self::create_emergency_access(
+            &env,
+            &requester,
+            &patient,
+            condition,
+            &attestation,
+            duration_seconds,
+            &emergency_contacts,
+            override_consent,
+        )
+    }
+
+    /// Grant `Masscasualties` emergency access for many patients at once.
+    ///
+    /// Creates one grant per patient under a single authorization and returns
+    /// the access ids in input order. At most `MAX_MASS_CASUALTY_PATIENTS`
+    /// patients may be listed, each must be a registered user, and repeated
+    /// entries receive a single grant.
+    pub fn grant_mass_casualty_access(
+        env: Env,
+        requester: Address,
+        patients: Vec<Address>,
+        attestation: String,
+        duration_seconds: u64,
+        emergency_contacts: Vec<Address>,
+    ) -> Result<Vec<u64>, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        requester.require_auth();
+
+        if patients.is_empty() || patients.len() > emergency::MAX_MASS_CASUALTY_PATIENTS {
+            return Err(ContractError::InvalidInput);
+        }
+
+        if !provider::is_verified_provider(&env, &requester) {
+            return Self::unauthorized(
+                &env,
+                &requester,
+                "grant_mass_casualty_access",
+                "verified_provider",
+            );
+        }
+
+        let mut unique: Vec<Address> = Vec::new(&env);
+        for patient in patients.iter() {
+            if unique.contains(&patient) {
+                continue;
+            }
+            if !env
+                .storage()
+                .persistent()
+                .has(&(symbol_short!("USER"), patient.clone()))
+            {
+                return Err(ContractError::UserNotFound);
+            }
+            unique.push_back(patient);
+        }
+
+        let mut access_ids = Vec::new(&env);
+        for patient in unique.iter() {
+            let access_id = Self::create_emergency_access(
+                &env,
+                &requester,
+                &patient,
+                EmergencyCondition::Masscasualties,
+                &attestation,
+                duration_seconds,
+                &emergency_contacts,
+                false,
+            )?;
+            access_ids.push_back(access_id);
+        }
+
+        events::publish_mass_casualty_access_granted(
+            &env,
+            requester,
+            access_ids.len(),
+            env.ledger().timestamp() + duration_seconds,
+        );
+
+        Ok(access_ids)
+    }
+
+    /// Get an emergency access grant by ID
+    pub fn get_emergency_access(
+        env: Env,
+        access_id: u64,
+    ) -> Result<EmergencyAccess, ContractError> {
+        emergency::get_emergency_access(&env, access_id)
+            .ok_or(ContractError::EmergencyAccessNotFound)
+    }
+
+    /// Returns the active emergency grant for a patient/requester pair, if any
+    pub fn check_emergency_access(
+        env: Env,
+        patient: Address,
+        requester: Address,
+    ) -> Option<EmergencyAccess> {
+        emergency::has_active_emergency_access(&env, &patient, &requester)
+    }
+
+    /// Read a patient's records under an active emergency grant.
+    ///
+    /// Returns the record ids made available: the requested record, or all of
+    /// the patient's records when `record_id` is `None`.
+    pub fn access_record_via_emergency(
+        env: Env,
+        requester: Address,
+        patient: Address,
+        record_id: Option<u64>,
+    ) -> Result<Vec<u64>, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        requester.require_auth();
+
+        let access = match emergency::has_active_emergency_access(&env, &patient, &requester) {
+            Some(access) => access,
+            None => {
+                return Self::access_denied(
+                    &env,
+                    &requester,
+                    "access_record_via_emergency",
+                    "active_emergency_access",
+                )
+            }
+        };
+
+        let policies_allow = if access.override_consent {
+            rbac::evaluate_access_policies_without_consent(
+                &env,
+                &requester,
+                record_id,
+                Some(patient.clone()),
+            )
+        } else {
+            rbac::evaluate_access_policies(&env, &requester, record_id, Some(patient.clone()))
+        };
+        if !policies_allow {
+            return Self::access_denied(
+                &env,
+                &requester,
+                "access_record_via_emergency",
+                "access_policy",
+            );
+        }
+
+        let record_ids = match record_id {
+            Some(id) => {
+                let record: VisionRecord = env
+                    .storage()
+                    .persistent()
+                    .get(&(symbol_short!("RECORD"), id))
+                    .ok_or(ContractError::RecordNotFound)?;
+                if record.patient != patient {
+                    return Err(ContractError::RecordNotFound);
+                }
+                let mut ids = Vec::new(&env);
+                ids.push_back(id);
+                ids
+            }
+            None => Self::get_patient_records(env.clone(), patient.clone()),
+        };
+
+        if access.override_consent {
+            emergency::add_audit_entry(
+                &env,
+                &EmergencyAuditEntry {
+                    access_id: access.id,
+                    actor: requester.clone(),
+                    action: String::from_str(&env, "CONSENT_OVERRIDE"),
+                    timestamp: env.ledger().timestamp(),
+                    record_id: None,
+                },
+            );
+        }
+
+        let mut audited = Vec::new(&env);
+        if record_ids.is_empty() {
+            audited.push_back(None);
+        } else {
+            for id in record_ids.iter() {
+                audited.push_back(Some(id));
+            }
+        }
+
+        // One ACCESSED entry per record viewed, so the trail shows exactly
+        // what the responder saw.
+        for id in audited.iter() {
+            emergency::add_audit_entry(
+                &env,
+                &EmergencyAuditEntry {
+                    access_id: access.id,
+                    actor: requester.clone(),
+                    action: String::from_str(&env, "ACCESSED"),
+                    timestamp: env.ledger().timestamp(),
+                    record_id: id,
+                },
+            );
+        }
+
+        // Mirror the read into the central audit log so it shows up alongside
+        // regular access for the actor, patient and each record touched.
+        let reason = if access.override_consent {
+            Some(String::from_str(&env, "CONSENT_OVERRIDE"))
+        } else {
+            None
+        };
+        for audited_record in audited.iter() {
+            let audit_entry = audit::create_audit_entry(
+                &env,
+                requester.clone(),
+                patient.clone(),
+                audited_record,
+                AccessAction::EmergencyAccess,
+                AccessResult::Success,
+                reason.clone(),
+            );
+            audit::add_audit_entry(&env, &audit_entry);
+            events::publish_audit_log_entry(&env, &audit_entry);
+        }
+        events::publish_emergency_access_used(&env, access.id, patient, requester, record_id);
+
+        Ok(record_ids)
+    }
+
+    /// Revoke an emergency grant. Allowed for the patient, the requester, or a SystemAdmin.
+    pub fn revoke_emergency_access(
+        env: Env,
+        caller: Address,
+        access_id: u64,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let access = emergency::get_emergency_access(&env, access_id)
+            .ok_or(ContractError::EmergencyAccessNotFound)?;
+
+        if caller != access.patient
+            && caller != access.requester
+            && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "revoke_emergency_access",
+                "patient_or_requester_or_SystemAdmin",
+            );
+        }
+
+        emergency::revoke_emergency_access(&env, access_id);
+        emergency::add_audit_entry(
+            &env,
+            &EmergencyAuditEntry {
+                access_id,
+                actor: caller.clone(),
+                action: String::from_str(&env, "REVOKED"),
+                timestamp: env.ledger().timestamp(),
+                record_id: None,
+            },
+        );
+        events::publish_emergency_access_revoked(&env, access_id, access.patient, caller);
+
+        Ok(())
+    }
+
+    /// Whether `caller` may review `patient`'s emergency accesses: the
+    /// patient, a delegate holding `ManageAccess` for them, or a system admin.
+    fn can_review_emergencies(env: &Env, caller: &Address, patient: &Address) -> bool {
+        caller == patient
+            || rbac::has_delegated_permission(env, patient, caller, &Permission::ManageAccess)
+            || rbac::has_permission(env, caller, &Permission::SystemAdmin)
+    }
+
+    /// Get the emergency-specific audit trail for a grant.
+    ///
+    /// Visible to the patient, a delegate holding `ManageAccess` for them, or
+    /// a system admin.
+    pub fn get_emergency_audit_trail(
+        env: Env,
+        caller: Address,
+        access_id: u64,
+    ) -> Result<Vec<EmergencyAuditEntry>, ContractError> {
+        caller.require_auth();
+
+        let access = emergency::get_emergency_access(&env, access_id)
+            .ok_or(ContractError::EmergencyAccessNotFound)?;
+        if !Self::can_review_emergencies(&env, &caller, &access.patient) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_emergency_audit_trail",
+                "patient_or_delegate_or_SystemAdmin",
+            );
+        }
+
+        Ok(emergency::get_audit_entries(&env, access_id))
+    }
+
+    /// Records viewed under an emergency grant, for post-incident review.
+    ///
+    /// Visible to the patient, a delegate holding `ManageAccess` for them, or
+    /// a system admin.
+    pub fn get_emergency_records_accessed(
+        env: Env,
+        caller: Address,
+        access_id: u64,
+    ) -> Result<Vec<u64>, ContractError> {
+        caller.require_auth();
+
+        let access = emergency::get_emergency_access(&env, access_id)
+            .ok_or(ContractError::EmergencyAccessNotFound)?;
+        if !Self::can_review_emergencies(&env, &caller, &access.patient) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_emergency_records_accessed",
+                "patient_or_delegate_or_SystemAdmin",
+            );
+        }
+
+        Ok(emergency::get_records_accessed(&env, access_id))
+    }
+
+    /// Every emergency audit entry touching `patient`, across all of their
+    /// emergency accesses, in chronological order.
+    ///
+    /// Visible to the patient, a delegate holding `ManageAccess` for them, or
+    /// a system admin.
+    pub fn get_patient_emergency_audit(
+        env: Env,
+        caller: Address,
+        patient: Address,
+    ) -> Result<Vec<EmergencyAuditEntry>, ContractError> {
+        caller.require_auth();
+
+        if !Self::can_review_emergencies(&env, &caller, &patient) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_patient_emergency_audit",
+                "patient_or_delegate_or_SystemAdmin",
+            );
+        }
+
+        Ok(emergency::get_patient_audit_entries(&env, &patient))
+    }
+
+    /// Keeper entry point: mark emergency grants past their expiry as
+    /// `Expired` and drop them from the active indexes. Each call examines
+    /// at most `max_to_process` (capped at `emergency::MAX_EXPIRY_BATCH`)
+    /// expiry buckets and grants, resuming where the last call stopped.
+    /// Returns how many were updated.
+    pub fn expire_emergency_accesses(env: Env, max_to_process: Option<u32>) -> u32 {
+        emergency::expire_emergency_accesses(&env, max_to_process)
+    }
+
+    /// Re-file emergency grants into the per-patient and active-grant
+    /// indexes, e.g. to include grants made before those indexes existed.
+    ///
+    /// Processes up to `limit` (capped at `emergency::MAX_REINDEX_PAGE`)
+    /// grant ids starting at `start_id`. Call with increasing start ids
+    /// until the returned count of processed ids is below `limit`.
+    /// Requires `SystemAdmin`.
+    pub fn rebuild_emergency_index(
+        env: Env,
+        caller: Address,
+        start_id: u64,
+        limit: u32,
+    ) -> Result<u32, ContractError> {
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "rebuild_emergency_index",
+                "permission:SystemAdmin",
+            );
+        }
+
+        Ok(emergency::reindex_emergency_accesses(&env, start_id, limit))
+    }
+
+    /// Get a patient's active emergency grants.
+    ///
+    /// Visible to the patient, a delegate holding `ManageAccess` for them, or
+    /// a system admin.
+    pub fn get_patient_emergency_accesses(
+        env: Env,
+        caller: Address,
+        patient: Address,
+    ) -> Result<Vec<EmergencyAccess>, ContractError> {
+        caller.require_auth();
+
+        if !Self::can_review_emergencies(&env, &caller, &patient) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_patient_emergency_accesses",
+                "patient_or_delegate_or_SystemAdmin",
+            );
+        }
+
+        Ok(emergency::get_patient_emergency_accesses(&env, &patient))
+    }
+
+    /// All of a patient's emergency grants, including expired and revoked
+    /// ones, ordered by when they were granted.
+    ///
+    /// Visible to the patient, a delegate holding `ManageAccess` for them, or
+    /// a system admin.
+    pub fn get_patient_emergency_history(
+        env: Env,
+        caller: Address,
+        patient: Address,
+    ) -> Result<Vec<EmergencyAccess>, ContractError> {
+        caller.require_auth();
+
+        if !Self::can_review_emergencies(&env, &caller, &patient) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_patient_emergency_history",
+                "patient_or_delegate_or_SystemAdmin",
+            );
+        }
+
+        Ok(emergency::get_patient_emergency_history(&env, &patient))
+    }
+
+    /// Break-glass read: a verified provider reads `record_id` immediately,
+    /// bypassing grants and consent. The access is logged as a
+    /// `BreakGlassEvent` that stays pending until an admin reviews it.
+    /// Returns the event ID and the record.
+    ///
+    /// The justification must pass `validate_attestation`. Restricted
+    /// records still require a professional credential, the caller's rate
+    /// limit applies, and a patient who opted in to access notifications
+    /// is notified.
+    pub fn break_glass_access(
+        env: Env,
+        provider: Address,
+        record_id: u64,
+        justification: String,
+    ) -> Result<(u64, VisionRecord), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
+
+        if !provider::is_verified_provider(&env, &provider) {
+            return Self::unauthorized(&env, &provider, "break_glass_access", "verified_provider");
+        }
+        validation::validate_attestation(&justification)?;
+        Self::enforce_rate_limit(&env, &provider, "break_glass_access")?;
+
+        let record: VisionRecord = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("RECORD"), record_id))
+            .ok_or(ContractError::RecordNotFound)?;
+
+        if provider != record.provider
+            && rbac::get_record_sensitivity(&env, &record_id) == SensitivityLevel::Restricted
+            && !matches!(
+                rbac::get_user_credential(&env, &provider),
+                CredentialType::MedicalLicense | CredentialType::AdminCredentials
+            )
+        {
+            return Self::access_denied(
+                &env,
+                &provider,
+                "break_glass_access",
+                "credential:MedicalLicense_or_AdminCredentials",
+            );
+        }
+
+        let event = emergency::create_break_glass_event(
+            &env,
+            &provider,
+            &record.patient,
+            record_id,
+            &justification,
+        );
+
+        let audit_entry = audit::create_audit_entry(
+            &env,
+            provider.clone(),
+            record.patient.clone(),
+            Some(record_id),
+            AccessAction::EmergencyAccess,
+            AccessResult::Success,
+            Some(String::from_str(&env, "BREAK_GLASS")),
+        );
+        audit::add_audit_entry(&env, &audit_entry);
+        events::publish_audit_log_entry(&env, &audit_entry);
+        events::publish_break_glass(&env, &event);
+
+        if provider != record.patient
+            && Self::get_access_notifications(env.clone(), record.patient.clone())
+        {
+            events::publish_record_accessed(
+                &env,
+                record.patient.clone(),
+                record_id,
+                provider.clone(),
+            );
+        }
+
+        let mut out_record = record.clone();
+        out_record.data_hash = decrypt_data_hash(&env, &record.data_hash, &record.key_version);
+        Ok((event.id, out_record))
+    }
+
+    /// Approve or reject a break-glass access after the fact. SystemAdmin only;
+    /// each event can be reviewed once.
+    pub fn review_break_glass(
+        env: Env,
+        admin: Address,
+        event_id: u64,
+        approved: bool,
     ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &admin,
+                "review_break_glass",
+                "permission:SystemAdmin",
+            );
+        }
+
+        let event = emergency::get_break_glass_event(&env, event_id)
+            .ok_or(ContractError::EmergencyAccessNotFound)?;
+        if event.approved.is_some() {
+            return Err(ContractError::InvalidInput);
+        }
+        if let Some(reviewed) =
+            emergency::review_break_glass_event(&env, event_id, &admin, approved)
+        {
+            events::publish_break_glass_reviewed(&env, &reviewed);
+        }
+        Ok(())
+    }
+
+    /// Break-glass accesses still awaiting review, oldest first.
+    /// SystemAdmin only.
+    pub fn get_pending_break_glass(
+        env: Env,
+        caller: Address,
+    ) -> Result<Vec<BreakGlassEvent>, ContractError> {
+        caller.require_auth();
+        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_pending_break_glass",
+                "permission:SystemAdmin",
+            );
+        }
+
+        let mut pending = Vec::new(&env);
+        for id in emergency::get_pending_break_glass_ids(&env).iter() {
+            if let Some(event) = emergency::get_break_glass_event(&env, id) {
+                pending.push_back(event);
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Look up a break-glass event, reviewed or not.
+    ///
+    /// Visible to the provider who broke the glass, the patient, a delegate
+    /// holding `ManageAccess` for them, or a system admin.
+    pub fn get_break_glass_event(
+        env: Env,
+        caller: Address,
+        event_id: u64,
+    ) -> Result<BreakGlassEvent, ContractError> {
+        caller.require_auth();
+
+        let event = emergency::get_break_glass_event(&env, event_id)
+            .ok_or(ContractError::EmergencyAccessNotFound)?;
+        if caller != event.provider && !Self::can_review_emergencies(&env, &caller, &event.patient)
+        {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_break_glass_event",
+                "provider_or_patient_or_delegate_or_SystemAdmin",
+            );
+        }
+        Ok(event)
+    }
+
+    // ── Appointments ──────────────────────────────────────────────────────────
+
+    /// Validate a slot and persist a new `Scheduled` appointment, recording
+    /// its history entry and event. Callers handle authorization.
+    fn book_appointment(
+        env: &Env,
+        actor: Address,
+        patient: Address,
+        provider: Address,
+        appointment_type: AppointmentType,
+        scheduled_at: u64,
+        duration_minutes: u32,
+        notes: Option<String>,
+        reminder_lead_seconds: Option<u64>,
+    ) -> Result<u64, ContractError> {
+        if actor != provider
+            && appointment::schedule_requires_approval(env, &patient)
+            && !rbac::has_permission(env, &actor, &Permission::SystemAdmin)
+        {
+            return Err(ContractError::TooManyNoShows);
+        }
+
+        let now = env.ledger().timestamp();
+        if scheduled_at <= now {
+            return Err(ContractError::InvalidAppointmentTime);
+        }
+        if !appointment::is_valid_duration(duration_minutes) {
+            return Err(ContractError::InvalidInput);
+        }
+        if !appointment::is_slot_available(env, &provider, scheduled_at, duration_minutes, None) {
+            return Err(ContractError::SlotUnavailable);
+        }
+
+        let appointment = Appointment {
+            id: appointment::increment_appointment_counter(env),
+            patient: patient.clone(),
+            provider: provider.clone(),
+            appointment_type: appointment_type.clone(),
+            scheduled_at,
+            duration_minutes,
+            status: AppointmentStatus::Scheduled,
+            notes: notes.clone(),
+            created_at: now,
+            updated_at: now,
+            verified_at: None,
+            verified_by: None,
+            reminders_sent: Vec::new(env),
+            record_id: None,
+            late_cancellation: false,
+            reminder_lead_seconds,
+            reschedule_count: 0,
+        };
+        appointment::set_appointment(env, &appointment);
+
+        appointment::add_history_entry(
+            env,
+            &AppointmentHistoryEntry {
+                appointment_id: appointment.id,
+                action: String::from_str(env, "CREATED"),
+                actor,
+                timestamp: now,
+                previous_status: AppointmentStatus::None,
+                new_status: AppointmentStatus::Scheduled,
+                notes,
+            },
+        );
+
+        events::publish_appointment_scheduled(
+            env,
+            appointment.id,
+            patient,
+            provider,
+            appointment_type,
+            scheduled_at,
+        );
+
+        Ok(appointment.id)
+    }
+
+    /// Schedule an appointment. The caller must be the patient, the provider,
+    /// or hold `SystemAdmin`.
+    ///
+    /// `reminder_lead_seconds` overrides the reminder sweep's window for this
+    /// appointment, e.g. a week's notice before surgery.
+    pub fn schedule_appointment(
+        env: Env,
+        caller: Address,
+        patient: Address,
+        provider: Address,
+        appointment_type: AppointmentType,
+        scheduled_at: u64,
+        duration_minutes: u32,
+        notes: Option<String>,
+        reminder_lead_seconds: Option<u64>,
+    ) -> Result<u64, ContractError> {
         circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
         caller.require_auth();
-        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+
+        if caller != patient
+            && caller != provider
+            && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+        {
             return Self::unauthorized(
                 &env,
                 &caller,
-                "add_user_to_group",
-                "permission:ManageUsers",
+                "schedule_appointment",
+                "patient_or_provider_or_SystemAdmin",
             );
         }
-        rbac::add_to_group(&env, user, group_name).map_err(|_| ContractError::InvalidInput)
+
+        Self::enforce_operation_rate_limit(&env, &caller, "schedule_appointment")?;
+
+        Self::book_appointment(
+            &env,
+            caller,
+            patient,
+            provider,
+            appointment_type,
+            scheduled_at,
+            duration_minutes,
+            notes,
+            reminder_lead_seconds,
+        )
     }
 
-    /// Removes a user from an ACL group.
-    pub fn remove_user_from_group(
+    /// Book a series of appointments `interval_seconds` apart starting at
+    /// `first_at`. Occurrences whose slot is unavailable are skipped; the IDs
+    /// of the appointments actually created are returned. Every created
+    /// appointment counts against the `schedule_appointment` rate limit.
+    pub fn schedule_recurring_appointment(
         env: Env,
         caller: Address,
-        user: Address,
-        group_name: String,
-    ) -> Result<(), ContractError> {
+        patient: Address,
+        provider: Address,
+        appointment_type: AppointmentType,
+        first_at: u64,
+        duration_minutes: u32,
+        interval_seconds: u64,
+        occurrences: u32,
+    ) -> Result<Vec<u64>, ContractError> {
         circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
         caller.require_auth();
-        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
+
+        if caller != patient
+            && caller != provider
+            && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+        {
             return Self::unauthorized(
                 &env,
                 &caller,
-                "remove_user_from_group",
-                "permission:ManageUsers",
+                "schedule_recurring_appointment",
+                "patient_or_provider_or_SystemAdmin",
             );
         }
-        rbac::remove_from_group(&env, user, group_name);
+
+        if occurrences == 0
+            || occurrences > appointment::MAX_RECURRING_OCCURRENCES
+            || interval_seconds == 0
+        {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let mut created = Vec::new(&env);
+        for i in 0..occurrences {
+            let at = first_at.saturating_add(interval_seconds.saturating_mul(u64::from(i)));
+            match Self::book_appointment(
+                &env,
+                caller.clone(),
+                patient.clone(),
+                provider.clone(),
+                appointment_type.clone(),
+                at,
+                duration_minutes,
+                None,
+                None,
+            ) {
+                Ok(id) => {
+                    // Each created appointment uses one `schedule_appointment`
+                    // slot; exceeding the limit fails the whole series.
+                    Self::enforce_operation_rate_limit(&env, &caller, "schedule_appointment")?;
+                    created.push_back(id);
+                }
+                Err(ContractError::SlotUnavailable) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(created)
+    }
+
+    /// Set the weekly hours (UTC) during which `provider` accepts
+    /// appointments. `day_mask` uses the `TimeRestriction::DaysOfWeek` bit
+    /// layout and slots must fit within `[start_hour, end_hour)`.
+    pub fn set_provider_availability(
+        env: Env,
+        provider: Address,
+        day_mask: u32,
+        start_hour: u32,
+        end_hour: u32,
+    ) -> Result<(), ContractError> {
+        provider.require_auth();
+
+        if day_mask == 0 || day_mask > 0b111_1111 || start_hour >= end_hour || end_hour > 24 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        appointment::set_provider_availability(
+            &env,
+            &provider,
+            &ProviderAvailability {
+                day_mask,
+                start_hour,
+                end_hour,
+            },
+        );
+
         Ok(())
     }
 
-    /// Returns all ACL groups assigned to a user.
-    pub fn get_user_groups(env: Env, user: Address) -> Vec<String> {
-        env.storage()
-            .persistent()
-            .get(&rbac::user_groups_key(&user))
-            .unwrap_or(Vec::new(&env))
+    /// Return a provider's availability, if set.
+    pub fn get_provider_availability(env: Env, provider: Address) -> Option<ProviderAvailability> {
+        appointment::get_provider_availability(&env, &provider)
+    }
+
+    /// Whether `provider` could take a booking at `scheduled_at` for
+    /// `duration_minutes`, using the same checks as scheduling. Read-only.
+    pub fn check_slot_available(
+        env: Env,
+        provider: Address,
+        scheduled_at: u64,
+        duration_minutes: u32,
+    ) -> bool {
+        scheduled_at > env.ledger().timestamp()
+            && appointment::is_valid_duration(duration_minutes)
+            && appointment::is_slot_available(&env, &provider, scheduled_at, duration_minutes, None)
     }
 
-    /// Checks if a user has a specific permission.
-    /// Returns true if the user has the permission, false otherwise.
-    pub fn check_permission(env: Env, user: Address, permission: Permission) -> bool {
-        rbac::has_permission(&env, &user, &permission)
+    /// Retrieve an appointment by ID.
+    pub fn get_appointment(env: Env, appointment_id: u64) -> Result<Appointment, ContractError> {
+        appointment::get_appointment(&env, appointment_id).ok_or(ContractError::AppointmentNotFound)
     }
 
-    /// Create an access policy with ABAC attributes
-    pub fn create_access_policy(
+    /// Cancel a scheduled or confirmed appointment, freeing its slot.
+    pub fn cancel_appointment(
         env: Env,
         caller: Address,
-        policy_id: String,
-        name: String,
-        required_role: Role,
-        time_restriction: TimeRestriction,
-        required_credential: CredentialType,
-        min_sensitivity_level: SensitivityLevel,
-        consent_required: bool,
+        appointment_id: u64,
     ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
         caller.require_auth();
 
-        // Only SystemAdmin can create policies
-        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
-            return Err(ContractError::Unauthorized);
+        let mut appt = appointment::get_appointment(&env, appointment_id)
+            .ok_or(ContractError::AppointmentNotFound)?;
+
+        if caller != appt.patient
+            && caller != appt.provider
+            && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "cancel_appointment",
+                "patient_or_provider_or_SystemAdmin",
+            );
         }
 
-        let conditions = rbac::PolicyConditions {
-            required_role,
-            time_restriction,
-            required_credential,
-            min_sensitivity_level,
-            consent_required,
-        };
+        let previous_status = appt.status.clone();
+        if previous_status != AppointmentStatus::Scheduled
+            && previous_status != AppointmentStatus::Confirmed
+            && previous_status != AppointmentStatus::Rescheduled
+        {
+            return Err(ContractError::InvalidAppointmentStatus);
+        }
 
-        let policy = rbac::AccessPolicy {
-            id: policy_id.clone(),
-            name,
-            conditions,
-            enabled: true,
+        let now = env.ledger().timestamp();
+        let window = appointment::get_cancellation_window(&env);
+        let late = window > 0 && now.saturating_add(window) > appt.scheduled_at;
+
+        appt.status = AppointmentStatus::Cancelled;
+        appt.late_cancellation = late;
+        appt.updated_at = now;
+        appointment::set_appointment(&env, &appt);
+
+        let action = if late {
+            "LATE_CANCELLATION"
+        } else {
+            "CANCELLED"
         };
+        appointment::add_history_entry(
+            &env,
+            &AppointmentHistoryEntry {
+                appointment_id,
+                action: String::from_str(&env, action),
+                actor: caller.clone(),
+                timestamp: now,
+                previous_status,
+                new_status: AppointmentStatus::Cancelled,
+                notes: None,
+            },
+        );
 
-        rbac::create_access_policy(&env, policy);
-        events::publish_policy_created(&env, policy_id, caller);
+        if late {
+            events::publish_appointment_late_cancelled(
+                &env,
+                appointment_id,
+                appt.patient,
+                appt.provider,
+                caller,
+                appt.scheduled_at,
+            );
+        } else {
+            events::publish_appointment_cancelled(
+                &env,
+                appointment_id,
+                appt.patient,
+                appt.provider,
+                caller,
+            );
+        }
 
         Ok(())
     }
 
-    /// Set credential type for a user
-    pub fn set_user_credential(
+    /// Move a scheduled, confirmed or already rescheduled appointment to
+    /// `new_scheduled_at`. The caller must be the patient, the provider, or
+    /// hold `SystemAdmin`. Reminders are reset for the new time. Fails with
+    /// `TooManyReschedules` once the configured limit is reached.
+    pub fn reschedule_appointment(
         env: Env,
         caller: Address,
-        user: Address,
-        credential: CredentialType,
+        appointment_id: u64,
+        new_scheduled_at: u64,
     ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
         caller.require_auth();
 
-        // Only SystemAdmin can set credentials
-        if !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
-            return Err(ContractError::Unauthorized);
+        let mut appt = appointment::get_appointment(&env, appointment_id)
+            .ok_or(ContractError::AppointmentNotFound)?;
+
+        if caller != appt.patient
+            && caller != appt.provider
+            && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "reschedule_appointment",
+                "patient_or_provider_or_SystemAdmin",
+            );
         }
 
-        rbac::set_user_credential(&env, user.clone(), credential);
-        events::publish_credential_set(&env, user, credential, caller);
+        let previous_status = appt.status.clone();
+        if previous_status != AppointmentStatus::Scheduled
+            && previous_status != AppointmentStatus::Confirmed
+            && previous_status != AppointmentStatus::Rescheduled
+        {
+            return Err(ContractError::InvalidAppointmentStatus);
+        }
+
+        let max = appointment::get_max_reschedules(&env);
+        if max > 0 && appt.reschedule_count >= max {
+            return Err(ContractError::TooManyReschedules);
+        }
+
+        let now = env.ledger().timestamp();
+        if new_scheduled_at <= now {
+            return Err(ContractError::InvalidAppointmentTime);
+        }
+        if !appointment::is_slot_available(
+            &env,
+            &appt.provider,
+            new_scheduled_at,
+            appt.duration_minutes,
+            Some(appointment_id),
+        ) {
+            return Err(ContractError::SlotUnavailable);
+        }
+
+        let old_scheduled_at = appt.scheduled_at;
+        appt.scheduled_at = new_scheduled_at;
+        appt.status = AppointmentStatus::Rescheduled;
+        appt.reminders_sent = Vec::new(&env);
+        appt.reschedule_count = appt.reschedule_count.saturating_add(1);
+        appt.updated_at = now;
+        appointment::set_appointment(&env, &appt);
+
+        appointment::add_history_entry(
+            &env,
+            &AppointmentHistoryEntry {
+                appointment_id,
+                action: String::from_str(&env, "RESCHEDULED"),
+                actor: caller.clone(),
+                timestamp: now,
+                previous_status,
+                new_status: AppointmentStatus::Rescheduled,
+                notes: None,
+            },
+        );
+
+        events::publish_appointment_rescheduled(
+            &env,
+            appointment_id,
+            appt.patient,
+            appt.provider,
+            old_scheduled_at,
+            new_scheduled_at,
+            caller,
+        );
 
         Ok(())
     }
 
-    /// Set sensitivity level for a record
-    pub fn set_record_sensitivity(
+    /// Set how close to its start an appointment can be cancelled before the
+    /// cancellation is flagged as late. Zero disables the check. Requires
+    /// `SystemAdmin`.
+    pub fn set_cancellation_window(
         env: Env,
-        caller: Address,
-        record_id: u64,
-        sensitivity: SensitivityLevel,
+        admin: Address,
+        seconds: u64,
     ) -> Result<(), ContractError> {
-        caller.require_auth();
-
-        // Only record provider or SystemAdmin can set sensitivity
-        let record_key = (symbol_short!("RECORD"), record_id);
-        let record: VisionRecord = env
-            .storage()
-            .persistent()
-            .get(&record_key)
-            .ok_or(ContractError::RecordNotFound)?;
-
-        let has_perm = caller == record.provider
-            || rbac::has_permission(&env, &caller, &Permission::SystemAdmin);
-        if !has_perm {
-            return Err(ContractError::Unauthorized);
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &admin,
+                "set_cancellation_window",
+                "permission:SystemAdmin",
+            );
         }
 
-        rbac::set_record_sensitivity(&env, record_id, sensitivity);
-        events::publish_sensitivity_set(&env, record_id, sensitivity, caller);
+        appointment::set_cancellation_window(&env, seconds);
 
         Ok(())
     }
 
-    /// Check access for a specific record with ABAC evaluation
-    /// Prepare phase for register_user operation
-    pub fn prepare_register_user(
+    /// Return the configured late-cancellation window in seconds.
+    pub fn get_cancellation_window(env: Env) -> u64 {
+        appointment::get_cancellation_window(&env)
+    }
+
+    /// Set how many no-shows a patient may accumulate before only their
+    /// provider (or a SystemAdmin) can book for them. 0 disables the gate.
+    pub fn set_no_show_threshold(
         env: Env,
-        caller: Address,
-        user: Address,
-        role: Role,
-        name: String,
+        admin: Address,
+        threshold: u32,
     ) -> Result<(), ContractError> {
-        // Validate all inputs without making state changes
-        circuit_breaker::require_not_paused(
-            &env,
-            &circuit_breaker::PauseScope::Function(symbol_short!("REG_USR")),
-        )?;
-
-        if !whitelist::check_whitelist_access(&env, &caller) {
-            return Err(ContractError::Unauthorized);
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &admin,
+                "set_no_show_threshold",
+                "permission:SystemAdmin",
+            );
         }
 
-        if !rbac::has_permission(&env, &caller, &Permission::ManageUsers) {
-            return Err(ContractError::Unauthorized);
-        }
+        appointment::set_no_show_threshold(&env, threshold);
 
-        validation::validate_name(&name)?;
+        Ok(())
+    }
 
-        // Check if user already exists
-        let key = (symbol_short!("USER"), user.clone());
-        if env.storage().persistent().get::<_, User>(&key).is_some() {
-            return Err(ContractError::UserAlreadyExists);
-        }
+    /// Return the configured no-show threshold (0 = disabled).
+    pub fn get_no_show_threshold(env: Env) -> u32 {
+        appointment::get_no_show_threshold(&env)
+    }
 
-        // Store temporary preparation data
-        let prep_key = (symbol_short!("P_REG_USR"), user.clone());
-        let prep_data = PrepareUserRegistration {
-            caller: caller.clone(),
-            user: user.clone(),
-            role,
-            name: name.clone(),
-            timestamp: env.ledger().timestamp(),
-        };
-        env.storage().temporary().set(&prep_key, &prep_data);
+    /// Return how many appointments `patient` has missed since the last reset.
+    pub fn get_patient_no_show_count(env: Env, patient: Address) -> u32 {
+        appointment::get_patient_no_shows(&env, &patient)
+    }
 
-        Ok(())
+    /// Whether new bookings for `patient` must be made by the provider.
+    pub fn schedule_requires_approval(env: Env, patient: Address) -> bool {
+        appointment::schedule_requires_approval(&env, &patient)
     }
 
-    /// Commit phase for register_user operation
-    pub fn commit_register_user(
+    /// Clear a patient's no-show count, lifting the scheduling gate.
+    pub fn reset_patient_no_shows(
         env: Env,
-        caller: Address,
-        user: Address,
-        role: Role,
-        name: String,
+        admin: Address,
+        patient: Address,
     ) -> Result<(), ContractError> {
-        // Retrieve preparation data
-        let prep_key = (symbol_short!("P_REG_USR"), user.clone());
-        let prep_data: PrepareUserRegistration = env
-            .storage()
-            .temporary()
-            .get(&prep_key)
-            .ok_or(ContractError::InvalidPhase)?;
-
-        // Verify preparation data matches commit parameters
-        if prep_data.caller != caller
-            || prep_data.user != user
-            || prep_data.role != role
-            || prep_data.name != name
-        {
-            return Err(ContractError::InvalidPhase);
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &admin,
+                "reset_patient_no_shows",
+                "permission:SystemAdmin",
+            );
         }
 
-        // Execute the actual registration
-        let user_data = User {
-            address: user.clone(),
-            role,
-            name: name.clone(),
-            registered_at: env.ledger().timestamp(),
-            is_active: true,
-        };
+        appointment::reset_patient_no_shows(&env, &patient);
 
-        let key = (symbol_short!("USER"), user.clone());
-        env.storage().persistent().set(&key, &user_data);
-        extend_ttl_address_key(&env, &key);
-        rbac::assign_role(&env, user.clone(), role, 0);
+        Ok(())
+    }
 
-        // Clean up preparation data
-        env.storage().temporary().remove(&prep_key);
+    /// Set how many times a single appointment may be rescheduled. Zero
+    /// means unlimited. Requires `SystemAdmin`.
+    pub fn set_max_reschedules(env: Env, admin: Address, max: u32) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !rbac::has_permission(&env, &admin, &Permission::SystemAdmin) {
+            return Self::unauthorized(
+                &env,
+                &admin,
+                "set_max_reschedules",
+                "permission:SystemAdmin",
+            );
+        }
 
-        events::publish_user_registered(&env, user, role, name);
+        appointment::set_max_reschedules(&env, max);
 
         Ok(())
     }
 
-    /// Rollback for register_user operation
-    pub fn rollback_register_user(
+    /// Return the configured reschedule limit (0 = unlimited).
+    pub fn get_max_reschedules(env: Env) -> u32 {
+        appointment::get_max_reschedules(&env)
+    }
+
+    /// Queue `patient` for the next slot with `provider` at or after
+    /// `desired_after`.
+    pub fn join_waitlist(
         env: Env,
-        user: Address,
-        _role: Role,
-        _name: String,
+        patient: Address,
+        provider: Address,
+        desired_after: u64,
+        appointment_type: AppointmentType,
     ) -> Result<(), ContractError> {
-        // Clean up preparation data
-        let prep_key = (symbol_short!("P_REG_USR"), user.clone());
-        env.storage().temporary().remove(&prep_key);
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        patient.require_auth();
+
+        let mut waitlist = appointment::get_waitlist(&env, &provider);
+        for entry in waitlist.iter() {
+            if entry.patient == patient {
+                return Err(ContractError::DuplicateRecord);
+            }
+        }
+
+        waitlist.push_back(WaitlistEntry {
+            patient,
+            appointment_type,
+            desired_after,
+            joined_at: env.ledger().timestamp(),
+        });
+        appointment::set_waitlist(&env, &provider, &waitlist);
 
         Ok(())
     }
 
-    // ======================== Two-Phase Commit Hooks ========================
-
-    /// Prepare phase for adding a vision record
-    pub fn prepare_add_record(
+    /// Book the earliest-joined waitlist entry that can take the slot at
+    /// `slot_at`, removing it from the waitlist. Returns the new appointment
+    /// ID, or `AppointmentNotFound` when nobody on the waitlist fits.
+    pub fn promote_from_waitlist(
         env: Env,
-        caller: Address,
-        patient: Address,
         provider: Address,
-        record_type: RecordType,
-        data_hash: String,
+        slot_at: u64,
+        duration_minutes: u32,
     ) -> Result<u64, ContractError> {
-        // Validate all inputs without making state changes
-        circuit_breaker::require_not_paused(
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
+
+        let mut waitlist = appointment::get_waitlist(&env, &provider);
+        let mut index = None;
+        for (i, entry) in waitlist.iter().enumerate() {
+            if entry.desired_after <= slot_at {
+                index = Some(i as u32);
+                break;
+            }
+        }
+        let index = index.ok_or(ContractError::AppointmentNotFound)?;
+        let entry = waitlist
+            .get(index)
+            .ok_or(ContractError::AppointmentNotFound)?;
+
+        let appointment_id = Self::book_appointment(
             &env,
-            &circuit_breaker::PauseScope::Function(symbol_short!("ADD_REC")),
+            provider.clone(),
+            entry.patient.clone(),
+            provider.clone(),
+            entry.appointment_type,
+            slot_at,
+            duration_minutes,
+            None,
+            None,
         )?;
 
-        if !whitelist::check_whitelist_access(&env, &caller) {
-            return Err(ContractError::Unauthorized);
-        }
+        waitlist.remove(index);
+        appointment::set_waitlist(&env, &provider, &waitlist);
+
+        events::publish_waitlist_promoted(&env, appointment_id, entry.patient, provider, slot_at);
+
+        Ok(appointment_id)
+    }
+
+    /// Return a provider's waitlist in join order.
+    pub fn get_waitlist(env: Env, provider: Address) -> Vec<WaitlistEntry> {
+        appointment::get_waitlist(&env, &provider)
+    }
+
+    /// Mark an appointment as completed. Only the appointment's provider or a
+    /// system admin may do this.
+    pub fn complete_appointment(
+        env: Env,
+        provider: Address,
+        appointment_id: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
 
-        Self::enforce_rate_limit(&env, &caller)?;
-        validation::validate_data_hash(&data_hash)?;
+        let mut appt = appointment::get_appointment(&env, appointment_id)
+            .ok_or(ContractError::AppointmentNotFound)?;
 
-        // Check permissions
-        let has_perm = if caller == provider {
-            rbac::has_permission(&env, &caller, &Permission::WriteRecord)
-        } else {
-            rbac::has_delegated_permission(&env, &provider, &caller, &Permission::WriteRecord)
-        };
+        if provider != appt.provider
+            && !rbac::has_permission(&env, &provider, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &provider,
+                "complete_appointment",
+                "appointment_provider_or_SystemAdmin",
+            );
+        }
 
-        if !has_perm && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
-            return Err(ContractError::Unauthorized);
+        let previous_status = appt.status.clone();
+        if previous_status != AppointmentStatus::Scheduled
+            && previous_status != AppointmentStatus::Confirmed
+            && previous_status != AppointmentStatus::Rescheduled
+        {
+            return Err(ContractError::InvalidAppointmentStatus);
         }
 
-        // Generate and return the record ID that will be used
-        let counter_key = symbol_short!("REC_CTR");
-        let record_id: u64 = env
-            .storage()
-            .instance()
-            .get(&counter_key)
-            .unwrap_or(0u64)
-            .saturating_add(1u64);
+        let now = env.ledger().timestamp();
+        appt.status = AppointmentStatus::Completed;
+        appt.updated_at = now;
+        appointment::set_appointment(&env, &appt);
+        provider::appointment_completed(&env, &appt.provider);
 
-        // Store preparation data temporarily
-        let prep_key = (symbol_short!("P_ADD_REC"), record_id);
-        let prep_data = PrepareAddRecord {
-            caller,
-            patient,
+        appointment::add_history_entry(
+            &env,
+            &AppointmentHistoryEntry {
+                appointment_id,
+                action: String::from_str(&env, "COMPLETED"),
+                actor: provider.clone(),
+                timestamp: now,
+                previous_status,
+                new_status: AppointmentStatus::Completed,
+                notes: None,
+            },
+        );
+
+        events::publish_appointment_completed(
+            &env,
+            appointment_id,
+            appt.patient,
+            appt.provider,
             provider,
-            record_type,
-            data_hash,
-            timestamp: env.ledger().timestamp(),
-        };
-        env.storage().temporary().set(&prep_key, &prep_data);
+        );
 
-        Ok(record_id)
+        Ok(())
     }
 
-    /// Commit phase for adding a vision record
-    pub fn commit_add_record(env: Env, record_id: u64) -> Result<(), ContractError> {
-        // Retrieve preparation data
-        let prep_key = (symbol_short!("P_ADD_REC"), record_id);
-        let prep_data: PrepareAddRecord = env
-            .storage()
-            .temporary()
-            .get(&prep_key)
-            .ok_or(ContractError::InvalidInput)?;
+    /// Link a completed appointment to the vision record produced during
+    /// the visit. The record must belong to the same patient and provider.
+    pub fn attach_record_to_appointment(
+        env: Env,
+        provider: Address,
+        appointment_id: u64,
+        record_id: u64,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
 
-        // Update the counter
-        let counter_key = symbol_short!("REC_CTR");
-        env.storage().instance().set(&counter_key, &record_id);
+        let mut appt = appointment::get_appointment(&env, appointment_id)
+            .ok_or(ContractError::AppointmentNotFound)?;
 
-        // Create the actual record
-        let record = VisionRecord {
-            id: record_id,
-            patient: prep_data.patient.clone(),
-            provider: prep_data.provider.clone(),
-            record_type: prep_data.record_type.clone(),
-            data_hash: prep_data.data_hash.clone(),
-            key_version: None,
-            created_at: prep_data.timestamp,
-            updated_at: prep_data.timestamp,
-        };
+        if provider != appt.provider
+            && !rbac::has_permission(&env, &provider, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &provider,
+                "attach_record_to_appointment",
+                "appointment_provider_or_SystemAdmin",
+            );
+        }
 
-        // Store the record
-        let key = (symbol_short!("RECORD"), record_id);
-        env.storage().persistent().set(&key, &record);
-        extend_ttl_u64_key(&env, &key);
+        if appt.status != AppointmentStatus::Completed {
+            return Err(ContractError::InvalidAppointmentStatus);
+        }
+        if appt.record_id.is_some() {
+            return Err(ContractError::DuplicateRecord);
+        }
 
-        // Add to patient's record list
-        let patient_key = (symbol_short!("PAT_REC"), prep_data.patient.clone());
-        let mut patient_records: Vec<u64> = env
+        let record: VisionRecord = env
             .storage()
             .persistent()
-            .get(&patient_key)
-            .unwrap_or(Vec::new(&env));
-        patient_records.push_back(record_id);
-        env.storage()
-            .persistent()
-            .set(&patient_key, &patient_records);
-        extend_ttl_address_key(&env, &patient_key);
+            .get(&(symbol_short!("RECORD"), record_id))
+            .ok_or(ContractError::RecordNotFound)?;
+        if record.patient != appt.patient || record.provider != appt.provider {
+            return Err(ContractError::InvalidInput);
+        }
 
-        // Clean up preparation data
-        env.storage().temporary().remove(&prep_key);
+        appt.record_id = Some(record_id);
+        appt.updated_at = env.ledger().timestamp();
+        appointment::set_appointment(&env, &appt);
 
         Ok(())
     }
 
-    /// Rollback phase for adding a vision record
-    pub fn rollback_add_record(env: Env, record_id: u64) -> Result<(), ContractError> {
-        // Clean up preparation data
-        let prep_key = (symbol_short!("P_ADD_REC"), record_id);
-        env.storage().temporary().remove(&prep_key);
-
-        Ok(())
+    /// Return the ID of the record attached to an appointment, if any. The
+    /// record itself is read through `get_record` so access checks apply.
+    pub fn get_appointment_record(
+        env: Env,
+        appointment_id: u64,
+    ) -> Result<Option<u64>, ContractError> {
+        let appt = appointment::get_appointment(&env, appointment_id)
+            .ok_or(ContractError::AppointmentNotFound)?;
+        Ok(appt.record_id)
     }
 
-    /// Prepare phase for granting access
-    pub fn prepare_grant_access(
+    /// Mark a past scheduled or confirmed appointment as a no-show. Only the
+    /// appointment's provider or a system admin may do this.
+    pub fn mark_no_show(
         env: Env,
-        caller: Address,
-        patient: Address,
-        grantee: Address,
-        access_level: AccessLevel,
-        expires_at: Option<u64>,
+        provider: Address,
+        appointment_id: u64,
     ) -> Result<(), ContractError> {
-        // Validate inputs without state changes
-        if caller != patient && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin) {
-            return Err(ContractError::Unauthorized);
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
+
+        let mut appt = appointment::get_appointment(&env, appointment_id)
+            .ok_or(ContractError::AppointmentNotFound)?;
+
+        if provider != appt.provider
+            && !rbac::has_permission(&env, &provider, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &provider,
+                "mark_no_show",
+                "appointment_provider_or_SystemAdmin",
+            );
         }
 
-        if access_level == AccessLevel::None {
-            return Err(ContractError::InvalidInput);
+        let previous_status = appt.status.clone();
+        if previous_status != AppointmentStatus::Scheduled
+            && previous_status != AppointmentStatus::Confirmed
+        {
+            return Err(ContractError::InvalidAppointmentStatus);
         }
 
-        // Store preparation data
-        let prep_key = (symbol_short!("P_GNT_ACC"), patient.clone(), grantee.clone());
-        let prep_data = PrepareGrantAccess {
-            caller,
-            patient,
-            grantee,
-            access_level,
-            expires_at,
-            timestamp: env.ledger().timestamp(),
-        };
-        env.storage().temporary().set(&prep_key, &prep_data);
+        let now = env.ledger().timestamp();
+        if appt.scheduled_at > now {
+            return Err(ContractError::InvalidAppointmentTime);
+        }
+
+        appt.status = AppointmentStatus::NoShow;
+        appt.updated_at = now;
+        appointment::set_appointment(&env, &appt);
+        provider::appointment_no_show(&env, &appt.provider);
+        appointment::record_patient_no_show(&env, &appt.patient);
+
+        appointment::add_history_entry(
+            &env,
+            &AppointmentHistoryEntry {
+                appointment_id,
+                action: String::from_str(&env, "NO_SHOW"),
+                actor: provider,
+                timestamp: now,
+                previous_status,
+                new_status: AppointmentStatus::NoShow,
+                notes: None,
+            },
+        );
 
         Ok(())
     }
 
-    /// Commit phase for granting access
-    pub fn commit_grant_access(
+    /// Keeper entry point: mark appointments still `Scheduled` a day after
+    /// their start time as no-shows. Returns how many were updated.
+    pub fn expire_past_appointments(env: Env) -> u32 {
+        appointment::expire_past_appointments(&env, &env.current_contract_address())
+    }
+
+    /// Send reminders for upcoming appointments within
+    /// `reminder_window_seconds`, or within an appointment's own
+    /// `reminder_lead_seconds` when it set one. Each lead time acts as its own
+    /// reminder bucket, so a 24h run followed by a 1h run reminds the same
+    /// appointment twice, but repeating a run does not. Returns how many
+    /// reminders were sent.
+    ///
+    /// Appointments with their own lead time are queued by the day it opens,
+    /// so they are found however long the lead; a week's worth of that queue
+    /// is drained per call.
+    pub fn send_appointment_reminders(env: Env, reminder_window_seconds: u64) -> u32 {
+        let mut due = appointment::take_due_lead_reminders(&env);
+        due.append(&appointment::get_appointments_needing_reminders(
+            &env,
+            reminder_window_seconds,
+        ));
+        let mut sent = 0u32;
+        for appt in due.iter() {
+            let bucket = appt
+                .reminder_lead_seconds
+                .unwrap_or(reminder_window_seconds);
+            if appointment::mark_reminder_sent(&env, appt.id, bucket).is_some() {
+                events::publish_appointment_reminder(
+                    &env,
+                    appt.id,
+                    appt.patient,
+                    appt.provider,
+                    appt.scheduled_at,
+                );
+                sent = sent.saturating_add(1);
+            }
+        }
+        sent
+    }
+
+    /// Return every appointment booked with a provider.
+    pub fn get_provider_appointments(env: Env, provider: Address) -> Vec<Appointment> {
+        appointment::get_provider_appointments(&env, &provider)
+    }
+
+    /// Return a provider's appointments with the given status.
+    pub fn get_provider_appts_by_status(
         env: Env,
-        patient: Address,
-        grantee: Address,
-    ) -> Result<(), ContractError> {
-        // Retrieve preparation data
-        let prep_key = (symbol_short!("P_GNT_ACC"), patient.clone(), grantee.clone());
-        let prep_data: PrepareGrantAccess = env
-            .storage()
-            .temporary()
-            .get(&prep_key)
-            .ok_or(ContractError::InvalidInput)?;
+        provider: Address,
+        status: AppointmentStatus,
+    ) -> Vec<Appointment> {
+        appointment::get_provider_appointments_by_status(&env, &provider, &status)
+    }
 
-        // Create the access grant
-        let grant = AccessGrant {
-            patient: prep_data.patient.clone(),
-            grantee: prep_data.grantee.clone(),
-            level: prep_data.access_level,
-            granted_at: prep_data.timestamp,
-            expires_at: prep_data.expires_at.unwrap_or(0),
-        };
+    /// Return a provider's appointments scheduled in `[start_ts, end_ts)`.
+    pub fn get_provider_appts_in_range(
+        env: Env,
+        provider: Address,
+        start_ts: u64,
+        end_ts: u64,
+    ) -> Vec<Appointment> {
+        appointment::get_provider_appointments_in_range(&env, &provider, start_ts, end_ts)
+    }
 
-        // Store the grant
-        let key = (
-            symbol_short!("GRANT"),
-            prep_data.patient.clone(),
-            prep_data.grantee.clone(),
-        );
-        env.storage().persistent().set(&key, &grant);
-        extend_ttl_access_key(&env, &key);
+    /// Return the change history of an appointment.
+    pub fn get_appointment_history(env: Env, appointment_id: u64) -> Vec<AppointmentHistoryEntry> {
+        appointment::get_appointment_history(&env, appointment_id)
+    }
 
-        // Add to patient's grants list
-        let grants_key = (symbol_short!("GRANTS"), prep_data.patient.clone());
-        let mut grants: Vec<Address> = env
-            .storage()
-            .persistent()
-            .get(&grants_key)
-            .unwrap_or(Vec::new(&env));
+    // ── Prescriptions ─────────────────────────────────────────────────────────
 
-        if !grants.contains(&prep_data.grantee) {
-            grants.push_back(prep_data.grantee.clone());
-            env.storage().persistent().set(&grants_key, &grants);
-            extend_ttl_address_key(&env, &grants_key);
+    /// Issue a new prescription for a patient.
+    pub fn add_prescription(
+        env: Env,
+        patient: Address,
+        provider: Address,
+        lens_type: LensType,
+        left_eye: PrescriptionData,
+        right_eye: PrescriptionData,
+        contact_data: OptionalContactLensData,
+        duration_seconds: u64,
+        metadata_hash: String,
+        refills_allowed: u32,
+    ) -> Result<u64, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
+
+        if !rbac::has_permission(&env, &provider, &Permission::WriteRecord)
+            && !rbac::has_permission(&env, &provider, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &provider,
+                "add_prescription",
+                "permission:WriteRecord_or_SystemAdmin",
+            );
         }
 
-        // Clean up preparation data
-        env.storage().temporary().remove(&prep_key);
+        validation::validate_prescription_data(&left_eye)?;
+        validation::validate_prescription_data(&right_eye)?;
 
-        Ok(())
+        let now = env.ledger().timestamp();
+        let rx = Prescription {
+            id: prescription::next_prescription_id(&env),
+            patient,
+            provider,
+            lens_type,
+            left_eye,
+            right_eye,
+            contact_data,
+            issued_at: now,
+            expires_at: now.saturating_add(duration_seconds),
+            verified: false,
+            metadata_hash,
+            revoked: false,
+            revoked_reason: None,
+            supersedes: None,
+            refills_allowed,
+            refills_used: 0,
+        };
+
+        prescription::save_prescription(&env, &rx, None);
+        events::publish_prescription_issued(&env, rx.id, rx.patient, rx.provider, rx.lens_type);
+
+        Ok(rx.id)
     }
 
-    /// Rollback phase for granting access
-    pub fn rollback_grant_access(
+    /// Retrieve a prescription by ID. Revoked prescriptions are still returned
+    /// with `revoked` set so callers can see why they are no longer usable.
+    ///
+    /// Readable by the patient, the prescribing provider, holders of
+    /// `ReadAnyRecord`, and grantees with active access to the patient.
+    pub fn get_prescription(
         env: Env,
-        patient: Address,
-        grantee: Address,
-    ) -> Result<(), ContractError> {
-        // Clean up preparation data
-        let prep_key = (symbol_short!("P_GNT_ACC"), patient.clone(), grantee.clone());
-        env.storage().temporary().remove(&prep_key);
+        caller: Address,
+        rx_id: u64,
+    ) -> Result<Prescription, ContractError> {
+        caller.require_auth();
+        let rx =
+            prescription::get_prescription(&env, rx_id).ok_or(ContractError::RecordNotFound)?;
+
+        let has_access = caller == rx.patient
+            || caller == rx.provider
+            || rbac::has_permission(&env, &caller, &Permission::ReadAnyRecord)
+            || Self::check_access(env.clone(), rx.patient.clone(), caller.clone())
+                != AccessLevel::None;
+        if !has_access {
+            let audit_entry = audit::create_audit_entry(
+                &env,
+                caller.clone(),
+                rx.patient.clone(),
+                None,
+                AccessAction::Read,
+                AccessResult::Denied,
+                Some(String::from_str(&env, "Prescription access denied")),
+            );
+            audit::add_audit_entry(&env, &audit_entry);
+            events::publish_audit_log_entry(&env, &audit_entry);
+
+            return Self::access_denied(
+                &env,
+                &caller,
+                "get_prescription",
+                "prescription_read_access",
+            );
+        }
 
-        Ok(())
+        Ok(rx)
     }
 
-    pub fn trace_record_ancestors(
+    /// Mark a prescription as verified by `verifier`. Requires
+    /// `DispensePrescription` or `SystemAdmin`; returns `false` if the
+    /// prescription does not exist.
+    pub fn verify_prescription(
         env: Env,
-        record_id: u64,
-        max_depth: u32,
-    ) -> teye_common::lineage::TraversalResult {
-        provenance_graph::trace_ancestors(&env, record_id, max_depth)
-    }
+        rx_id: u64,
+        verifier: Address,
+    ) -> Result<bool, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        verifier.require_auth();
 
-    pub fn trace_record_descendants(
-        env: Env,
-        record_id: u64,
-        max_depth: u32,
-    ) -> teye_common::lineage::TraversalResult {
-        provenance_graph::trace_descendants(&env, record_id, max_depth)
-    }
+        if !rbac::has_permission(&env, &verifier, &Permission::DispensePrescription)
+            && !rbac::has_permission(&env, &verifier, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &verifier,
+                "verify_prescription",
+                "permission:DispensePrescription_or_SystemAdmin",
+            );
+        }
 
-    pub fn export_record_dag(env: Env, record_id: u64, max_depth: u32) -> ProvenanceExport {
-        provenance_graph::export_dag(&env, record_id, max_depth)
+        let verified = prescription::verify_prescription(&env, rx_id);
+        if verified {
+            events::publish_prescription_verified(&env, rx_id, verifier);
+        }
+        Ok(verified)
     }
 
-    pub fn verify_record_provenance(
-        env: Env,
-        record_id: u64,
-        max_depth: u32,
-    ) -> teye_common::lineage::VerificationResult {
-        provenance_graph::verify_provenance(&env, record_id, max_depth)
+    /// Return the IDs of every prescription issued to a patient.
+    pub fn get_prescription_history(env: Env, patient: Address) -> Vec<u64> {
+        prescription::get_patient_history(&env, patient)
     }
 
-    pub fn check_lineage_based_access(
-        env: Env,
-        record_id: u64,
-        requester: Address,
-        max_depth: u32,
-    ) -> LineageAccessResult {
-        let (result, _) =
-            provenance_graph::check_lineage_access(&env, record_id, &requester, max_depth);
-        result
+    /// Whether a prescription can currently be filled: it must exist, be
+    /// verified, not be revoked, and not have expired.
+    pub fn is_prescription_valid(env: Env, rx_id: u64) -> bool {
+        prescription::is_prescription_valid(&env, rx_id)
     }
 
-    pub fn collect_lineage_actors(env: Env, record_id: u64, max_depth: u32) -> Vec<Address> {
-        provenance_graph::collect_lineage_actors(&env, record_id, max_depth)
+    /// Return the IDs of the patient's currently valid prescriptions.
+    pub fn get_active_prescriptions(env: Env, patient: Address) -> Vec<u64> {
+        prescription::get_active_prescriptions(&env, patient)
     }
 
-    pub fn transition_record_state(
-        env: Env,
-        caller: Address,
-        record_id: u64,
-        to_state: LifecycleState,
-        retention_until: u64,
-        expires_at: u64,
-        prerequisites_met: bool,
-    ) -> Result<TransitionRecord, ContractError> {
-        caller.require_auth();
+    /// Record a fill of a valid prescription, consuming one refill.
+    /// Requires `DispensePrescription` or `SystemAdmin`.
+    pub fn record_dispense(env: Env, dispenser: Address, rx_id: u64) -> Result<u32, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        dispenser.require_auth();
 
-        let role = match rbac::get_active_assignment(&env, &caller).map(|a| a.role) {
-            Some(Role::Admin) => symbol_short!("ADMIN"),
-            Some(Role::Ophthalmologist) => symbol_short!("OPHT"),
-            Some(Role::Optometrist) => symbol_short!("PROV"),
-            _ => symbol_short!("USER"),
-        };
+        if !rbac::has_permission(&env, &dispenser, &Permission::DispensePrescription)
+            && !rbac::has_permission(&env, &dispenser, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &dispenser,
+                "record_dispense",
+                "permission:DispensePrescription_or_SystemAdmin",
+            );
+        }
 
-        let ctx = TransitionContext {
-            actor: caller,
-            actor_role: role,
-            now: env.ledger().timestamp(),
-            retention_until,
-            expires_at,
-            prerequisites_met,
-        };
+        let mut rx =
+            prescription::get_prescription(&env, rx_id).ok_or(ContractError::RecordNotFound)?;
 
-        state_machine::apply_transition(
+        if !prescription::is_prescription_valid(&env, rx_id) {
+            return Err(ContractError::InvalidInput);
+        }
+
+        if rx.refills_used >= rx.refills_allowed {
+            return Err(ContractError::RefillLimitReached);
+        }
+
+        rx.refills_used = rx.refills_used.saturating_add(1);
+        prescription::update_prescription(&env, &rx);
+
+        prescription::add_dispense_record(
             &env,
-            0,
-            &EntityKind::VisionRecord,
-            record_id,
-            to_state,
-            ctx,
-        )
-        .map_err(|_| ContractError::InvalidInput)
+            &DispenseRecord {
+                rx_id,
+                dispenser: dispenser.clone(),
+                refill_number: rx.refills_used,
+                dispensed_at: env.ledger().timestamp(),
+            },
+        );
+
+        events::publish_dispense(
+            &env,
+            rx_id,
+            rx.patient,
+            dispenser,
+            rx.refills_used,
+            rx.refills_allowed,
+        );
+
+        Ok(rx.refills_used)
     }
 
-    pub fn export_state_machine_dot(env: Env, kind: String) -> String {
-        if kind == String::from_str(&env, "vision_record") {
-            return state_machine::export_dot(&env, &EntityKind::VisionRecord);
-        }
-        state_machine::export_dot(&env, &EntityKind::Prescription)
+    /// Return the dispense log for a prescription.
+    pub fn get_dispense_history(env: Env, rx_id: u64) -> Vec<DispenseRecord> {
+        prescription::get_dispense_records(&env, rx_id)
     }
 
-    /// Prepare phase for adding a prescription
-    pub fn prepare_add_prescription(
+    /// Revoke a prescription. Only the issuing provider or a system admin may
+    /// revoke, and a revoked prescription cannot be revoked or renewed again.
+    pub fn revoke_prescription(
         env: Env,
-        patient: Address,
         provider: Address,
-        prescription_data: prescription::PrescriptionData,
-    ) -> Result<u64, ContractError> {
-        // Validate without state changes
-        validation::validate_prescription_data(&prescription_data);
+        rx_id: u64,
+        reason: String,
+    ) -> Result<(), ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
 
-        // Check provider permissions
-        let caller = provider.clone();
-        let has_perm = rbac::has_permission(&env, &caller, &Permission::WriteRecord)
-            || rbac::has_delegated_permission(&env, &provider, &caller, &Permission::WriteRecord)
-            || rbac::has_permission(&env, &caller, &Permission::SystemAdmin);
+        let mut rx =
+            prescription::get_prescription(&env, rx_id).ok_or(ContractError::RecordNotFound)?;
 
-        if !has_perm {
-            return Err(ContractError::Unauthorized);
+        if rx.provider != provider
+            && !rbac::has_permission(&env, &provider, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &provider,
+                "revoke_prescription",
+                "issuing_provider_or_SystemAdmin",
+            );
         }
 
-        // Generate prescription ID
-        let counter_key = symbol_short!("RX_CTR");
-        let rx_id: u64 = env
-            .storage()
-            .instance()
-            .get(&counter_key)
-            .unwrap_or(0u64)
-            .saturating_add(1u64);
+        if rx.revoked {
+            return Err(ContractError::InvalidInput);
+        }
 
-        // Store preparation data
-        let prep_key = (symbol_short!("P_ADD_RX"), rx_id);
-        let prep_data = PrepareAddPrescription {
-            patient,
+        rx.revoked = true;
+        rx.revoked_reason = Some(reason);
+        prescription::update_prescription(&env, &rx);
+
+        audit::AuditManager::log_event(
+            &env,
             provider,
-            prescription_data,
-            timestamp: env.ledger().timestamp(),
-        };
-        env.storage().temporary().set(&prep_key, &prep_data);
+            "prescription.revoke",
+            soroban_sdk::String::from_str(&env, &rx_id.to_string()),
+            "ok",
+        );
 
-        Ok(rx_id)
+        Ok(())
     }
 
-    /// Commit phase for adding a prescription
-    pub fn commit_add_prescription(env: Env, rx_id: u64) -> Result<(), ContractError> {
-        // Retrieve preparation data
-        let prep_key = (symbol_short!("P_ADD_RX"), rx_id);
-        let prep_data: PrepareAddPrescription = env
-            .storage()
-            .temporary()
-            .get(&prep_key)
-            .ok_or(ContractError::InvalidInput)?;
+    /// Renew a prescription by issuing a copy with a new expiry. The new
+    /// prescription records the prior one in `supersedes`, and the prior one
+    /// is revoked so only the renewal can be filled.
+    pub fn renew_prescription(
+        env: Env,
+        provider: Address,
+        rx_id: u64,
+        new_expiry: u64,
+    ) -> Result<u64, ContractError> {
+        circuit_breaker::require_not_paused(&env, &circuit_breaker::PauseScope::Global)?;
+        provider.require_auth();
 
-        // Update counter
-        let counter_key = symbol_short!("RX_CTR");
-        env.storage().instance().set(&counter_key, &rx_id);
+        let prior =
+            prescription::get_prescription(&env, rx_id).ok_or(ContractError::RecordNotFound)?;
 
-        // Create the prescription
-        let prescription = prescription::Prescription {
-            id: rx_id,
-            patient: prep_data.patient.clone(),
-            provider: prep_data.provider.clone(),
-            lens_type: LensType::Glasses,
-            left_eye: prep_data.prescription_data.clone(),
-            right_eye: prep_data.prescription_data.clone(),
-            contact_data: OptionalContactLensData::None,
-            issued_at: prep_data.timestamp,
-            expires_at: prep_data.timestamp.saturating_add(31_536_000),
-            verified: false,
-            metadata_hash: String::from_str(&env, ""),
-        };
+        if prior.provider != provider
+            && !rbac::has_permission(&env, &provider, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &provider,
+                "renew_prescription",
+                "issuing_provider_or_SystemAdmin",
+            );
+        }
 
-        // Store the prescription
-        let key = (symbol_short!("RX_DATA"), rx_id);
-        env.storage().persistent().set(&key, &prescription);
-        extend_ttl_u64_key(&env, &key);
+        if prior.revoked {
+            return Err(ContractError::InvalidInput);
+        }
 
-        // Add to patient's prescription history and lineage records.
-        prescription::save_prescription(&env, &prescription, None);
+        let now = env.ledger().timestamp();
+        if new_expiry <= now {
+            return Err(ContractError::InvalidTimestamp);
+        }
 
-        // Clean up preparation data
-        env.storage().temporary().remove(&prep_key);
+        let renewed = Prescription {
+            id: prescription::next_prescription_id(&env),
+            provider: provider.clone(),
+            issued_at: now,
+            expires_at: new_expiry,
+            verified: false,
+            supersedes: Some(rx_id),
+            refills_used: 0,
+            ..prior.clone()
+        };
 
-        Ok(())
-    }
+        prescription::save_prescription(&env, &renewed, None);
 
-    /// Rollback phase for adding a prescription
-    pub fn rollback_add_prescription(env: Env, rx_id: u64) -> Result<(), ContractError> {
-        // Clean up preparation data
-        let prep_key = (symbol_short!("P_ADD_RX"), rx_id);
-        env.storage().temporary().remove(&prep_key);
+        let mut superseded = prior;
+        superseded.revoked = true;
+        superseded.revoked_reason = Some(String::from_str(&env, "superseded"));
+        prescription::update_prescription(&env, &superseded);
 
-        Ok(())
+        lineage::add_edge(
+            &env,
+            rx_id,
+            renewed.id,
+            RelationshipKind::DerivedFrom,
+            provider,
+            None,
+        );
+
+        Ok(renewed.id)
     }
 
     // ── Query helpers ─────────────────────────────────────────────────────────
@@ -2816,6 +6990,78 @@ impl VisionRecordsContract {
         env.storage().instance().get(&counter_key).unwrap_or(0)
     }
 
+    /// Number of records held for `patient`.
+    pub fn get_patient_record_count(env: Env, patient: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<_, Vec<u64>>(&(symbol_short!("PAT_REC"), patient))
+            .map(|ids| ids.len())
+            .unwrap_or(0)
+    }
+
+    /// Number of records currently in `provider`'s custody.
+    pub fn get_provider_record_count(env: Env, provider: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<_, Vec<u64>>(&(symbol_short!("PRV_REC"), provider))
+            .map(|ids| ids.len())
+            .unwrap_or(0)
+    }
+
+    /// Activity totals for a provider: records authored (kept across custody
+    /// transfers), appointments completed and no-shows. Visible to the
+    /// provider and to callers holding `ManageUsers` or `SystemAdmin`.
+    pub fn get_provider_stats(
+        env: Env,
+        caller: Address,
+        provider: Address,
+    ) -> Result<ProviderStats, ContractError> {
+        caller.require_auth();
+        if caller != provider
+            && !rbac::has_permission(&env, &caller, &Permission::ManageUsers)
+            && !rbac::has_permission(&env, &caller, &Permission::SystemAdmin)
+        {
+            return Self::unauthorized(
+                &env,
+                &caller,
+                "get_provider_stats",
+                "provider_or_ManageUsers_or_SystemAdmin",
+            );
+        }
+        Ok(provider::get_provider_stats(&env, &provider))
+    }
+
+    /// Restrict who may author records of `record_type` to providers holding
+    /// one of `roles` or registered with one of `specialties`. Passing both
+    /// empty lifts the restriction. Requires the `ContractAdmin` tier.
+    pub fn set_record_type_requirement(
+        env: Env,
+        admin: Address,
+        record_type: RecordType,
+        roles: Vec<Role>,
+        specialties: Vec<String>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if !admin_tiers::require_tier(&env, &admin, &AdminTier::ContractAdmin) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        provider::set_record_type_requirement(
+            &env,
+            &record_type,
+            &RecordTypeRequirement { roles, specialties },
+        );
+        Ok(())
+    }
+
+    /// The authoring requirement for `record_type`, if one is configured.
+    pub fn get_record_type_requirement(
+        env: Env,
+        record_type: RecordType,
+    ) -> Option<RecordTypeRequirement> {
+        provider::get_record_type_requirement(&env, &record_type)
+    }
+
     /// Get multiple records by their IDs.
     pub fn get_records(env: Env, ids: Vec<u64>) -> Result<Vec<VisionRecord>, ContractError> {
         let mut records: Vec<VisionRecord> = Vec::new(&env);
@@ -2832,6 +7078,37 @@ impl VisionRecordsContract {
         Ok(records)
     }
 
+    /// Fetch several records with `get_record`'s access checks applied to
+    /// each one. Missing or unreadable ids are reported in place instead of
+    /// failing the batch; every attempt is audited.
+    ///
+    /// Because the call succeeds, denied reads keep their audit entry and
+    /// its reason. Denials through `get_record` and friends are rolled back
+    /// with the failing call.
+    pub fn get_records_checked(
+        env: Env,
+        caller: Address,
+        record_ids: Vec<u64>,
+    ) -> Vec<RecordFetchResult> {
+        caller.require_auth();
+        let mut results = Vec::new(&env);
+        for record_id in record_ids.iter() {
+            let result = match Self::read_record(
+                env.clone(),
+                caller.clone(),
+                record_id,
+                ConsentType::Treatment,
+                None,
+            ) {
+                Ok(record) => RecordFetchResult::Found(record),
+                Err(ContractError::RecordNotFound) => RecordFetchResult::NotFound(record_id),
+                Err(_) => RecordFetchResult::AccessDenied(record_id),
+            };
+            results.push_back(result);
+        }
+        results
+    }
+
     // ── Admin tier management ─────────────────────────────────────────────────
 
     /// Return the admin tier for a given address.
@@ -2894,3 +7171,21 @@ mod test_admin_tiers;
 
 #[cfg(test)]
 mod test_occ;
+
+#[cfg(test)]
+mod test_prescription;
+
+#[cfg(test)]
+mod test_rate_limit;
+
+#[cfg(test)]
+mod test_appointment;
+
+#[cfg(test)]
+mod test_provider;
+
+#[cfg(test)]
+mod test_emergency;
+
+#[cfg(test)]
+mod test_reentrancy;
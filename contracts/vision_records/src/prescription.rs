@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Env, String, Vec};
+use soroban_sdk::{contracttype, Address, Env, Map, String, Symbol, TryFromVal, Val, Vec};
 use teye_common::concurrency::{self, FieldChange, UpdateOutcome, VersionStamp};
 use teye_common::lineage::{self, RelationshipKind};
 use teye_common::state_machine::{
@@ -52,6 +52,88 @@ pub struct Prescription {
     pub expires_at: u64,
     pub verified: bool,
     pub metadata_hash: String,
+    /// Set once the issuing provider or an admin withdraws the prescription.
+    pub revoked: bool,
+    pub revoked_reason: Option<String>,
+    /// Prior prescription that this one renews, if any.
+    pub supersedes: Option<u64>,
+    pub refills_allowed: u32,
+    pub refills_used: u32,
+}
+
+/// Layout of prescriptions stored before revocation, renewal and refill
+/// tracking were added. Only ever read; see [`get_prescription`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyPrescription {
+    pub id: u64,
+    pub patient: Address,
+    pub provider: Address,
+    pub lens_type: LensType,
+    pub left_eye: PrescriptionData,
+    pub right_eye: PrescriptionData,
+    pub contact_data: OptionalContactLensData,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub verified: bool,
+    pub metadata_hash: String,
+}
+
+impl From<LegacyPrescription> for Prescription {
+    /// Older prescriptions carried no refill count, so they may be filled once.
+    fn from(rx: LegacyPrescription) -> Self {
+        Prescription {
+            id: rx.id,
+            patient: rx.patient,
+            provider: rx.provider,
+            lens_type: rx.lens_type,
+            left_eye: rx.left_eye,
+            right_eye: rx.right_eye,
+            contact_data: rx.contact_data,
+            issued_at: rx.issued_at,
+            expires_at: rx.expires_at,
+            verified: rx.verified,
+            metadata_hash: rx.metadata_hash,
+            revoked: false,
+            revoked_reason: None,
+            supersedes: None,
+            refills_allowed: 1,
+            refills_used: 0,
+        }
+    }
+}
+
+/// A single fill of a prescription by a pharmacy or optical shop.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DispenseRecord {
+    pub rx_id: u64,
+    pub dispenser: Address,
+    pub refill_number: u32,
+    pub dispensed_at: u64,
+}
+
+/// Returns the id the next prescription will receive without reserving it.
+pub fn peek_prescription_id(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&soroban_sdk::symbol_short!("RX_CTR"))
+        .unwrap_or(0u64)
+        .saturating_add(1u64)
+}
+
+/// Advances the shared `RX_CTR` counter to `rx_id`.
+pub fn set_prescription_counter(env: &Env, rx_id: u64) {
+    env.storage()
+        .instance()
+        .set(&soroban_sdk::symbol_short!("RX_CTR"), &rx_id);
+}
+
+/// Allocates the next prescription id from the shared `RX_CTR` counter.
+pub fn next_prescription_id(env: &Env) -> u64 {
+    let rx_id = peek_prescription_id(env);
+    set_prescription_counter(env, rx_id);
+    rx_id
 }
 
 /// Persists a prescription and initialises its lineage node.
@@ -128,9 +210,19 @@ pub fn save_prescription(env: &Env, prescription: &Prescription, exam_record_id:
     );
 }
 
+/// Loads a prescription, upgrading entries still in the [`LegacyPrescription`]
+/// layout. The upgraded form is written back on the next update.
 pub fn get_prescription(env: &Env, id: u64) -> Option<Prescription> {
     let key = (soroban_sdk::symbol_short!("RX"), id);
-    env.storage().persistent().get(&key)
+    let raw: Val = env.storage().persistent().get(&key)?;
+    let fields = Map::<Symbol, Val>::try_from_val(env, &raw).ok()?;
+    if fields.contains_key(soroban_sdk::symbol_short!("revoked")) {
+        Prescription::try_from_val(env, &raw).ok()
+    } else {
+        LegacyPrescription::try_from_val(env, &raw)
+            .ok()
+            .map(Prescription::from)
+    }
 }
 
 pub fn get_patient_history(env: &Env, patient: Address) -> Vec<u64> {
@@ -141,9 +233,57 @@ pub fn get_patient_history(env: &Env, patient: Address) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
-pub fn verify_prescription(env: &Env, id: u64, verifier: Address) -> bool {
+/// Returns true when the prescription exists, has been verified, has not
+/// been revoked, and has not yet expired.
+pub fn is_prescription_valid(env: &Env, id: u64) -> bool {
+    match get_prescription(env, id) {
+        Some(rx) => rx.verified && !rx.revoked && rx.expires_at > env.ledger().timestamp(),
+        None => false,
+    }
+}
+
+/// Returns the patient's prescription IDs that are currently fillable.
+pub fn get_active_prescriptions(env: &Env, patient: Address) -> Vec<u64> {
+    let mut active = Vec::new(env);
+    for id in get_patient_history(env, patient).iter() {
+        if is_prescription_valid(env, id) {
+            active.push_back(id);
+        }
+    }
+    active
+}
+
+/// Overwrites a stored prescription without touching history or lineage.
+pub fn update_prescription(env: &Env, prescription: &Prescription) {
+    let key = (soroban_sdk::symbol_short!("RX"), prescription.id);
+    env.storage().persistent().set(&key, prescription);
+}
+
+/// Appends a dispense to the prescription's dispense log.
+pub fn add_dispense_record(env: &Env, record: &DispenseRecord) {
+    let key = (soroban_sdk::symbol_short!("RX_DISP"), record.rx_id);
+    let mut log: Vec<DispenseRecord> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    log.push_back(record.clone());
+    env.storage().persistent().set(&key, &log);
+}
+
+/// Returns every dispense recorded against a prescription, oldest first.
+pub fn get_dispense_records(env: &Env, rx_id: u64) -> Vec<DispenseRecord> {
+    let key = (soroban_sdk::symbol_short!("RX_DISP"), rx_id);
+    env.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Marks a prescription verified. Callers are responsible for authorizing the
+/// verifier; returns `false` if the prescription does not exist.
+pub fn verify_prescription(env: &Env, id: u64) -> bool {
     if let Some(mut rx) = get_prescription(env, id) {
-        verifier.require_auth();
         rx.verified = true;
         let key = (soroban_sdk::symbol_short!("RX"), id);
         env.storage().persistent().set(&key, &rx);
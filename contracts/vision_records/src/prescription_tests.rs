@@ -52,7 +52,7 @@ fn test_prescription_workflow() {
 
     assert_eq!(rx_id, 1);
 
-    let rx = client.get_prescription(&rx_id);
+    let rx = client.get_prescription(&patient, &rx_id);
     assert_eq!(rx.patient, patient);
     assert_eq!(rx.provider, doctor);
     assert!(!rx.verified);
@@ -68,7 +68,7 @@ fn test_prescription_workflow() {
 
     assert!(client.verify_prescription(&rx_id, &pharmacist));
 
-    let updated_rx = client.get_prescription(&rx_id);
+    let updated_rx = client.get_prescription(&patient, &rx_id);
     assert!(updated_rx.verified);
 
     // Check history
@@ -122,7 +122,7 @@ fn test_contact_lens_workflow() {
         &String::from_str(&env, "contact_hash"),
     );
 
-    let rx = client.get_prescription(&rx_id);
+    let rx = client.get_prescription(&patient, &rx_id);
     assert_eq!(rx.lens_type, LensType::ContactLens);
     assert!(matches!(rx.contact_data, OptionalContactLensData::Some(_)));
 }
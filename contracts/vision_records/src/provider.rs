@@ -1,31 +1,35 @@
 #![allow(clippy::arithmetic_side_effects)]
 use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Vec};
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
+use crate::rbac::{self, Role};
+use crate::RecordType;
 
 fn extend_ttl(env: &Env, key: &(soroban_sdk::Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 fn extend_ttl_string_key(env: &Env, key: &(soroban_sdk::Symbol, String)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 fn extend_ttl_u64_key(env: &Env, key: &(soroban_sdk::Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 fn extend_ttl_status_key(env: &Env, key: &(soroban_sdk::Symbol, VerificationStatus)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 #[contracttype]
@@ -102,6 +106,49 @@ pub fn get_provider(env: &Env, provider: &Address) -> Option<Provider> {
     env.storage().persistent().get(&key)
 }
 
+/// Returns true if the address is an active provider with `Verified` status.
+pub fn is_verified_provider(env: &Env, provider: &Address) -> bool {
+    get_provider(env, provider)
+        .map(|p| p.is_active && p.verification_status == VerificationStatus::Verified)
+        .unwrap_or(false)
+}
+
+/// A provider's standing evaluated against the current ledger time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProviderStatus {
+    pub address: Address,
+    pub verification_status: VerificationStatus,
+    pub is_active: bool,
+    /// At least one license has been issued and has not yet expired.
+    pub has_active_license: bool,
+    /// Active, `Verified` and holding an active license.
+    pub can_practice: bool,
+}
+
+/// Returns true if any license is within its issued..expiry window at `now`.
+pub fn has_active_license(provider: &Provider, now: u64) -> bool {
+    provider
+        .licenses
+        .iter()
+        .any(|license| license.issued_date <= now && now < license.expiry_date)
+}
+
+pub fn get_provider_status(env: &Env, provider: &Address) -> Option<ProviderStatus> {
+    let data = get_provider(env, provider)?;
+    let has_active_license = has_active_license(&data, env.ledger().timestamp());
+    let can_practice = data.is_active
+        && data.verification_status == VerificationStatus::Verified
+        && has_active_license;
+    Some(ProviderStatus {
+        address: data.address,
+        verification_status: data.verification_status,
+        is_active: data.is_active,
+        has_active_license,
+        can_practice,
+    })
+}
+
 pub fn set_provider(env: &Env, provider: &Provider) {
     let key = provider_key(&provider.address);
 
@@ -238,6 +285,31 @@ pub fn get_all_provider_ids(env: &Env) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
+/// Largest page `get_providers_page` will return.
+pub const MAX_PROVIDER_PAGE: u32 = 50;
+
+/// Resolves up to `limit` (capped at `MAX_PROVIDER_PAGE`) providers in
+/// registration order, skipping the first `offset`.
+pub fn get_providers_page(env: &Env, offset: u32, limit: u32) -> Vec<Provider> {
+    let ids = get_all_provider_ids(env);
+    let end = ids
+        .len()
+        .min(offset.saturating_add(limit.min(MAX_PROVIDER_PAGE)));
+    let mut page = Vec::new(env);
+    for i in offset..end {
+        if let Some(id) = ids.get(i) {
+            let address: Option<Address> = env
+                .storage()
+                .persistent()
+                .get(&(symbol_short!("PROV_ID"), id));
+            if let Some(provider) = address.and_then(|a| get_provider(env, &a)) {
+                page.push_back(provider);
+            }
+        }
+    }
+    page
+}
+
 pub fn add_provider_id(env: &Env, provider_id: u64, provider: &Address) {
     let ids_key = symbol_short!("PROV_IDS");
     let mut ids: Vec<u64> = get_all_provider_ids(env);
@@ -249,3 +321,106 @@ pub fn add_provider_id(env: &Env, provider_id: u64, provider: &Address) {
     env.storage().persistent().set(&id_key, provider);
     extend_ttl_u64_key(env, &id_key);
 }
+
+/// Running activity totals for a provider, maintained as events happen so
+/// reads don't have to scan the record and appointment indexes.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProviderStats {
+    pub records_created: u64,
+    pub appointments_completed: u64,
+    pub no_shows: u64,
+}
+
+pub fn stats_key(provider: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("PRV_STAT"), provider.clone())
+}
+
+pub fn get_provider_stats(env: &Env, provider: &Address) -> ProviderStats {
+    env.storage()
+        .persistent()
+        .get(&stats_key(provider))
+        .unwrap_or_default()
+}
+
+fn update_provider_stats(env: &Env, provider: &Address, f: impl FnOnce(&mut ProviderStats)) {
+    let key = stats_key(provider);
+    let mut stats = get_provider_stats(env, provider);
+    f(&mut stats);
+    env.storage().persistent().set(&key, &stats);
+    extend_ttl(env, &key);
+}
+
+pub fn record_created(env: &Env, provider: &Address) {
+    update_provider_stats(env, provider, |s| {
+        s.records_created = s.records_created.saturating_add(1)
+    });
+}
+
+pub fn appointment_completed(env: &Env, provider: &Address) {
+    update_provider_stats(env, provider, |s| {
+        s.appointments_completed = s.appointments_completed.saturating_add(1)
+    });
+}
+
+pub fn appointment_no_show(env: &Env, provider: &Address) {
+    update_provider_stats(env, provider, |s| s.no_shows = s.no_shows.saturating_add(1));
+}
+
+/// Who may author records of a given type. A provider qualifies by holding
+/// one of `roles` or by being registered with one of `specialties`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordTypeRequirement {
+    pub roles: Vec<Role>,
+    pub specialties: Vec<String>,
+}
+
+fn record_type_requirement_key(record_type: &RecordType) -> (soroban_sdk::Symbol, RecordType) {
+    (symbol_short!("REC_REQ"), record_type.clone())
+}
+
+pub fn get_record_type_requirement(
+    env: &Env,
+    record_type: &RecordType,
+) -> Option<RecordTypeRequirement> {
+    env.storage()
+        .instance()
+        .get(&record_type_requirement_key(record_type))
+}
+
+/// Stores the requirement for `record_type`; an empty requirement removes
+/// it, making the type open to any provider again.
+pub fn set_record_type_requirement(
+    env: &Env,
+    record_type: &RecordType,
+    requirement: &RecordTypeRequirement,
+) {
+    let key = record_type_requirement_key(record_type);
+    if requirement.roles.is_empty() && requirement.specialties.is_empty() {
+        env.storage().instance().remove(&key);
+    } else {
+        env.storage().instance().set(&key, requirement);
+    }
+}
+
+/// Whether `provider` may author a record of `record_type`. Types without a
+/// configured requirement are open to everyone.
+pub fn can_author_record_type(env: &Env, provider: &Address, record_type: &RecordType) -> bool {
+    let Some(requirement) = get_record_type_requirement(env, record_type) else {
+        return true;
+    };
+
+    if let Some(assignment) = rbac::get_active_assignment(env, provider) {
+        if requirement.roles.contains(assignment.role) {
+            return true;
+        }
+    }
+    match get_provider(env, provider) {
+        Some(p) => p
+            .specialties
+            .iter()
+            .any(|s| requirement.specialties.contains(s)),
+        None => false,
+    }
+}
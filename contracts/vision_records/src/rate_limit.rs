@@ -1,37 +1,41 @@
 use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
 
+use crate::rbac::{self, Role};
+
 // ── Storage keys ──────────────────────────────────────────────
 pub(crate) const RATE_LIMIT_CONFIG: Symbol = symbol_short!("RL_CFG");
-pub(crate) const RATE_LIMIT_WINDOW: Symbol = symbol_short!("RL_WIN");
-pub(crate) const RATE_LIMIT_COUNT: Symbol = symbol_short!("RL_CNT");
+pub(crate) const RATE_LIMIT_HITS: Symbol = symbol_short!("RL_HITS");
 pub(crate) const RATE_LIMIT_BYPASS: Symbol = symbol_short!("RL_BYP");
-
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
+pub(crate) const RATE_LIMIT_USER: Symbol = symbol_short!("RL_USR");
+pub(crate) const RATE_LIMIT_EXEMPT_ROLES: Symbol = symbol_short!("RL_EXMPT");
 
 /// Extends the time-to-live (TTL) for rate limit storage keys.
 fn extend_ttl_config_key(env: &Env, key: &(Symbol, String)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
-fn extend_ttl_window_key(env: &Env, key: &(Symbol, Address, String)) {
+fn extend_ttl_hits_key(env: &Env, key: &(Symbol, Address, String)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
-fn extend_ttl_count_key(env: &Env, key: &(Symbol, Address, String)) {
+fn extend_ttl_user_key(env: &Env, key: &(Symbol, Address, String)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 fn extend_ttl_bypass_key(env: &Env, key: &(Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 // ── Types ─────────────────────────────────────────────────────
@@ -84,43 +88,77 @@ pub fn set_rate_limit_config(env: &Env, config: &RateLimitConfig) {
     extend_ttl_config_key(env, &key);
 }
 
-/// Gets the current rate limit window start time for an address and operation
-pub fn get_rate_limit_window(env: &Env, address: &Address, operation: &String) -> u64 {
-    let key = (RATE_LIMIT_WINDOW, address.clone(), operation.clone());
+/// Gets the per-user override for an operation, if one is set
+pub fn get_user_rate_limit(
+    env: &Env,
+    address: &Address,
+    operation: &String,
+) -> Option<RateLimitConfig> {
+    let key = (RATE_LIMIT_USER, address.clone(), operation.clone());
+    env.storage().persistent().get(&key)
+}
+
+/// Sets a per-user override that takes precedence over the operation's
+/// global configuration
+pub fn set_user_rate_limit(env: &Env, address: &Address, config: &RateLimitConfig) {
+    let key = (RATE_LIMIT_USER, address.clone(), config.operation.clone());
+    env.storage().persistent().set(&key, config);
+    extend_ttl_user_key(env, &key);
+}
+
+/// Gets the limit that applies to an address for an operation: its own
+/// override if present, otherwise the operation's global configuration
+pub fn get_effective_config(
+    env: &Env,
+    address: &Address,
+    operation: &String,
+) -> Option<RateLimitConfig> {
+    get_user_rate_limit(env, address, operation).or_else(|| get_rate_limit_config(env, operation))
+}
+
+/// Gets the timestamps of recent requests for an address and operation.
+/// The list is a ring of at most `max_requests` entries, oldest first.
+pub fn get_request_timestamps(env: &Env, address: &Address, operation: &String) -> Vec<u64> {
+    let key = (RATE_LIMIT_HITS, address.clone(), operation.clone());
     env.storage()
         .persistent()
         .get(&key)
-        .unwrap_or(env.ledger().timestamp())
+        .unwrap_or(Vec::new(env))
 }
 
-/// Sets the rate limit window start time for an address and operation
-pub fn set_rate_limit_window(env: &Env, address: &Address, operation: &String, window_start: u64) {
-    let key = (RATE_LIMIT_WINDOW, address.clone(), operation.clone());
-    env.storage().persistent().set(&key, &window_start);
-    extend_ttl_window_key(env, &key);
+fn set_request_timestamps(env: &Env, address: &Address, operation: &String, hits: &Vec<u64>) {
+    let key = (RATE_LIMIT_HITS, address.clone(), operation.clone());
+    env.storage().persistent().set(&key, hits);
+    extend_ttl_hits_key(env, &key);
 }
 
-/// Gets the current request count for an address and operation in the current window
-pub fn get_rate_limit_count(env: &Env, address: &Address, operation: &String) -> u32 {
-    let key = (RATE_LIMIT_COUNT, address.clone(), operation.clone());
-    env.storage().persistent().get(&key).unwrap_or(0)
+/// Clears the recorded requests for an address and operation, starting a
+/// fresh window
+pub fn reset_rate_limit(env: &Env, address: &Address, operation: &String) {
+    let key = (RATE_LIMIT_HITS, address.clone(), operation.clone());
+    env.storage().persistent().remove(&key);
 }
 
-/// Increments the rate limit count for an address and operation
-pub fn increment_rate_limit_count(env: &Env, address: &Address, operation: &String) -> u32 {
-    let key = (RATE_LIMIT_COUNT, address.clone(), operation.clone());
-    let current: u32 = env.storage().persistent().get(&key).unwrap_or(0);
-    let new_count = current + 1;
-    env.storage().persistent().set(&key, &new_count);
-    extend_ttl_count_key(env, &key);
-    new_count
+/// Drops timestamps that have slid out of the window ending at `now`.
+fn prune_request_timestamps(env: &Env, hits: &Vec<u64>, now: u64, window_seconds: u64) -> Vec<u64> {
+    let mut recent = Vec::new(env);
+    for ts in hits.iter() {
+        if ts.saturating_add(window_seconds) > now {
+            recent.push_back(ts);
+        }
+    }
+    recent
 }
 
-/// Resets the rate limit count for an address and operation
-pub fn reset_rate_limit_count(env: &Env, address: &Address, operation: &String) {
-    let key = (RATE_LIMIT_COUNT, address.clone(), operation.clone());
-    env.storage().persistent().set(&key, &0u32);
-    extend_ttl_count_key(env, &key);
+/// Gets the number of requests an address has made for an operation within
+/// the sliding window ending now.
+pub fn get_rate_limit_count(env: &Env, address: &Address, operation: &String) -> u32 {
+    let config = match get_effective_config(env, address, operation) {
+        Some(cfg) => cfg,
+        None => return 0,
+    };
+    let hits = get_request_timestamps(env, address, operation);
+    prune_request_timestamps(env, &hits, env.ledger().timestamp(), config.window_seconds).len()
 }
 
 /// Checks if an address has rate limit bypass (e.g., verified providers)
@@ -140,18 +178,49 @@ pub fn set_rate_limit_bypass(env: &Env, address: &Address, bypass: bool) {
     }
 }
 
+/// Gets the roles whose holders are never rate limited
+pub fn get_exempt_roles(env: &Env) -> Vec<Role> {
+    env.storage()
+        .instance()
+        .get(&RATE_LIMIT_EXEMPT_ROLES)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Replaces the set of roles whose holders are never rate limited
+pub fn set_exempt_roles(env: &Env, roles: &Vec<Role>) {
+    env.storage()
+        .instance()
+        .set(&RATE_LIMIT_EXEMPT_ROLES, roles);
+}
+
+/// Checks if an address skips rate limiting, either through an explicit
+/// bypass or because its active role is exempt
+pub fn is_rate_limit_exempt(env: &Env, address: &Address) -> bool {
+    if has_rate_limit_bypass(env, address) {
+        return true;
+    }
+    match rbac::get_active_assignment(env, address) {
+        Some(assignment) => get_exempt_roles(env).contains(assignment.role),
+        None => false,
+    }
+}
+
 // ── Rate Limit Logic ────────────────────────────────────────
 
-/// Checks if a request should be rate limited
+/// Checks if a request should be rate limited using a sliding window.
+///
+/// A request is allowed when fewer than `max_requests` requests were made in
+/// the last `window_seconds`. `reset_at` is when the oldest counted request
+/// leaves the window and a slot frees up.
 /// Returns (is_allowed, current_count, max_requests, reset_at)
 pub fn check_rate_limit(env: &Env, address: &Address, operation: &String) -> (bool, u32, u32, u64) {
-    // Check if address has bypass
-    if has_rate_limit_bypass(env, address) {
+    // Check if address has bypass or an exempt role
+    if is_rate_limit_exempt(env, address) {
         return (true, 0, 0, 0);
     }
 
-    // Get rate limit configuration
-    let config = match get_rate_limit_config(env, operation) {
+    // Get rate limit configuration, preferring a per-user override
+    let config = match get_effective_config(env, address, operation) {
         Some(cfg) => cfg,
         None => {
             // No rate limit configured for this operation - allow
@@ -159,58 +228,57 @@ pub fn check_rate_limit(env: &Env, address: &Address, operation: &String) -> (bo
         }
     };
 
-    let current_time = env.ledger().timestamp();
-    let window_start = get_rate_limit_window(env, address, operation);
-    let window_end = window_start + config.window_seconds;
-
-    // Check if current window has expired
-    if current_time >= window_end {
-        // Reset window and count
-        set_rate_limit_window(env, address, operation, current_time);
-        reset_rate_limit_count(env, address, operation);
-        let new_count = increment_rate_limit_count(env, address, operation);
-        let new_window_end = current_time + config.window_seconds;
-        return (true, new_count, config.max_requests, new_window_end);
+    let now = env.ledger().timestamp();
+    let hits = get_request_timestamps(env, address, operation);
+    let mut recent = prune_request_timestamps(env, &hits, now, config.window_seconds);
+
+    if recent.len() >= config.max_requests {
+        let oldest = recent.first().unwrap_or(now);
+        return (
+            false,
+            recent.len(),
+            config.max_requests,
+            oldest.saturating_add(config.window_seconds),
+        );
     }
 
-    // Window is still active, check count
-    let current_count = get_rate_limit_count(env, address, operation);
-
-    // If this is the first request in a window (count is 0), store the window_start
-    if current_count == 0 {
-        set_rate_limit_window(env, address, operation, window_start);
-    }
-
-    if current_count >= config.max_requests {
-        // Rate limit exceeded
-        return (false, current_count, config.max_requests, window_end);
-    }
+    recent.push_back(now);
+    set_request_timestamps(env, address, operation, &recent);
 
-    // Increment count and allow
-    let new_count = increment_rate_limit_count(env, address, operation);
-    (true, new_count, config.max_requests, window_end)
+    let oldest = recent.first().unwrap_or(now);
+    (
+        true,
+        recent.len(),
+        config.max_requests,
+        oldest.saturating_add(config.window_seconds),
+    )
 }
 
-/// Gets rate limit status for an address and operation
+/// Gets rate limit status for an address and operation.
+/// `window_start`/`window_end` describe the sliding window ending now.
 pub fn get_rate_limit_status(
     env: &Env,
     address: &Address,
     operation: &String,
 ) -> Option<RateLimitStatus> {
-    let config = get_rate_limit_config(env, operation)?;
-    let window_start = get_rate_limit_window(env, address, operation);
-    let current_count = get_rate_limit_count(env, address, operation);
-    let window_end = window_start + config.window_seconds;
+    let config = get_effective_config(env, address, operation)?;
+    let now = env.ledger().timestamp();
+    let hits = get_request_timestamps(env, address, operation);
+    let recent = prune_request_timestamps(env, &hits, now, config.window_seconds);
+    let reset_at = recent
+        .first()
+        .map(|oldest| oldest.saturating_add(config.window_seconds))
+        .unwrap_or(now);
 
     Some(RateLimitStatus {
         address: address.clone(),
         operation: operation.clone(),
-        current_count,
+        current_count: recent.len(),
         max_requests: config.max_requests,
         window_seconds: config.window_seconds,
-        window_start,
-        window_end,
-        reset_at: window_end,
+        window_start: now.saturating_sub(config.window_seconds),
+        window_end: now,
+        reset_at,
     })
 }
 
@@ -105,28 +105,31 @@
 //! - `("DLG_SCOPE", delegator, delegatee)` → ScopedDelegation
 //! - `("DEL_IDX", delegatee)` → Vec<Address> (index of delegators)
 //! - `("DLGTR_IDX", delegator)` → Vec<Address> (index of delegatees)
+//! - `"DLG_TRANS"` (instance) → bool (transitive delegation enabled)
 //! - `("ACL_GRP", group_name)` → AclGroup
 //! - `("USR_GRPS", user)` → Vec<String> (groups user belongs to)
 //! - `("ACC_POL", policy_id)` → AccessPolicy
+//! - `"ACT_POLS"` (instance) → Vec<String> (policy ids evaluated on access)
 //! - `("USER_CRED", user)` → CredentialType
 //! - `("REC_SENS", record_id)` → SensitivityLevel
 
 use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
 
-const TTL_THRESHOLD: u32 = 5184000;
-const TTL_EXTEND_TO: u32 = 10368000;
-
 /// Time-based access restrictions for contextual access control.
 ///
 /// Allows policies to enforce time-of-day restrictions, day-of-week restrictions,
 /// and business hours enforcement. Useful for controlling access to sensitive
 /// operations during specific times.
+///
+/// All hours and days are evaluated in UTC against the ledger timestamp; there
+/// is no time-zone adjustment.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
 pub enum TimeRestriction {
     /// No time restriction
     None,
-    /// Only allow access during business hours (9 AM - 5 PM UTC)
+    /// Only allow access during business hours, 09:00 up to but not
+    /// including 17:00 UTC
     BusinessHours,
     /// Only allow access during specific hour range (start_hour, end_hour, inclusive)
     HourRange(u32, u32),
@@ -190,15 +193,31 @@ pub struct AccessPolicy {
 }
 
 fn extend_ttl_address_key(env: &Env, key: &(soroban_sdk::Symbol, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+fn extend_ttl_role_key(env: &Env, key: &(soroban_sdk::Symbol, Role)) {
+    let policy = crate::ttl::get_policy(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+fn extend_ttl_role_bucket_key(env: &Env, key: &(soroban_sdk::Symbol, Role, u32)) {
+    let policy = crate::ttl::get_policy(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 fn extend_ttl_delegation_key(env: &Env, key: &(soroban_sdk::Symbol, Address, Address)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Core permissions in the Teye system.
@@ -219,6 +238,8 @@ pub enum Permission {
     ManageUsers = 4,
     /// System-level administrative access (contract upgrades, configuration)
     SystemAdmin = 5,
+    /// Verify and fill prescriptions (pharmacies, optical shops)
+    DispensePrescription = 6,
 }
 
 /// User roles in the Teye system.
@@ -372,6 +393,21 @@ pub fn delegator_index_key(delegator: &Address) -> (Symbol, Address) {
     (symbol_short!("DLGTR_IDX"), delegator.clone())
 }
 
+/// Maximum number of users held in one page of a role index.
+pub const ROLE_INDEX_PAGE_SIZE: u32 = 50;
+
+pub fn role_index_key(role: &Role, page: u32) -> (Symbol, Role, u32) {
+    (symbol_short!("ROLE_IDX"), *role, page)
+}
+
+pub fn role_page_count_key(role: &Role) -> (Symbol, Role) {
+    (symbol_short!("ROLE_PGS"), *role)
+}
+
+pub fn user_role_page_key(user: &Address) -> (Symbol, Address) {
+    (symbol_short!("ROLE_UPG"), user.clone())
+}
+
 pub fn acl_group_key(name: &String) -> (Symbol, String) {
     (symbol_short!("ACL_GRP"), name.clone())
 }
@@ -427,8 +463,104 @@ pub fn assign_role(env: &Env, user: Address, role: Role, expires_at: u64) {
     };
 
     let key = user_assignment_key(&user);
+    let previous: Option<RoleAssignment> = env.storage().persistent().get(&key);
     env.storage().persistent().set(&key, &assignment);
     extend_ttl_address_key(env, &key);
+
+    // Keep the role index in step so admins can enumerate users per role.
+    if let Some(prev) = previous {
+        if prev.role != role {
+            remove_from_role_index(env, &prev.role, &user);
+        }
+    }
+    add_to_role_index(env, &role, &user);
+}
+
+/// Move a user to a different role, keeping their custom grants, custom
+/// revokes and expiry.
+///
+/// Unlike `assign_role`, explicit overrides such as a bulk revoke survive
+/// the change. Users without a stored assignment get a fresh one that never
+/// expires.
+pub fn change_role(env: &Env, user: Address, role: Role) {
+    let key = user_assignment_key(&user);
+    let mut assignment: RoleAssignment = match env.storage().persistent().get(&key) {
+        Some(assignment) => assignment,
+        None => return assign_role(env, user, role, 0),
+    };
+
+    assignment.role = role;
+    env.storage().persistent().set(&key, &assignment);
+    extend_ttl_address_key(env, &key);
+    add_to_role_index(env, &role, &user);
+}
+
+/// Adds `user` to the last page of the `role` index, opening a new page once
+/// it holds `ROLE_INDEX_PAGE_SIZE` users. The page is remembered per user so
+/// removal only touches that page.
+fn add_to_role_index(env: &Env, role: &Role, user: &Address) {
+    let user_key = user_role_page_key(user);
+    if let Some((indexed_role, _)) = env.storage().persistent().get::<_, (Role, u32)>(&user_key) {
+        if indexed_role == *role {
+            return;
+        }
+        remove_from_role_index(env, &indexed_role, user);
+    }
+
+    let count_key = role_page_count_key(role);
+    let pages: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let mut page = pages.saturating_sub(1);
+    let mut users = get_users_by_role(env, role, page);
+    if pages == 0 || users.len() >= ROLE_INDEX_PAGE_SIZE {
+        page = pages;
+        users = Vec::new(env);
+        env.storage().persistent().set(&count_key, &(page + 1));
+        extend_ttl_role_key(env, &count_key);
+    }
+
+    users.push_back(user.clone());
+    let key = role_index_key(role, page);
+    env.storage().persistent().set(&key, &users);
+    extend_ttl_role_bucket_key(env, &key);
+    env.storage().persistent().set(&user_key, &(*role, page));
+    extend_ttl_address_key(env, &user_key);
+}
+
+/// Removes `user` from the page of the `role` index it was added to. Pages
+/// are not compacted, so page numbers stay stable for callers paging through.
+fn remove_from_role_index(env: &Env, role: &Role, user: &Address) {
+    let user_key = user_role_page_key(user);
+    let page = match env.storage().persistent().get::<_, (Role, u32)>(&user_key) {
+        Some((indexed_role, page)) if indexed_role == *role => page,
+        _ => return,
+    };
+    env.storage().persistent().remove(&user_key);
+
+    let mut users = get_users_by_role(env, role, page);
+    if let Some(pos) = users.first_index_of(user) {
+        users.remove(pos);
+        let key = role_index_key(role, page);
+        env.storage().persistent().set(&key, &users);
+        extend_ttl_role_bucket_key(env, &key);
+    }
+}
+
+/// Returns one page of the users whose current role assignment is `role`.
+/// Pages hold at most `ROLE_INDEX_PAGE_SIZE` users and may be partly empty
+/// after removals.
+pub fn get_users_by_role(env: &Env, role: &Role, page: u32) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&role_index_key(role, page))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Returns how many pages the `role` index spans.
+pub fn get_role_page_count(env: &Env, role: &Role) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&role_page_count_key(role))
+        .unwrap_or(0)
 }
 
 /// Retrieve the active role assignment for a user.
@@ -456,6 +588,51 @@ pub fn get_active_assignment(env: &Env, user: &Address) -> Option<RoleAssignment
     None
 }
 
+/// Deletes a user's role assignment if it has expired.
+///
+/// Returns true when storage was removed. Assignments that never expire
+/// (`expires_at == 0`) or are still active are left alone.
+pub fn remove_expired_assignment(env: &Env, user: &Address) -> bool {
+    let key = user_assignment_key(user);
+    match env.storage().persistent().get::<_, RoleAssignment>(&key) {
+        Some(assignment)
+            if assignment.expires_at != 0 && assignment.expires_at <= env.ledger().timestamp() =>
+        {
+            env.storage().persistent().remove(&key);
+            remove_from_role_index(env, &assignment.role, user);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Moves a user's assignment expiry to `expires_at` (0 for never),
+/// reviving it if it had lapsed but was not yet cleaned up.
+///
+/// Custom grants and revokes are kept. Returns the assigned role, or fails
+/// if the user has no stored assignment.
+pub fn renew_assignment(env: &Env, user: &Address, expires_at: u64) -> Result<Role, ()> {
+    let key = user_assignment_key(user);
+    let mut assignment: RoleAssignment = env.storage().persistent().get(&key).ok_or(())?;
+    assignment.expires_at = expires_at;
+    env.storage().persistent().set(&key, &assignment);
+    extend_ttl_address_key(env, &key);
+    add_to_role_index(env, &assignment.role, user);
+    Ok(assignment.role)
+}
+
+/// Returns true when the user's active role ranks at or above `min_role`.
+///
+/// Roles are compared by their discriminant, so an Ophthalmologist satisfies
+/// a `>= Optometrist` check. Users without an active assignment rank as
+/// `Role::None`.
+pub fn role_at_least(env: &Env, user: &Address, min_role: &Role) -> bool {
+    let role = get_active_assignment(env, user)
+        .map(|a| a.role)
+        .unwrap_or(Role::None);
+    role as u32 >= *min_role as u32
+}
+
 /// Grant a custom permission to a user.
 ///
 /// Adds a permission to the user's custom_grants, which overrides their base role.
@@ -542,6 +719,27 @@ pub fn revoke_custom_permission(
     Ok(())
 }
 
+/// Revoke every permission a user holds through their role or custom grants.
+///
+/// Clears `custom_grants` and adds each base-role permission to
+/// `custom_revokes`, so the user keeps their assignment but can no longer act
+/// through it. Returns the permissions that are now revoked.
+pub fn revoke_all_custom_permissions(env: &Env, user: Address) -> Result<Vec<Permission>, ()> {
+    let mut assignment = get_active_assignment(env, &user).ok_or(())?;
+
+    assignment.custom_grants = Vec::new(env);
+    for permission in get_base_permissions(env, &assignment.role).iter() {
+        if !assignment.custom_revokes.contains(&permission) {
+            assignment.custom_revokes.push_back(permission);
+        }
+    }
+
+    let key = user_assignment_key(&user);
+    env.storage().persistent().set(&key, &assignment);
+    extend_ttl_address_key(env, &key);
+    Ok(assignment.custom_revokes)
+}
+
 /// Create a full role delegation from delegator to delegatee.
 ///
 /// The delegatee immediately receives all base permissions of the specified role.
@@ -743,6 +941,83 @@ pub fn get_active_scoped_delegation(
     None
 }
 
+/// Remove the full role delegation from `delegator` to `delegatee`.
+///
+/// The pair is dropped from the delegator and delegatee indexes unless a
+/// scoped delegation between them remains. Returns false if there was no
+/// delegation to remove.
+pub fn revoke_delegation(env: &Env, delegator: &Address, delegatee: &Address) -> bool {
+    let key = delegation_key(delegator, delegatee);
+    if !env.storage().persistent().has(&key) {
+        return false;
+    }
+    env.storage().persistent().remove(&key);
+
+    if !env
+        .storage()
+        .persistent()
+        .has(&scoped_delegation_key(delegator, delegatee))
+    {
+        remove_from_address_index(env, &delegatee_index_key(delegatee), delegator);
+        remove_from_address_index(env, &delegator_index_key(delegator), delegatee);
+    }
+    true
+}
+
+fn remove_from_address_index(env: &Env, key: &(Symbol, Address), addr: &Address) {
+    let mut addrs: Vec<Address> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+    if let Some(pos) = addrs.first_index_of(addr) {
+        addrs.remove(pos);
+        env.storage().persistent().set(key, &addrs);
+        extend_ttl_address_key(env, key);
+    }
+}
+
+/// All active full-role delegations made by `delegator`.
+pub fn get_outgoing_delegations(env: &Env, delegator: &Address) -> Vec<Delegation> {
+    let delegatees: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&delegator_index_key(delegator))
+        .unwrap_or(Vec::new(env));
+    let mut delegations = Vec::new(env);
+    for delegatee in delegatees.iter() {
+        if let Some(del) = get_active_delegation(env, delegator, &delegatee) {
+            delegations.push_back(del);
+        }
+    }
+    delegations
+}
+
+/// All active full-role delegations received by `delegatee`.
+pub fn get_incoming_delegations(env: &Env, delegatee: &Address) -> Vec<Delegation> {
+    let mut delegations = Vec::new(env);
+    for delegator in delegators_of(env, delegatee).iter() {
+        if let Some(del) = get_active_delegation(env, &delegator, delegatee) {
+            delegations.push_back(del);
+        }
+    }
+    delegations
+}
+
+/// All active scoped permission delegations received by `delegatee`.
+pub fn get_incoming_scoped_delegations(env: &Env, delegatee: &Address) -> Vec<ScopedDelegation> {
+    let mut delegations = Vec::new(env);
+    for delegator in delegators_of(env, delegatee).iter() {
+        if let Some(del) = get_active_scoped_delegation(env, &delegator, delegatee) {
+            delegations.push_back(del);
+        }
+    }
+    delegations
+}
+
+fn delegators_of(env: &Env, delegatee: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&delegatee_index_key(delegatee))
+        .unwrap_or(Vec::new(env))
+}
+
 // ======================== ACL Group Management ========================
 
 /// Create a new ACL group with the specified permissions.
@@ -921,11 +1196,110 @@ pub fn has_permission(env: &Env, user: &Address, permission: &Permission) -> boo
 /// caller must be acting on behalf of a particular entity (e.g., a provider
 /// delegating record-writing authority, or a patient delegating access
 /// management).
+///
+/// When transitive delegation is enabled (see `set_transitive_delegation`),
+/// chains such as A→B→C also count, up to `MAX_DELEGATION_HOPS` links.
 pub fn has_delegated_permission(
     env: &Env,
     delegator: &Address,
     delegatee: &Address,
     permission: &Permission,
+) -> bool {
+    if has_direct_delegated_permission(env, delegator, delegatee, permission) {
+        return true;
+    }
+    if !is_transitive_delegation_enabled(env) {
+        return false;
+    }
+    let mut path = Vec::new(env);
+    path.push_back(delegatee.clone());
+    has_chained_permission(
+        env,
+        delegator,
+        delegatee,
+        permission,
+        MAX_DELEGATION_HOPS - 1,
+        &mut path,
+    )
+}
+
+/// Longest delegation chain honoured when transitive delegation is enabled.
+pub const MAX_DELEGATION_HOPS: u32 = 3;
+
+const TRANSITIVE_DELEGATION: Symbol = symbol_short!("DLG_TRANS");
+
+/// Enables or disables transitive delegation resolution. Off by default.
+pub fn set_transitive_delegation(env: &Env, enabled: bool) {
+    env.storage()
+        .instance()
+        .set(&TRANSITIVE_DELEGATION, &enabled);
+}
+
+pub fn is_transitive_delegation_enabled(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&TRANSITIVE_DELEGATION)
+        .unwrap_or(false)
+}
+
+/// Walks the delegatee's delegators (via `DEL_IDX`) looking for a chain back
+/// to `delegator` in which every link carries `permission`.
+///
+/// Loop protection: `hops_left` bounds the recursion depth, so the walk ends
+/// even if delegations form a cycle, and `path` holds the addresses on the
+/// chain being explored so none is revisited within it. An address is only
+/// added once the link to it carries `permission`, and is removed again on
+/// backtracking, so reaching it first through a link without the permission
+/// does not hide a valid chain through it.
+fn has_chained_permission(
+    env: &Env,
+    delegator: &Address,
+    delegatee: &Address,
+    permission: &Permission,
+    hops_left: u32,
+    path: &mut Vec<Address>,
+) -> bool {
+    if hops_left == 0 {
+        return false;
+    }
+    let intermediates: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&delegatee_index_key(delegatee))
+        .unwrap_or(Vec::new(env));
+    for intermediate in intermediates.iter() {
+        if path.contains(&intermediate) {
+            continue;
+        }
+        if !has_direct_delegated_permission(env, &intermediate, delegatee, permission) {
+            continue;
+        }
+        if has_direct_delegated_permission(env, delegator, &intermediate, permission) {
+            return true;
+        }
+        path.push_back(intermediate.clone());
+        let found = has_chained_permission(
+            env,
+            delegator,
+            &intermediate,
+            permission,
+            hops_left - 1,
+            path,
+        );
+        path.pop_back();
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+/// `has_delegated_permission` restricted to a single delegator→delegatee link.
+fn has_direct_delegated_permission(
+    env: &Env,
+    delegator: &Address,
+    delegatee: &Address,
+    permission: &Permission,
 ) -> bool {
     // Full role delegation: delegatee gets all permissions of the role
     if let Some(delegation) = get_active_delegation(env, delegator, delegatee) {
@@ -946,15 +1320,19 @@ pub fn has_delegated_permission(
 
 /// Check if current time satisfies time restriction
 fn satisfies_time_restriction(env: &Env, restriction: &TimeRestriction) -> bool {
+    time_restriction_allows(restriction, env.ledger().timestamp())
+}
+
+/// Check if `timestamp` (UTC seconds) satisfies a time restriction
+pub(crate) fn time_restriction_allows(restriction: &TimeRestriction, timestamp: u64) -> bool {
     match restriction {
         TimeRestriction::None => true,
         TimeRestriction::BusinessHours => {
-            let timestamp = env.ledger().timestamp();
             let hour = (timestamp / 3600) % 24;
-            (9..=17).contains(&hour)
+            // Equivalent to HourRange(9, 16): the 17:00 hour is outside.
+            (9..17).contains(&hour)
         }
         TimeRestriction::HourRange(start, end) => {
-            let timestamp = env.ledger().timestamp();
             let hour = (timestamp / 3600) % 24;
             if start <= end {
                 hour >= *start as u64 && hour <= *end as u64
@@ -964,7 +1342,6 @@ fn satisfies_time_restriction(env: &Env, restriction: &TimeRestriction) -> bool
             }
         }
         TimeRestriction::DaysOfWeek(day_mask) => {
-            let timestamp = env.ledger().timestamp();
             let day_of_week = ((timestamp / 86400) + 4) % 7; // Unix epoch was Thursday
             (day_mask & (1 << day_of_week)) != 0
         }
@@ -972,7 +1349,7 @@ fn satisfies_time_restriction(env: &Env, restriction: &TimeRestriction) -> bool
 }
 
 /// Get user's credential type from storage
-fn get_user_credential(env: &Env, user: &Address) -> CredentialType {
+pub fn get_user_credential(env: &Env, user: &Address) -> CredentialType {
     let key = user_credential_key(user);
     env.storage()
         .persistent()
@@ -981,7 +1358,7 @@ fn get_user_credential(env: &Env, user: &Address) -> CredentialType {
 }
 
 /// Get record sensitivity level from storage
-fn get_record_sensitivity(env: &Env, record_id: &u64) -> SensitivityLevel {
+pub fn get_record_sensitivity(env: &Env, record_id: &u64) -> SensitivityLevel {
     let key = record_sensitivity_key(record_id);
     env.storage()
         .persistent()
@@ -1001,6 +1378,16 @@ pub struct PolicyContext {
 
 /// Evaluate an access policy against the given context
 pub fn evaluate_policy(env: &Env, policy: &AccessPolicy, context: &PolicyContext) -> bool {
+    evaluate_policy_conditions(env, policy, context, true)
+}
+
+/// Evaluate a policy, optionally skipping its `consent_required` condition.
+fn evaluate_policy_conditions(
+    env: &Env,
+    policy: &AccessPolicy,
+    context: &PolicyContext,
+    check_consent: bool,
+) -> bool {
     if !policy.enabled {
         return false;
     }
@@ -1041,7 +1428,7 @@ pub fn evaluate_policy(env: &Env, policy: &AccessPolicy, context: &PolicyContext
     }
 
     // Check consent requirement
-    if conditions.consent_required {
+    if check_consent && conditions.consent_required {
         if let (Some(patient), Some(_record_id)) = (&context.patient, &context.resource_id) {
             // Check if there's active consent for this user to access this patient's records
             let consent_key = (
@@ -1075,12 +1462,49 @@ pub fn evaluate_access_policies(
     resource_id: Option<u64>,
     patient: Option<Address>,
 ) -> bool {
-    // Get all policies (in a real implementation, you might want to index policies by user/resource)
-    // For now, we'll check a few default policy IDs
-    let mut default_policy_ids = Vec::new(env);
-    default_policy_ids.push_back(String::from_str(env, "default_medical_access"));
-    default_policy_ids.push_back(String::from_str(env, "emergency_access"));
-    default_policy_ids.push_back(String::from_str(env, "research_access"));
+    let (found_policy, granted) = match_access_policies(env, user, resource_id, patient, true);
+
+    // Backward-compatible default: if no ABAC policies are configured,
+    // don't block otherwise valid consent/access grants.
+    !found_policy || granted
+}
+
+/// Like [`evaluate_access_policies`], but ignores `consent_required` conditions.
+///
+/// Used only for emergency access carrying an explicit consent override.
+pub fn evaluate_access_policies_without_consent(
+    env: &Env,
+    user: &Address,
+    resource_id: Option<u64>,
+    patient: Option<Address>,
+) -> bool {
+    let (found_policy, granted) = match_access_policies(env, user, resource_id, patient, false);
+    !found_policy || granted
+}
+
+/// Returns true only when a configured, enabled policy grants access.
+///
+/// Unlike [`evaluate_access_policies`] this does not fall back to allowing
+/// access when no policies exist, so it is safe to use as a grant path.
+pub fn policy_grants_access(
+    env: &Env,
+    user: &Address,
+    resource_id: Option<u64>,
+    patient: Option<Address>,
+) -> bool {
+    match_access_policies(env, user, resource_id, patient, true).1
+}
+
+/// Evaluates the active policy IDs, returning whether any policy was stored
+/// and whether one of them matched.
+fn match_access_policies(
+    env: &Env,
+    user: &Address,
+    resource_id: Option<u64>,
+    patient: Option<Address>,
+    check_consent: bool,
+) -> (bool, bool) {
+    let policy_ids = get_active_policies(env);
 
     let context = PolicyContext {
         user: user.clone(),
@@ -1090,25 +1514,18 @@ pub fn evaluate_access_policies(
     };
     let mut found_policy = false;
 
-    for i in 0..default_policy_ids.len() {
-        if let Some(policy_id) = default_policy_ids.get(i) {
-            let key = access_policy_key(&policy_id);
-            if let Some(policy) = env.storage().persistent().get::<_, AccessPolicy>(&key) {
+    for i in 0..policy_ids.len() {
+        if let Some(policy_id) = policy_ids.get(i) {
+            if let Some(policy) = get_access_policy(env, &policy_id) {
                 found_policy = true;
-                if evaluate_policy(env, &policy, &context) {
-                    return true;
+                if evaluate_policy_conditions(env, &policy, &context, check_consent) {
+                    return (true, true);
                 }
             }
         }
     }
 
-    // Backward-compatible default: if no ABAC policies are configured,
-    // don't block otherwise valid consent/access grants.
-    if !found_policy {
-        return true;
-    }
-
-    false
+    (found_policy, false)
 }
 
 /// Set user credential type
@@ -1131,10 +1548,52 @@ pub fn create_access_policy(env: &Env, policy: AccessPolicy) {
     env.storage().persistent().set(&key, &policy);
 }
 
+/// Get an access policy by ID
+pub fn get_access_policy(env: &Env, id: &String) -> Option<AccessPolicy> {
+    env.storage().persistent().get(&access_policy_key(id))
+}
+
+/// Maximum number of policy ids evaluated on each access check.
+pub const MAX_ACTIVE_POLICIES: u32 = 20;
+
+const ACTIVE_POLICIES: Symbol = symbol_short!("ACT_POLS");
+
+/// Replaces the list of policy ids consulted by [`evaluate_access_policies`].
+pub fn set_active_policies(env: &Env, ids: &Vec<String>) {
+    env.storage().instance().set(&ACTIVE_POLICIES, ids);
+}
+
+/// Policy ids consulted on access. Until an admin configures the list, the
+/// built-in `default_medical_access`, `emergency_access` and
+/// `research_access` ids are used.
+pub fn get_active_policies(env: &Env) -> Vec<String> {
+    if let Some(ids) = env.storage().instance().get(&ACTIVE_POLICIES) {
+        return ids;
+    }
+    let mut ids = Vec::new(env);
+    ids.push_back(String::from_str(env, "default_medical_access"));
+    ids.push_back(String::from_str(env, "emergency_access"));
+    ids.push_back(String::from_str(env, "research_access"));
+    ids
+}
+
+/// Enable or disable an existing access policy. Returns false if it does not exist.
+pub fn set_policy_enabled(env: &Env, id: &String, enabled: bool) -> bool {
+    match get_access_policy(env, id) {
+        Some(mut policy) => {
+            policy.enabled = enabled;
+            create_access_policy(env, policy);
+            true
+        }
+        None => false,
+    }
+}
+
 fn extend_ttl_u64_key(env: &Env, key: &(soroban_sdk::Symbol, u64)) {
+    let policy = crate::ttl::get_policy(env);
     env.storage()
         .persistent()
-        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+        .extend_ttl(key, policy.threshold, policy.extend_to);
 }
 
 /// Consent grant structure for ABAC evaluation
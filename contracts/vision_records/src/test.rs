@@ -76,10 +76,30 @@ fn test_initialize() {
 
     // Grant access calls should also consume the same per-address budget
     let doctor = Address::generate(&env);
-    client.grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
-    client.grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
-    let rate_limited =
-        client.try_grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    let rate_limited = client.try_grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
     assert!(rate_limited.is_err());
 }
 
@@ -98,7 +118,14 @@ fn test_permission_without_consent_denied() {
     let doctor = Address::generate(&env);
 
     // Grant access but NOT consent
-    client.grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
 
     // Access denied — no consent
     assert_eq!(client.check_access(&patient, &doctor), AccessLevel::None);
@@ -120,7 +147,14 @@ fn test_consent_and_permission_grants_access() {
 
     // Grant both consent and access
     client.grant_consent(&patient, &doctor, &ConsentType::Treatment, &86400);
-    client.grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
 
     assert_eq!(client.check_access(&patient, &doctor), AccessLevel::Read);
 }
@@ -140,7 +174,14 @@ fn test_revoked_consent_blocks_access() {
     let doctor = Address::generate(&env);
 
     client.grant_consent(&patient, &doctor, &ConsentType::Sharing, &86400);
-    client.grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
     assert_eq!(client.check_access(&patient, &doctor), AccessLevel::Read);
 
     // Revoke consent
@@ -166,7 +207,14 @@ fn test_expired_consent_blocks_access() {
 
     // Grant short-lived consent and long-lived access
     client.grant_consent(&patient, &doctor, &ConsentType::Research, &100);
-    client.grant_access(&patient, &patient, &doctor, &AccessLevel::Read, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
 
     assert_eq!(client.check_access(&patient, &doctor), AccessLevel::Read);
 
@@ -214,3 +262,1145 @@ fn test_get_record_consent_required() {
     let record = client.get_record(&doctor, &record_id);
     assert_eq!(record.patient, patient);
 }
+
+#[test]
+fn test_get_access_grant_returns_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let doctor = Address::generate(&env);
+
+    assert!(client.get_access_grant(&patient, &doctor).is_none());
+
+    env.ledger().set_timestamp(1000);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Write,
+        &86400,
+        &ConsentType::Treatment,
+    );
+
+    let grant = client.get_access_grant(&patient, &doctor).unwrap();
+    assert_eq!(grant.level, AccessLevel::Write);
+    assert_eq!(grant.granted_at, 1000);
+    assert_eq!(grant.expires_at, 1000 + 86400);
+
+    // The grant is still returned after it lapses.
+    env.ledger().set_timestamp(1000 + 86400);
+    let grant = client.get_access_grant(&patient, &doctor).unwrap();
+    assert_eq!(grant.expires_at, 1000 + 86400);
+}
+
+#[test]
+fn test_get_patient_grantees_returns_only_active() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let kept = Address::generate(&env);
+    let revoked = Address::generate(&env);
+    let expiring = Address::generate(&env);
+
+    client.grant_access(
+        &patient,
+        &patient,
+        &kept,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    client.grant_access(
+        &patient,
+        &patient,
+        &revoked,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    client.grant_access(
+        &patient,
+        &patient,
+        &expiring,
+        &AccessLevel::Read,
+        &3600,
+        &ConsentType::Treatment,
+    );
+    assert_eq!(client.get_patient_grantees(&patient, &patient).len(), 3);
+
+    client.revoke_access(&patient, &revoked);
+    env.ledger().set_timestamp(3600);
+
+    let grantees = client.get_patient_grantees(&patient, &patient);
+    assert_eq!(grantees.len(), 1);
+    assert_eq!(grantees.get(0).unwrap().grantee, kept);
+
+    // Admins can audit too; strangers cannot.
+    assert_eq!(client.get_patient_grantees(&admin, &patient).len(), 1);
+    let res = client.try_get_patient_grantees(&kept, &patient);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+}
+
+#[test]
+fn test_upgrade_and_migrate_require_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.version(), 1);
+
+    let stranger = Address::generate(&env);
+    let hash = BytesN::from_array(&env, &[7u8; 32]);
+    let res = client.try_upgrade(&stranger, &hash);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    let res = client.try_migrate(&stranger);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    // Already at the current version, so migrating is a no-op.
+    let current = client.contract_version();
+    assert_eq!(client.migrate(&admin), current);
+    assert_eq!(client.version(), 1);
+}
+
+#[test]
+fn test_migrate_bumps_contract_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Simulate state written by code from before semantic versioning.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&SEMVER);
+        env.storage().instance().set(&VERSION, &1u32);
+    });
+    let old = client.contract_version();
+    assert_eq!(
+        old,
+        ContractVersion {
+            major: 1,
+            minor: 0,
+            patch: 0
+        }
+    );
+
+    let migrated = client.migrate(&admin);
+    assert_eq!(
+        migrated,
+        ContractVersion {
+            major: 1,
+            minor: 1,
+            patch: 0
+        }
+    );
+    assert!(migrated > old);
+    assert_eq!(client.contract_version(), migrated);
+    assert_eq!(client.version(), migrated.major);
+}
+
+#[test]
+fn test_missing_version_is_treated_as_legacy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Deployments that never stored a version must still be migrated.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&SEMVER);
+        env.storage().instance().remove(&VERSION);
+    });
+    assert_eq!(client.contract_version(), LEGACY_VERSION);
+
+    assert_eq!(client.migrate(&admin), CONTRACT_VERSION);
+    assert_eq!(client.contract_version(), CONTRACT_VERSION);
+}
+
+#[test]
+fn test_ttl_policy_applies_to_new_entries() {
+    use soroban_sdk::testutils::storage::Persistent as _;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let policy = client.get_ttl_policy();
+    assert_eq!(policy.threshold, ttl::DEFAULT_TTL_THRESHOLD);
+    assert_eq!(policy.extend_to, ttl::DEFAULT_TTL_EXTEND_TO);
+
+    let res = client.try_set_ttl_policy(&admin, &200_000, &100_000);
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+    let stranger = Address::generate(&env);
+    let res = client.try_set_ttl_policy(&stranger, &100_000, &200_000);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    client.set_ttl_policy(&admin, &100_000, &200_000);
+    let policy = client.get_ttl_policy();
+    assert_eq!(policy.threshold, 100_000);
+    assert_eq!(policy.extend_to, 200_000);
+
+    let user = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &user,
+        &Role::Patient,
+        &String::from_str(&env, "Patient"),
+    );
+
+    let live_for = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&(symbol_short!("USER"), user.clone()))
+    });
+    assert_eq!(live_for, 200_000);
+}
+
+#[test]
+fn test_patient_access_summary_for_read_grantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let doctor = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let first = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let second = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Prescription,
+        &data_hash,
+    );
+
+    // No grant yet: nothing is revealed.
+    let summary = client.get_patient_access_summary(&doctor, &patient);
+    assert_eq!(summary.level, AccessLevel::None);
+    assert_eq!(summary.record_count, 0);
+
+    client.grant_consent(&patient, &doctor, &ConsentType::Treatment, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+
+    let summary = client.get_patient_access_summary(&doctor, &patient);
+    assert_eq!(summary.level, AccessLevel::Read);
+    assert_eq!(summary.record_count, 2);
+    assert_eq!(summary.record_ids.get(0).unwrap(), first);
+    assert_eq!(summary.record_ids.get(1).unwrap(), second);
+}
+
+#[test]
+fn test_record_versions_track_each_data_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let v1 = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let v2 = String::from_str(&env, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o");
+    let v3 = String::from_str(&env, "QmPChd2hVbrJ6bfo3WBcTW4iZnpHm8TEzWkLHmLpXhF68A");
+
+    env.ledger().set_timestamp(1000);
+    let record_id = client.add_record(&admin, &patient, &admin, &RecordType::Examination, &v1);
+    env.ledger().set_timestamp(2000);
+    client.update_record(
+        &admin,
+        &record_id,
+        &client.get_record_version_stamp(&record_id).version,
+        &0,
+        &v2,
+    );
+    env.ledger().set_timestamp(3000);
+    client.update_record(
+        &admin,
+        &record_id,
+        &client.get_record_version_stamp(&record_id).version,
+        &0,
+        &v3,
+    );
+
+    let versions = client.get_record_versions(&patient, &record_id);
+    assert_eq!(versions.len(), 3);
+    for (i, (hash, at)) in [(v1, 1000), (v2, 2000), (v3.clone(), 3000)]
+        .into_iter()
+        .enumerate()
+    {
+        let version = versions.get(i as u32).unwrap();
+        assert_eq!(version.data_hash, hash);
+        assert_eq!(version.updated_at, at);
+        assert_eq!(version.updated_by, admin);
+    }
+    assert_eq!(client.get_record(&patient, &record_id).data_hash, v3);
+
+    // Same gating as get_record.
+    assert!(client
+        .try_get_record_versions(&outsider, &record_id)
+        .is_err());
+}
+
+#[test]
+fn test_record_counts_per_patient_and_provider() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let provider_a = Address::generate(&env);
+    let provider_b = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider_a,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. A"),
+    );
+    client.register_user(
+        &admin,
+        &provider_b,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. B"),
+    );
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+
+    for (patient, provider) in [
+        (&alice, &provider_a),
+        (&alice, &provider_b),
+        (&bob, &provider_a),
+    ] {
+        client.add_record(
+            provider,
+            patient,
+            provider,
+            &RecordType::Examination,
+            &data_hash,
+        );
+    }
+
+    let mut batch = Vec::new(&env);
+    batch.push_back(BatchRecordInput {
+        patient: bob.clone(),
+        record_type: RecordType::Prescription,
+        data_hash: data_hash.clone(),
+    });
+    client.add_records(&provider_b, &batch);
+
+    assert_eq!(client.get_patient_record_count(&alice), 2);
+    assert_eq!(client.get_patient_record_count(&bob), 2);
+    assert_eq!(client.get_provider_record_count(&provider_a), 2);
+    assert_eq!(client.get_provider_record_count(&provider_b), 2);
+    assert_eq!(client.get_patient_record_count(&Address::generate(&env)), 0);
+    assert_eq!(client.get_record_count(), 4);
+}
+
+#[test]
+fn test_research_grant_does_not_allow_treatment_read() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let researcher = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+
+    client.grant_consent(&patient, &researcher, &ConsentType::Research, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &researcher,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Research,
+    );
+    assert_eq!(
+        client.get_access_grant_purpose(&patient, &researcher),
+        ConsentType::Research
+    );
+
+    let record = client.get_record_for_purpose(&researcher, &record_id, &ConsentType::Research);
+    assert_eq!(record.id, record_id);
+
+    assert_eq!(
+        client
+            .try_get_record_for_purpose(&researcher, &record_id, &ConsentType::Treatment)
+            .map(|_| ()),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_get_record(&researcher, &record_id).map(|_| ()),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_grant_stored_without_purpose_reads_as_treatment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let doctor = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    client.grant_consent(&patient, &doctor, &ConsentType::Treatment, &86400);
+
+    // A grant written before purposes existed has no purpose entry.
+    env.as_contract(&contract_id, || {
+        let grant = AccessGrant {
+            patient: patient.clone(),
+            grantee: doctor.clone(),
+            level: AccessLevel::Read,
+            granted_at: 0,
+            expires_at: 86400,
+        };
+        env.storage().persistent().set(
+            &(symbol_short!("ACCESS"), patient.clone(), doctor.clone()),
+            &grant,
+        );
+    });
+
+    assert_eq!(
+        client.get_access_grant_purpose(&patient, &doctor),
+        ConsentType::Treatment
+    );
+    assert_eq!(client.check_access(&patient, &doctor), AccessLevel::Read);
+    assert_eq!(client.get_record(&doctor, &record_id).id, record_id);
+}
+
+fn verified_doctor(env: &Env, client: &VisionRecordsContractClient, admin: &Address) -> Address {
+    let doctor = Address::generate(env);
+    client.register_user(
+        admin,
+        &doctor,
+        &Role::Optometrist,
+        &String::from_str(env, "Dr. Request"),
+    );
+    client.register_provider(
+        admin,
+        &doctor,
+        &String::from_str(env, "Dr. Request"),
+        &Vec::new(env),
+        &Vec::new(env),
+        &Vec::new(env),
+        &Vec::new(env),
+    );
+    client.verify_provider(admin, &doctor, &VerificationStatus::Verified);
+    doctor
+}
+
+#[test]
+fn test_access_request_approved_becomes_grant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let doctor = verified_doctor(&env, &client, &admin);
+
+    let request_id = client.request_access(&doctor, &patient, &AccessLevel::Read, &86400);
+    let pending = client.get_pending_access_requests(&patient);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().provider, doctor);
+    assert!(client.get_access_grant(&patient, &doctor).is_none());
+
+    client.respond_to_access_request(&patient, &request_id, &true);
+
+    assert_eq!(client.get_pending_access_requests(&patient).len(), 0);
+    let grant = client.get_access_grant(&patient, &doctor).unwrap();
+    assert_eq!(grant.level, AccessLevel::Read);
+    assert_eq!(grant.expires_at, grant.granted_at + 86400);
+
+    client.grant_consent(&patient, &doctor, &ConsentType::Treatment, &86400);
+    assert_eq!(client.check_access(&patient, &doctor), AccessLevel::Read);
+
+    // A request can only be answered once.
+    assert_eq!(
+        client.try_respond_to_access_request(&patient, &request_id, &false),
+        Err(Ok(ContractError::InvalidPhase))
+    );
+}
+
+#[test]
+fn test_access_request_denied_grants_nothing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let doctor = verified_doctor(&env, &client, &admin);
+    let stranger = Address::generate(&env);
+
+    let request_id = client.request_access(&doctor, &patient, &AccessLevel::Write, &86400);
+
+    // Only the patient may answer.
+    assert_eq!(
+        client.try_respond_to_access_request(&stranger, &request_id, &true),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    client.respond_to_access_request(&patient, &request_id, &false);
+    assert_eq!(client.get_pending_access_requests(&patient).len(), 0);
+    assert!(client.get_access_grant(&patient, &doctor).is_none());
+
+    assert_eq!(
+        client.try_respond_to_access_request(&patient, &99, &true),
+        Err(Ok(ContractError::AccessRequestNotFound))
+    );
+}
+
+#[test]
+fn test_access_request_requires_verified_provider_and_is_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_request_access(&stranger, &patient, &AccessLevel::Read, &86400),
+        Err(Ok(ContractError::AccessDenied))
+    );
+
+    let doctor = verified_doctor(&env, &client, &admin);
+    for _ in 0..MAX_PENDING_ACCESS_REQUESTS {
+        client.request_access(&doctor, &patient, &AccessLevel::Read, &86400);
+    }
+    assert_eq!(
+        client.try_request_access(&doctor, &patient, &AccessLevel::Read, &86400),
+        Err(Ok(ContractError::TooManyPendingRequests))
+    );
+
+    // Answering a request frees a slot.
+    let first = client.get_pending_access_requests(&patient).get(0).unwrap().id;
+    client.respond_to_access_request(&patient, &first, &false);
+    client.request_access(&doctor, &patient, &AccessLevel::Read, &86400);
+}
+
+#[test]
+fn test_restricted_record_requires_credential() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let restricted = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let standard = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    client.set_record_sensitivity(&admin, &restricted, &SensitivityLevel::Restricted);
+    client.set_record_sensitivity(&admin, &standard, &SensitivityLevel::Standard);
+
+    let grantee = Address::generate(&env);
+    client.grant_consent(&patient, &grantee, &ConsentType::Treatment, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &grantee,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+
+    // Normal access suffices for non-restricted records.
+    client.get_record(&grantee, &standard);
+    assert_eq!(
+        client.try_get_record(&grantee, &restricted).map(|_| ()),
+        Err(Ok(ContractError::AccessDenied))
+    );
+
+    // The patient is never blocked from their own record.
+    client.get_record(&patient, &restricted);
+
+    client.set_user_credential(&admin, &grantee, &CredentialType::MedicalLicense);
+    assert_eq!(
+        client.try_get_record(&grantee, &restricted).map(|_| ()),
+        Err(Ok(ContractError::AccessDenied))
+    );
+    let reason = String::from_str(&env, "follow-up on referral");
+    assert_eq!(
+        client
+            .get_record_with_reason(&grantee, &restricted, &reason)
+            .id,
+        restricted
+    );
+}
+
+#[test]
+fn test_patient_record_index_stays_ascending_and_unique() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+
+    let first = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let mut batch = Vec::new(&env);
+    for _ in 0..2 {
+        batch.push_back(BatchRecordInput {
+            patient: patient.clone(),
+            record_type: RecordType::Prescription,
+            data_hash: data_hash.clone(),
+        });
+    }
+    client.add_records(&admin, &batch);
+    client.update_record(
+        &admin,
+        &first,
+        &client.get_record_version_stamp(&first).version,
+        &0,
+        &data_hash,
+    );
+
+    // Re-indexing existing ids, in any order, changes nothing.
+    env.as_contract(&contract_id, || {
+        track_patient_record(&env, &patient, 3);
+        track_patient_record(&env, &patient, first);
+    });
+
+    let ids = client.get_patient_records(&patient);
+    assert_eq!(ids.len(), 3);
+    assert!(is_strictly_ascending(&ids));
+    assert_eq!(ids.get(0).unwrap(), first);
+
+    // Out-of-order inserts are placed in position.
+    env.as_contract(&contract_id, || {
+        track_patient_record(&env, &patient, 10);
+        track_patient_record(&env, &patient, 7);
+    });
+    let ids = client.get_patient_records(&patient);
+    assert!(is_strictly_ascending(&ids));
+    assert_eq!(ids.get(3).unwrap(), 7);
+    assert_eq!(ids.get(4).unwrap(), 10);
+}
+
+#[test]
+fn test_transfer_record_custody_moves_write_rights() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let old_clinic = Address::generate(&env);
+    let new_clinic = Address::generate(&env);
+    for (provider, name) in [(&old_clinic, "Old Clinic"), (&new_clinic, "New Clinic")] {
+        client.register_user(
+            &admin,
+            provider,
+            &Role::Optometrist,
+            &String::from_str(&env, name),
+        );
+    }
+
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &old_clinic,
+        &patient,
+        &old_clinic,
+        &RecordType::Examination,
+        &data_hash,
+    );
+
+    // Strangers cannot move custody.
+    assert_eq!(
+        client.try_transfer_record_custody(&new_clinic, &record_id, &new_clinic),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    // The new custodian must be a verified provider.
+    assert_eq!(
+        client.try_transfer_record_custody(&patient, &record_id, &new_clinic),
+        Err(Ok(ContractError::AccessDenied))
+    );
+    let mut specialties = Vec::new(&env);
+    specialties.push_back(String::from_str(&env, "Optometry"));
+    client.register_provider(
+        &admin,
+        &new_clinic,
+        &String::from_str(&env, "New Clinic"),
+        &Vec::new(&env),
+        &specialties,
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(
+        client.try_transfer_record_custody(&patient, &record_id, &new_clinic),
+        Err(Ok(ContractError::AccessDenied))
+    );
+    client.verify_provider(&admin, &new_clinic, &VerificationStatus::Verified);
+
+    client.transfer_record_custody(&patient, &record_id, &new_clinic);
+
+    assert_eq!(client.get_record(&patient, &record_id).provider, new_clinic);
+    assert_eq!(client.get_provider_record_count(&old_clinic), 0);
+    assert_eq!(client.get_provider_record_count(&new_clinic), 1);
+
+    client.update_record(
+        &new_clinic,
+        &record_id,
+        &client.get_record_version_stamp(&record_id).version,
+        &0,
+        &data_hash,
+    );
+    assert_eq!(
+        client.try_update_record(
+            &old_clinic,
+            &record_id,
+            &client.get_record_version_stamp(&record_id).version,
+            &0,
+            &data_hash
+        ),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    let log = env.as_contract(&contract_id, || {
+        audit::get_record_audit_log(&env, record_id)
+    });
+    assert!(log
+        .iter()
+        .any(|e| e.reason == Some(String::from_str(&env, "CUSTODY_TRANSFER"))));
+}
+
+#[test]
+fn test_get_record_reports_lapsed_grant_as_access_expired() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Smith"),
+    );
+
+    let patient = Address::generate(&env);
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let former = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.grant_access(
+        &patient,
+        &patient,
+        &former,
+        &AccessLevel::Read,
+        &3600,
+        &ConsentType::Treatment,
+    );
+    client.grant_consent(&patient, &former, &ConsentType::Treatment, &3600);
+    assert!(client.try_get_record(&former, &record_id).is_ok());
+
+    let now = env.ledger().timestamp();
+    env.ledger().set_timestamp(now + 3601);
+
+    assert_eq!(
+        client.try_get_record(&former, &record_id).map(|_| ()),
+        Err(Ok(ContractError::AccessExpired))
+    );
+    assert_eq!(
+        client.try_get_record(&stranger, &record_id).map(|_| ()),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_typed_access_limits_grant_to_record_types() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Smith"),
+    );
+
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let rx_record = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Prescription,
+        &data_hash,
+    );
+    let surgery_record = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Surgery,
+        &data_hash,
+    );
+
+    let optician = Address::generate(&env);
+    client.grant_consent(&patient, &optician, &ConsentType::Treatment, &86400);
+    client.grant_typed_access(
+        &patient,
+        &patient,
+        &optician,
+        &AccessLevel::Read,
+        &Vec::from_array(&env, [RecordType::Prescription]),
+        &86400,
+    );
+    assert_eq!(
+        client.get_access_scope(&patient, &optician),
+        Vec::from_array(&env, [RecordType::Prescription])
+    );
+
+    assert!(client.try_get_record(&optician, &rx_record).is_ok());
+    assert_eq!(
+        client
+            .try_get_record(&optician, &surgery_record)
+            .map(|_| ()),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    // A plain grant drops the scope again.
+    client.grant_access(
+        &patient,
+        &patient,
+        &optician,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    assert!(client.get_access_scope(&patient, &optician).is_empty());
+    assert!(client.try_get_record(&optician, &surgery_record).is_ok());
+
+    // So does a batch grant.
+    let prescriptions_only = Vec::from_array(&env, [RecordType::Prescription]);
+    client.grant_typed_access(
+        &patient,
+        &patient,
+        &optician,
+        &AccessLevel::Read,
+        &prescriptions_only,
+        &86400,
+    );
+    client.grant_access_batch(
+        &patient,
+        &Vec::from_array(
+            &env,
+            [BatchGrantInput {
+                grantee: optician.clone(),
+                level: AccessLevel::Read,
+                duration_seconds: 86400,
+            }],
+        ),
+    );
+    assert!(client.get_access_scope(&patient, &optician).is_empty());
+}
+
+#[test]
+fn test_record_audit_filtered_by_action() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Smith"),
+    );
+
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let other_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &data_hash,
+    );
+
+    client.get_record(&patient, &record_id);
+    client.update_record(
+        &provider,
+        &record_id,
+        &client.get_record_version_stamp(&record_id).version,
+        &0,
+        &data_hash,
+    );
+    client.get_record(&provider, &record_id);
+    client.get_record(&patient, &other_id);
+
+    let reads = env.as_contract(&contract_id, || {
+        audit::get_record_audit_by_action(&env, record_id, AccessAction::Read, 0)
+    });
+    assert_eq!(reads.len(), 2);
+    assert_eq!(reads.get(0).unwrap().actor, patient);
+    assert_eq!(reads.get(1).unwrap().actor, provider);
+    assert!(reads
+        .iter()
+        .all(|e| e.record_id == Some(record_id) && e.action == AccessAction::Read));
+
+    let writes = env.as_contract(&contract_id, || {
+        audit::get_record_audit_by_action(&env, record_id, AccessAction::Write, 0)
+    });
+    assert!(!writes.is_empty());
+    assert!(writes.iter().all(|e| e.action == AccessAction::Write));
+}
+
+#[test]
+fn test_record_audit_index_is_paged() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    for _ in 0..=audit::AUDIT_RECORD_PAGE_SIZE {
+        client.get_record(&patient, &record_id);
+    }
+
+    env.as_contract(&contract_id, || {
+        let pages = audit::get_record_audit_page_count(&env, record_id);
+        assert_eq!(pages, 2);
+        let first = audit::get_record_audit_page(&env, record_id, 0);
+        assert_eq!(first.len(), audit::AUDIT_RECORD_PAGE_SIZE);
+        let last = audit::get_record_audit_page(&env, record_id, 1);
+        assert!(first.last().unwrap() < last.first().unwrap());
+
+        let log = audit::get_record_audit_log(&env, record_id);
+        assert_eq!(log.len(), first.len() + last.len());
+        let reads_on_last_page =
+            audit::get_record_audit_by_action(&env, record_id, AccessAction::Read, 1);
+        assert_eq!(reads_on_last_page.len(), last.len());
+    });
+}
+
+#[test]
+fn test_record_access_notification_opt_in() {
+    use crate::events::RecordAccessedEvent;
+    use soroban_sdk::testutils::Events;
+    use soroban_sdk::xdr::{ContractEventBody, ScVal};
+    use soroban_sdk::{IntoVal, TryFromVal, Val};
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let doctor = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    client.grant_consent(&patient, &doctor, &ConsentType::Treatment, &86400);
+    env.ledger().set_timestamp(5_000);
+
+    let event: Val = RecordAccessedEvent {
+        patient: patient.clone(),
+        record_id,
+        accessor: doctor.clone(),
+        timestamp: 5_000,
+    }
+    .into_val(&env);
+    let expected = ScVal::try_from_val(&env, &event).unwrap();
+    let notified = |env: &Env| {
+        env.events().all().events().iter().any(|event| {
+            let ContractEventBody::V0(body) = &event.body;
+            body.data == expected
+        })
+    };
+
+    // Off by default.
+    assert!(!client.get_access_notifications(&patient));
+    client.get_record(&doctor, &record_id);
+    assert!(!notified(&env));
+
+    client.set_access_notifications(&patient, &true);
+    assert!(client.get_access_notifications(&patient));
+    client.get_record(&doctor, &record_id);
+    assert!(notified(&env));
+
+    client.set_access_notifications(&patient, &false);
+    client.get_record(&doctor, &record_id);
+    assert!(!notified(&env));
+}
+
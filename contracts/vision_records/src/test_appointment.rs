@@ -0,0 +1,746 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::arithmetic_side_effects
+)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Env;
+
+const HOUR: u64 = 3600;
+const DAY: u64 = 86400;
+
+fn setup() -> (Env, VisionRecordsContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    env.ledger().set_timestamp(1_000);
+
+    (env, client, admin)
+}
+
+fn schedule(
+    client: &VisionRecordsContractClient,
+    patient: &Address,
+    provider: &Address,
+    at: u64,
+    duration_minutes: u32,
+) -> Result<u64, ContractError> {
+    match client.try_schedule_appointment(
+        patient,
+        patient,
+        provider,
+        &AppointmentType::Examination,
+        &at,
+        &duration_minutes,
+        &None,
+        &None,
+    ) {
+        Ok(Ok(id)) => Ok(id),
+        Err(Ok(err)) => Err(err),
+        _ => panic!("unexpected host error"),
+    }
+}
+
+#[test]
+fn test_overlapping_slot_is_rejected() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let at = DAY;
+    schedule(&client, &alice, &provider, at, 30).unwrap();
+
+    // Starts inside the first appointment.
+    assert_eq!(
+        schedule(&client, &bob, &provider, at + 15 * 60, 30),
+        Err(ContractError::SlotUnavailable)
+    );
+    // Ends inside the first appointment.
+    assert_eq!(
+        schedule(&client, &bob, &provider, at - 10 * 60, 30),
+        Err(ContractError::SlotUnavailable)
+    );
+
+    // Back-to-back and other providers are fine.
+    assert!(schedule(&client, &bob, &provider, at + 30 * 60, 30).is_ok());
+    let other = Address::generate(&env);
+    assert!(schedule(&client, &bob, &other, at, 30).is_ok());
+}
+
+#[test]
+fn test_overlap_across_midnight_and_after_reschedule() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    // An evening booking that runs past midnight blocks the next morning.
+    let late = 2 * DAY - HOUR;
+    let id = schedule(&client, &alice, &provider, late, 120).unwrap();
+    assert_eq!(
+        schedule(&client, &bob, &provider, 2 * DAY + 30 * 60, 30),
+        Err(ContractError::SlotUnavailable)
+    );
+
+    // Moving it to another day frees the old slot and blocks the new one.
+    client.reschedule_appointment(&alice, &id, &(5 * DAY));
+    assert!(schedule(&client, &bob, &provider, 2 * DAY + 30 * 60, 30).is_ok());
+    assert_eq!(
+        schedule(&client, &bob, &provider, 5 * DAY + 15 * 60, 30),
+        Err(ContractError::SlotUnavailable)
+    );
+}
+
+#[test]
+fn test_cancelled_appointment_frees_slot() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let at = DAY + HOUR;
+    let first = schedule(&client, &alice, &provider, at, 60).unwrap();
+    client.cancel_appointment(&alice, &first);
+    assert_eq!(
+        client.get_appointment(&first).status,
+        AppointmentStatus::Cancelled
+    );
+
+    assert!(schedule(&client, &bob, &provider, at, 60).is_ok());
+
+    let res = client.try_cancel_appointment(&alice, &first);
+    assert_eq!(res, Err(Ok(ContractError::InvalidAppointmentStatus)));
+}
+
+#[test]
+fn test_availability_rejects_weekend_and_off_hours() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    // Monday–Friday, 09:00–17:00 UTC.
+    client.set_provider_availability(&provider, &0b011_1110, &9, &17);
+
+    // 1970-01-05 was a Monday; 1970-01-10 a Saturday.
+    let monday = 4 * DAY;
+    let saturday = 9 * DAY;
+
+    assert!(schedule(&client, &patient, &provider, monday + 10 * HOUR, 30).is_ok());
+    assert_eq!(
+        schedule(&client, &patient, &provider, saturday + 10 * HOUR, 30),
+        Err(ContractError::SlotUnavailable)
+    );
+    assert_eq!(
+        schedule(&client, &patient, &provider, monday + 3 * HOUR, 30),
+        Err(ContractError::SlotUnavailable)
+    );
+    // Would run past closing time.
+    assert_eq!(
+        schedule(
+            &client,
+            &patient,
+            &provider,
+            monday + 16 * HOUR + 30 * 60,
+            60
+        ),
+        Err(ContractError::SlotUnavailable)
+    );
+
+    let res = client.try_set_provider_availability(&provider, &0b011_1110, &17, &9);
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_recurring_weekly_appointments() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let first_at = DAY + 10 * HOUR;
+    let week = 7 * DAY;
+
+    // Someone already holds the third week's slot; it gets skipped.
+    let other = Address::generate(&env);
+    schedule(&client, &other, &provider, first_at + 2 * week, 30).unwrap();
+
+    let ids = client.schedule_recurring_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::FollowUp,
+        &first_at,
+        &30,
+        &week,
+        &5,
+    );
+    assert_eq!(ids.len(), 4);
+
+    let expected = [0u64, 1, 3, 4];
+    for (i, offset) in expected.iter().enumerate() {
+        let appt = client.get_appointment(&ids.get(i as u32).unwrap());
+        assert_eq!(appt.scheduled_at, first_at + offset * week);
+        assert_eq!(appt.patient, patient);
+        assert_eq!(appt.appointment_type, AppointmentType::FollowUp);
+    }
+
+    let too_many = client.try_schedule_recurring_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::FollowUp,
+        &first_at,
+        &30,
+        &week,
+        &(appointment::MAX_RECURRING_OCCURRENCES + 1),
+    );
+    assert_eq!(too_many, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_check_slot_available_matches_booking_rules() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let at = DAY;
+    assert!(client.check_slot_available(&provider, &at, &30));
+    schedule(&client, &patient, &provider, at, 30).unwrap();
+
+    // Overlapping slots are reported taken; adjacent ones stay free.
+    assert!(!client.check_slot_available(&provider, &at, &30));
+    assert!(!client.check_slot_available(&provider, &(at + 15 * 60), &30));
+    assert!(client.check_slot_available(&provider, &(at + 30 * 60), &30));
+    assert!(client.check_slot_available(&Address::generate(&env), &at, &30));
+
+    // Past times and bad durations are never available.
+    assert!(!client.check_slot_available(&provider, &500, &30));
+    assert!(!client.check_slot_available(&provider, &(2 * DAY), &0));
+    assert!(!client.check_slot_available(
+        &provider,
+        &(2 * DAY),
+        &(appointment::MAX_APPOINTMENT_MINUTES + 1)
+    ));
+
+    // Checking books nothing.
+    assert_eq!(client.get_provider_appointments(&provider).len(), 1);
+}
+
+#[test]
+fn test_reschedule_limit() {
+    let (env, client, admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    client.set_max_reschedules(&admin, &2);
+    let id = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    // Another booking blocks the slot it would move into.
+    schedule(&client, &Address::generate(&env), &provider, 4 * DAY, 30).unwrap();
+
+    client.reschedule_appointment(&patient, &id, &(2 * DAY));
+    let res = client.try_reschedule_appointment(&patient, &id, &(4 * DAY));
+    assert_eq!(res, Err(Ok(ContractError::SlotUnavailable)));
+    client.reschedule_appointment(&provider, &id, &(3 * DAY));
+
+    let appt = client.get_appointment(&id);
+    assert_eq!(appt.scheduled_at, 3 * DAY);
+    assert_eq!(appt.status, AppointmentStatus::Rescheduled);
+    assert_eq!(appt.reschedule_count, 2);
+
+    let res = client.try_reschedule_appointment(&patient, &id, &(5 * DAY));
+    assert_eq!(res, Err(Ok(ContractError::TooManyReschedules)));
+    assert_eq!(client.get_appointment(&id).scheduled_at, 3 * DAY);
+}
+
+#[test]
+fn test_reschedule_rejects_overlap_but_not_own_slot() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let id = schedule(&client, &patient, &provider, DAY, 60).unwrap();
+    schedule(
+        &client,
+        &Address::generate(&env),
+        &provider,
+        DAY + 2 * HOUR,
+        60,
+    )
+    .unwrap();
+
+    // Overlapping the tail of the other booking is a double-booking.
+    let res = client.try_reschedule_appointment(&patient, &id, &(DAY + 90 * 60));
+    assert_eq!(res, Err(Ok(ContractError::SlotUnavailable)));
+    assert_eq!(client.get_appointment(&id).scheduled_at, DAY);
+
+    // Sliding within its own current slot does not conflict with itself.
+    client.reschedule_appointment(&patient, &id, &(DAY + 30 * 60));
+    assert_eq!(client.get_appointment(&id).scheduled_at, DAY + 30 * 60);
+
+    // Back-to-back with the other booking is fine.
+    client.reschedule_appointment(&provider, &id, &(DAY + HOUR));
+    assert_eq!(client.get_appointment(&id).scheduled_at, DAY + HOUR);
+}
+
+#[test]
+fn test_mark_no_show() {
+    let (env, client, admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let at = DAY;
+    let id = schedule(&client, &patient, &provider, at, 30).unwrap();
+
+    // Not yet happened.
+    let res = client.try_mark_no_show(&provider, &id);
+    assert_eq!(res, Err(Ok(ContractError::InvalidAppointmentTime)));
+
+    env.ledger().set_timestamp(at + HOUR);
+
+    let res = client.try_mark_no_show(&patient, &id);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    client.mark_no_show(&provider, &id);
+    assert_eq!(
+        client.get_appointment(&id).status,
+        AppointmentStatus::NoShow
+    );
+
+    let history = client.get_appointment_history(&id);
+    let last = history.get(history.len() - 1).unwrap();
+    assert_eq!(last.action, String::from_str(&env, "NO_SHOW"));
+    assert_eq!(last.previous_status, AppointmentStatus::Scheduled);
+
+    let res = client.try_mark_no_show(&admin, &id);
+    assert_eq!(res, Err(Ok(ContractError::InvalidAppointmentStatus)));
+}
+
+#[test]
+fn test_repeated_no_shows_gate_self_scheduling() {
+    let (env, client, admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    client.set_no_show_threshold(&admin, &1);
+
+    let first = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    let second = schedule(&client, &patient, &provider, DAY + HOUR, 30).unwrap();
+    env.ledger().set_timestamp(DAY + 2 * HOUR);
+    client.mark_no_show(&provider, &first);
+    // Reaching the threshold is allowed; only going above it is gated.
+    assert!(!client.schedule_requires_approval(&patient));
+    client.mark_no_show(&provider, &second);
+
+    assert_eq!(client.get_patient_no_show_count(&patient), 2);
+    assert!(client.schedule_requires_approval(&patient));
+    assert_eq!(
+        schedule(&client, &patient, &provider, 2 * DAY, 30),
+        Err(ContractError::TooManyNoShows)
+    );
+
+    // The provider can still book the patient in.
+    client.schedule_appointment(
+        &provider,
+        &patient,
+        &provider,
+        &AppointmentType::Examination,
+        &(2 * DAY),
+        &30,
+        &None,
+        &None,
+    );
+
+    let res = client.try_reset_patient_no_shows(&patient, &patient);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+    client.reset_patient_no_shows(&admin, &patient);
+    assert_eq!(client.get_patient_no_show_count(&patient), 0);
+    assert!(schedule(&client, &patient, &provider, 3 * DAY, 30).is_ok());
+}
+
+#[test]
+fn test_expire_past_appointments() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let stale = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    let cancelled = schedule(&client, &patient, &provider, DAY + HOUR, 30).unwrap();
+    client.cancel_appointment(&patient, &cancelled);
+    let recent = schedule(&client, &patient, &provider, 3 * DAY, 30).unwrap();
+
+    env.ledger().set_timestamp(3 * DAY + HOUR);
+    assert_eq!(client.expire_past_appointments(), 1);
+
+    assert_eq!(
+        client.get_appointment(&stale).status,
+        AppointmentStatus::NoShow
+    );
+    assert_eq!(
+        client.get_appointment(&cancelled).status,
+        AppointmentStatus::Cancelled
+    );
+    // Still inside the grace period.
+    assert_eq!(
+        client.get_appointment(&recent).status,
+        AppointmentStatus::Scheduled
+    );
+
+    env.ledger().set_timestamp(4 * DAY + HOUR);
+    assert_eq!(client.expire_past_appointments(), 1);
+    assert_eq!(
+        client.get_appointment(&recent).status,
+        AppointmentStatus::NoShow
+    );
+    assert_eq!(client.expire_past_appointments(), 0);
+}
+
+#[test]
+fn test_expiry_is_not_blocked_by_future_appointment() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    // The oldest id is far in the future and holds the low-water cursor.
+    let future = schedule(&client, &patient, &provider, 100 * DAY, 30).unwrap();
+    let mut stale = Vec::new(&env);
+    for i in 0..10u64 {
+        stale.push_back(schedule(&client, &patient, &provider, DAY + i * HOUR, 30).unwrap());
+    }
+
+    env.ledger().set_timestamp(5 * DAY);
+    // The first run covers one batch; the next continues past it.
+    assert_eq!(client.expire_past_appointments(), 7);
+    assert_eq!(client.expire_past_appointments(), 3);
+    assert_eq!(client.expire_past_appointments(), 0);
+
+    for id in stale.iter() {
+        assert_eq!(
+            client.get_appointment(&id).status,
+            AppointmentStatus::NoShow
+        );
+    }
+    assert_eq!(
+        client.get_appointment(&future).status,
+        AppointmentStatus::Scheduled
+    );
+}
+
+#[test]
+fn test_waitlist_promotes_first_compatible_patient() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    // Carol can only come much later; Alice then Bob are flexible.
+    client.join_waitlist(&carol, &provider, &(10 * DAY), &AppointmentType::Routine);
+    client.join_waitlist(&alice, &provider, &DAY, &AppointmentType::Examination);
+    client.join_waitlist(&bob, &provider, &DAY, &AppointmentType::Consultation);
+    assert_eq!(client.get_waitlist(&provider).len(), 3);
+
+    let res = client.try_join_waitlist(&alice, &provider, &DAY, &AppointmentType::Examination);
+    assert_eq!(res, Err(Ok(ContractError::DuplicateRecord)));
+
+    let slot = 2 * DAY;
+    let id = client.promote_from_waitlist(&provider, &slot, &30);
+    let appt = client.get_appointment(&id);
+    assert_eq!(appt.patient, alice);
+    assert_eq!(appt.scheduled_at, slot);
+    assert_eq!(appt.appointment_type, AppointmentType::Examination);
+
+    let waitlist = client.get_waitlist(&provider);
+    assert_eq!(waitlist.len(), 2);
+    assert_eq!(waitlist.get(0).unwrap().patient, carol);
+    assert_eq!(waitlist.get(1).unwrap().patient, bob);
+
+    // Nobody left who can make an early slot besides Bob.
+    let id = client.promote_from_waitlist(&provider, &(3 * DAY), &30);
+    assert_eq!(client.get_appointment(&id).patient, bob);
+    let res = client.try_promote_from_waitlist(&provider, &(4 * DAY), &30);
+    assert_eq!(res, Err(Ok(ContractError::AppointmentNotFound)));
+}
+
+#[test]
+fn test_provider_appointment_queries() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let a = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    let b = schedule(&client, &patient, &provider, DAY + HOUR, 30).unwrap();
+    let c = schedule(&client, &patient, &provider, 2 * DAY, 30).unwrap();
+    client.cancel_appointment(&patient, &b);
+
+    let scheduled = client.get_provider_appts_by_status(&provider, &AppointmentStatus::Scheduled);
+    assert_eq!(scheduled.len(), 2);
+    assert_eq!(scheduled.get(0).unwrap().id, a);
+    assert_eq!(scheduled.get(1).unwrap().id, c);
+
+    let cancelled = client.get_provider_appts_by_status(&provider, &AppointmentStatus::Cancelled);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled.get(0).unwrap().id, b);
+
+    let day_one = client.get_provider_appts_in_range(&provider, &DAY, &(2 * DAY));
+    assert_eq!(day_one.len(), 2);
+    let day_two = client.get_provider_appts_in_range(&provider, &(2 * DAY), &(3 * DAY));
+    assert_eq!(day_two.len(), 1);
+    assert_eq!(day_two.get(0).unwrap().id, c);
+}
+
+#[test]
+fn test_provider_queries_are_not_limited_to_recent_ids() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let first = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    // Push the provider's appointment beyond the last 100 ids.
+    for _ in 0..101 {
+        let other = Address::generate(&env);
+        schedule(&client, &patient, &other, DAY, 30).unwrap();
+    }
+
+    let all = client.get_provider_appointments(&provider);
+    assert_eq!(all.len(), 1);
+    assert_eq!(all.get(0).unwrap().id, first);
+}
+
+#[test]
+fn test_multi_stage_reminders() {
+    let (env, client, _admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let at = 3 * DAY;
+    let id = schedule(&client, &patient, &provider, at, 30).unwrap();
+
+    // Too early for either reminder.
+    env.ledger().set_timestamp(at - 2 * DAY);
+    assert_eq!(client.send_appointment_reminders(&DAY), 0);
+
+    // 24h reminder fires once.
+    env.ledger().set_timestamp(at - 20 * HOUR);
+    assert_eq!(client.send_appointment_reminders(&DAY), 1);
+    assert_eq!(client.send_appointment_reminders(&DAY), 0);
+    assert_eq!(client.send_appointment_reminders(&HOUR), 0);
+
+    // 1h reminder fires once as well.
+    env.ledger().set_timestamp(at - 30 * 60);
+    assert_eq!(client.send_appointment_reminders(&HOUR), 1);
+    assert_eq!(client.send_appointment_reminders(&HOUR), 0);
+    assert_eq!(client.send_appointment_reminders(&DAY), 0);
+
+    let appt = client.get_appointment(&id);
+    assert_eq!(appt.reminders_sent.len(), 2);
+    assert_eq!(appt.reminders_sent.get(0).unwrap(), DAY);
+    assert_eq!(appt.reminders_sent.get(1).unwrap(), HOUR);
+}
+
+#[test]
+fn test_attach_record_to_completed_appointment() {
+    let (env, client, admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Visit"),
+    );
+
+    let id = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    // Only completed visits can be linked.
+    let res = client.try_attach_record_to_appointment(&provider, &id, &record_id);
+    assert_eq!(res, Err(Ok(ContractError::InvalidAppointmentStatus)));
+
+    client.complete_appointment(&provider, &id);
+    assert_eq!(
+        client.get_appointment(&id).status,
+        AppointmentStatus::Completed
+    );
+    assert_eq!(client.get_appointment_record(&id), None);
+
+    // A record for someone else does not match the visit.
+    let other_patient = Address::generate(&env);
+    let other_record = client.add_record(
+        &provider,
+        &other_patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdH"),
+    );
+    let res = client.try_attach_record_to_appointment(&provider, &id, &other_record);
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+
+    client.attach_record_to_appointment(&provider, &id, &record_id);
+    assert_eq!(client.get_appointment_record(&id), Some(record_id));
+
+    let res = client.try_attach_record_to_appointment(&provider, &id, &record_id);
+    assert_eq!(res, Err(Ok(ContractError::DuplicateRecord)));
+}
+
+#[test]
+fn test_late_cancellation_is_flagged() {
+    let (env, client, admin) = setup();
+    let provider = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    client.set_cancellation_window(&admin, &DAY);
+    assert_eq!(client.get_cancellation_window(), DAY);
+
+    let early = schedule(&client, &patient, &provider, 5 * DAY, 30).unwrap();
+    let late = schedule(&client, &patient, &provider, 2 * DAY, 30).unwrap();
+
+    env.ledger().set_timestamp(2 * DAY - 2 * HOUR);
+    client.cancel_appointment(&patient, &early);
+    client.cancel_appointment(&patient, &late);
+
+    let early_appt = client.get_appointment(&early);
+    assert_eq!(early_appt.status, AppointmentStatus::Cancelled);
+    assert!(!early_appt.late_cancellation);
+
+    let late_appt = client.get_appointment(&late);
+    assert_eq!(late_appt.status, AppointmentStatus::Cancelled);
+    assert!(late_appt.late_cancellation);
+
+    let history = client.get_appointment_history(&late);
+    let last = history.get(history.len() - 1).unwrap();
+    assert_eq!(last.action, String::from_str(&env, "LATE_CANCELLATION"));
+
+    let res = client.try_set_cancellation_window(&patient, &HOUR);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+}
+
+#[test]
+fn test_per_appointment_reminder_lead_time() {
+    let (env, client, _admin) = setup();
+    let surgeon = Address::generate(&env);
+    let optometrist = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let at = 10 * DAY;
+    let surgery = client.schedule_appointment(
+        &patient,
+        &patient,
+        &surgeon,
+        &AppointmentType::Surgery,
+        &at,
+        &120,
+        &None,
+        &Some(7 * DAY),
+    );
+    let routine = schedule(&client, &patient, &optometrist, at, 30).unwrap();
+
+    // Five days out only the surgery's week-long lead time has opened.
+    env.ledger().set_timestamp(at - 5 * DAY);
+    assert_eq!(client.send_appointment_reminders(&DAY), 1);
+    assert_eq!(client.send_appointment_reminders(&DAY), 0);
+    assert_eq!(
+        client.get_appointment(&surgery).reminders_sent,
+        Vec::from_array(&env, [7 * DAY])
+    );
+    assert!(client.get_appointment(&routine).reminders_sent.is_empty());
+
+    // The routine appointment falls back to the sweep's window.
+    env.ledger().set_timestamp(at - 20 * HOUR);
+    assert_eq!(client.send_appointment_reminders(&DAY), 1);
+    assert_eq!(
+        client.get_appointment(&routine).reminders_sent,
+        Vec::from_array(&env, [DAY])
+    );
+}
+
+#[test]
+fn test_long_lead_reminder_survives_many_later_bookings() {
+    let (env, client, _admin) = setup();
+    let surgeon = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    let at = 40 * DAY;
+    let surgery = client.schedule_appointment(
+        &patient,
+        &patient,
+        &surgeon,
+        &AppointmentType::Surgery,
+        &at,
+        &120,
+        &None,
+        &Some(30 * DAY),
+    );
+    // Bury the surgery well outside the most recent 100 appointment ids.
+    for _ in 0..101 {
+        let other = Address::generate(&env);
+        schedule(&client, &patient, &other, 2 * DAY, 30).unwrap();
+    }
+
+    // The keeper runs daily; the reminder fires the day its lead time opens.
+    env.ledger().set_timestamp(9 * DAY);
+    assert_eq!(client.send_appointment_reminders(&HOUR), 0);
+    env.ledger().set_timestamp(10 * DAY + HOUR);
+    assert_eq!(client.send_appointment_reminders(&HOUR), 1);
+    assert_eq!(client.send_appointment_reminders(&HOUR), 0);
+    assert_eq!(
+        client.get_appointment(&surgery).reminders_sent,
+        Vec::from_array(&env, [30 * DAY])
+    );
+}
+
+#[test]
+fn test_provider_stats_track_records_and_outcomes() {
+    let (env, client, admin) = setup();
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Stats"),
+    );
+    let patient = Address::generate(&env);
+
+    for _ in 0..2 {
+        client.add_record(
+            &provider,
+            &patient,
+            &provider,
+            &RecordType::Examination,
+            &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+        );
+    }
+
+    let seen = schedule(&client, &patient, &provider, DAY, 30).unwrap();
+    let missed = schedule(&client, &patient, &provider, 2 * DAY, 30).unwrap();
+    env.ledger().set_timestamp(3 * DAY);
+    client.complete_appointment(&provider, &seen);
+    client.mark_no_show(&provider, &missed);
+
+    let expected = ProviderStats {
+        records_created: 2,
+        appointments_completed: 1,
+        no_shows: 1,
+    };
+    assert_eq!(client.get_provider_stats(&provider, &provider), expected);
+    assert_eq!(client.get_provider_stats(&admin, &provider), expected);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_get_provider_stats(&stranger, &provider),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
@@ -6,10 +6,16 @@
 )]
 
 use super::{
-    AccessLevel, BatchGrantInput, BatchRecordInput, ContractError, RecordType, Role,
-    VisionRecordsContract, VisionRecordsContractClient,
+    audit, AccessLevel, AccessResult, BatchGrantInput, BatchRecordInput, ConsentType,
+    ContractError, RecordFetchResult, RecordType, Role, VisionRecordsContract,
+    VisionRecordsContractClient,
+};
+use crate::events::GrantDurationLimitsUpdatedEvent;
+use soroban_sdk::xdr::{ContractEventBody, ScVal};
+use soroban_sdk::{
+    testutils::Address as _, testutils::Events, testutils::Ledger as _, Address, Env, IntoVal,
+    String, TryFromVal, Val, Vec,
 };
-use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Address, Env, String, Vec};
 
 // ── Helpers ──────────────────────────────────────────────────────
 
@@ -347,15 +353,15 @@ fn test_batch_grant_access_expiration() {
     grants.push_back(BatchGrantInput {
         grantee: doc.clone(),
         level: AccessLevel::Read,
-        duration_seconds: 500, // expires at 1500
+        duration_seconds: 3600, // expires at 4600
     });
 
-    client.grant_consent(&patient, &doc, &super::ConsentType::Treatment, &500);
+    client.grant_consent(&patient, &doc, &super::ConsentType::Treatment, &3600);
     client.grant_access_batch(&patient, &grants);
     assert_eq!(client.check_access(&patient, &doc), AccessLevel::Read);
 
     // Advance time past expiration
-    env.ledger().set_timestamp(1501);
+    env.ledger().set_timestamp(4601);
     assert_eq!(client.check_access(&patient, &doc), AccessLevel::None);
 }
 
@@ -453,3 +459,469 @@ fn test_batch_add_and_retrieve_round_trip() {
     assert_eq!(records.get(0).unwrap().provider, provider);
     assert_eq!(records.get(1).unwrap().provider, provider);
 }
+
+// ======================== Batch User Registration ========================
+
+#[test]
+fn test_batch_register_users() {
+    let (env, client, admin) = setup();
+
+    let roles = [
+        Role::Patient,
+        Role::Patient,
+        Role::Staff,
+        Role::Optometrist,
+        Role::Ophthalmologist,
+    ];
+    let mut users = Vec::new(&env);
+    for role in roles.iter() {
+        users.push_back((
+            Address::generate(&env),
+            *role,
+            String::from_str(&env, "Clinic Member"),
+        ));
+    }
+
+    client.register_users(&admin, &users);
+
+    for (user, role, name) in users.iter() {
+        let stored = client.get_user(&user);
+        assert_eq!(stored.role, role);
+        assert_eq!(stored.name, name);
+        assert!(stored.is_active);
+    }
+}
+
+#[test]
+fn test_batch_register_users_empty_input() {
+    let (env, client, admin) = setup();
+
+    let users: Vec<(Address, Role, String)> = Vec::new(&env);
+    let result = client.try_register_users(&admin, &users);
+    assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_batch_register_users_unauthorized() {
+    let (env, client, admin) = setup();
+    let patient = register_patient(&env, &client, &admin, "Patient");
+
+    let mut users = Vec::new(&env);
+    users.push_back((
+        Address::generate(&env),
+        Role::Staff,
+        String::from_str(&env, "Staff"),
+    ));
+    let result = client.try_register_users(&patient, &users);
+    assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+}
+
+#[test]
+fn test_batch_register_users_is_atomic() {
+    let (env, client, admin) = setup();
+    let existing = register_patient(&env, &client, &admin, "Existing");
+
+    let fresh = Address::generate(&env);
+    let mut users = Vec::new(&env);
+    users.push_back((fresh.clone(), Role::Staff, String::from_str(&env, "Fresh")));
+    users.push_back((existing, Role::Staff, String::from_str(&env, "Again")));
+
+    let result = client.try_register_users(&admin, &users);
+    assert_eq!(result, Err(Ok(ContractError::UserAlreadyExists)));
+    assert!(client.try_get_user(&fresh).is_err());
+}
+
+// ======================== Batch Access Checks ========================
+
+#[test]
+fn test_check_access_batch_mixed_grants() {
+    let (env, client, _admin) = setup();
+    let patient = Address::generate(&env);
+    let active = Address::generate(&env);
+    let expired = Address::generate(&env);
+    let absent = Address::generate(&env);
+
+    for grantee in [&active, &expired] {
+        client.grant_consent(&patient, grantee, &ConsentType::Treatment, &86400);
+    }
+    client.grant_access(
+        &patient,
+        &patient,
+        &active,
+        &AccessLevel::Write,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    client.grant_access(
+        &patient,
+        &patient,
+        &expired,
+        &AccessLevel::Read,
+        &3600,
+        &ConsentType::Treatment,
+    );
+
+    env.ledger().set_timestamp(3600);
+
+    let mut grantees = Vec::new(&env);
+    grantees.push_back(expired.clone());
+    grantees.push_back(active.clone());
+    grantees.push_back(absent.clone());
+
+    let levels = client.check_access_batch(&patient, &grantees);
+    assert_eq!(levels.len(), 3);
+    assert_eq!(levels.get(0).unwrap(), AccessLevel::None);
+    assert_eq!(levels.get(1).unwrap(), AccessLevel::Write);
+    assert_eq!(levels.get(2).unwrap(), AccessLevel::None);
+}
+
+#[test]
+fn test_get_records_checked_reports_each_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Smith"),
+    );
+
+    let patient = Address::generate(&env);
+    let other_patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let own = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let foreign = client.add_record(
+        &provider,
+        &other_patient,
+        &provider,
+        &RecordType::Examination,
+        &data_hash,
+    );
+
+    let results = client.get_records_checked(&patient, &Vec::from_array(&env, [own, 999, foreign]));
+    assert_eq!(results.len(), 3);
+    match results.get(0).unwrap() {
+        RecordFetchResult::Found(record) => assert_eq!(record.id, own),
+        other => panic!("expected record, got {:?}", other),
+    }
+    assert!(matches!(
+        results.get(1).unwrap(),
+        RecordFetchResult::NotFound(999)
+    ));
+    assert!(matches!(
+        results.get(2).unwrap(),
+        RecordFetchResult::AccessDenied(id) if id == foreign
+    ));
+
+    // Denied and missing attempts are audited along with the successful read.
+    let denied = env.as_contract(&contract_id, || audit::get_record_audit_log(&env, foreign));
+    assert!(denied
+        .iter()
+        .any(|e| e.actor == patient && e.result == AccessResult::Denied));
+    let missing = env.as_contract(&contract_id, || audit::get_record_audit_log(&env, 999));
+    assert_eq!(missing.get(0).unwrap().result, AccessResult::NotFound);
+}
+
+#[test]
+fn test_denied_reads_record_denial_reason() {
+    let (env, client, admin) = setup();
+    let contract_id = client.address.clone();
+    let provider = register_provider(&env, &client, &admin);
+    let patient = register_patient(&env, &client, &admin, "Alice");
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let former = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.grant_access(
+        &patient,
+        &patient,
+        &former,
+        &AccessLevel::Read,
+        &3600,
+        &ConsentType::Treatment,
+    );
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3601);
+
+    let ids = Vec::from_array(&env, [record_id]);
+    client.get_records_checked(&former, &ids);
+    client.get_records_checked(&stranger, &ids);
+
+    let log = env.as_contract(&contract_id, || {
+        audit::get_record_audit_log(&env, record_id)
+    });
+    let reason_for = |actor: &Address| {
+        log.iter()
+            .find(|e| &e.actor == actor)
+            .and_then(|e| e.reason)
+            .unwrap()
+    };
+    assert_eq!(reason_for(&former), String::from_str(&env, "grant expired"));
+    assert_eq!(reason_for(&stranger), String::from_str(&env, "no grant"));
+
+    // A denied single read fails the call, which rolls its audit entry back.
+    let other = Address::generate(&env);
+    assert!(client.try_get_record(&other, &record_id).is_err());
+    let log = env.as_contract(&contract_id, || {
+        audit::get_record_audit_log(&env, record_id)
+    });
+    assert!(!log.iter().any(|e| e.actor == other));
+}
+
+#[test]
+fn test_grant_duration_limits_enforced_at_boundaries() {
+    let (env, client, admin) = setup();
+    let patient = register_patient(&env, &client, &admin, "Alice");
+    let doc = register_provider(&env, &client, &admin);
+
+    // Defaults: one hour to five years.
+    assert_eq!(client.get_grant_duration_limits(), (3600, 157_680_000));
+    let batch = |duration_seconds: u64| {
+        let mut grants = Vec::new(&env);
+        grants.push_back(BatchGrantInput {
+            grantee: doc.clone(),
+            level: AccessLevel::Read,
+            duration_seconds,
+        });
+        grants
+    };
+    assert_eq!(
+        client.try_grant_access_batch(&patient, &batch(3599)),
+        Err(Ok(ContractError::InvalidInput))
+    );
+    client.grant_access_batch(&patient, &batch(3600));
+
+    client.set_grant_duration_limits(&admin, &7200, &86400);
+    let event: Val = GrantDurationLimitsUpdatedEvent {
+        min_seconds: 7200,
+        max_seconds: 86400,
+        updated_by: admin.clone(),
+        timestamp: env.ledger().timestamp(),
+    }
+    .into_val(&env);
+    let expected = ScVal::try_from_val(&env, &event).unwrap();
+    assert!(env.events().all().events().iter().any(|event| {
+        let ContractEventBody::V0(body) = &event.body;
+        body.data == expected
+    }));
+
+    for (duration, ok) in [(7199, false), (7200, true), (86400, true), (86401, false)] {
+        let res = client.try_grant_access(
+            &patient,
+            &patient,
+            &doc,
+            &AccessLevel::Read,
+            &duration,
+            &ConsentType::Treatment,
+        );
+        assert_eq!(res.is_ok(), ok, "duration {}", duration);
+        assert_eq!(
+            client
+                .try_grant_access_batch(&patient, &batch(duration))
+                .is_ok(),
+            ok,
+            "batch duration {}",
+            duration
+        );
+    }
+
+    // The per-record and two-phase paths honour the same bounds.
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(&admin, &patient, &doc, &RecordType::Examination, &data_hash);
+    assert_eq!(
+        client.try_grant_record_access(&patient, &doc, &record_id, &AccessLevel::Read, &3600),
+        Err(Ok(ContractError::InvalidInput))
+    );
+    client.grant_record_access(&patient, &doc, &record_id, &AccessLevel::Read, &7200);
+    let now = env.ledger().timestamp();
+    assert_eq!(
+        client.try_prepare_grant_access(
+            &patient,
+            &patient,
+            &doc,
+            &AccessLevel::Read,
+            &Some(now + 86401)
+        ),
+        Err(Ok(ContractError::InvalidInput))
+    );
+
+    assert_eq!(
+        client.try_set_grant_duration_limits(&admin, &86400, &7200),
+        Err(Ok(ContractError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_grant_duration_limits(&doc, &7200, &86400),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_grant_access_default_uses_patient_duration() {
+    let (env, client, admin) = setup();
+    let patient = register_patient(&env, &client, &admin, "Alice");
+    let doc = register_provider(&env, &client, &admin);
+    let optician = register_provider(&env, &client, &admin);
+    env.ledger().set_timestamp(1_000);
+
+    // Without a stored default the system default applies.
+    assert_eq!(client.get_default_grant_duration(&patient), 2_592_000);
+    client.grant_access_default(&patient, &patient, &doc, &AccessLevel::Read);
+    let grant = client.get_access_grant(&patient, &doc).unwrap();
+    assert_eq!(grant.expires_at, 1_000 + 2_592_000);
+    assert_eq!(
+        client.get_access_grant_purpose(&patient, &doc),
+        ConsentType::Treatment
+    );
+
+    client.set_default_grant_duration(&patient, &(7 * 86400));
+    assert_eq!(client.get_default_grant_duration(&patient), 7 * 86400);
+    client.grant_access_default(&patient, &patient, &optician, &AccessLevel::Read);
+    let grant = client.get_access_grant(&patient, &optician).unwrap();
+    assert_eq!(grant.expires_at, 1_000 + 7 * 86400);
+
+    // Defaults must respect the grant duration limits.
+    assert_eq!(
+        client.try_set_default_grant_duration(&patient, &60),
+        Err(Ok(ContractError::InvalidInput))
+    );
+
+    client.set_default_grant_duration(&patient, &0);
+    assert_eq!(client.get_default_grant_duration(&patient), 2_592_000);
+}
+
+#[test]
+fn test_patient_record_type_counts() {
+    let (env, client, admin) = setup();
+    let provider = register_provider(&env, &client, &admin);
+    let patient = register_patient(&env, &client, &admin, "Alice");
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+
+    assert!(client
+        .get_patient_record_type_counts(&patient, &patient)
+        .is_empty());
+
+    for record_type in [
+        RecordType::Examination,
+        RecordType::Prescription,
+        RecordType::Examination,
+    ] {
+        client.add_record(&provider, &patient, &provider, &record_type, &hash);
+    }
+    let mut inputs = Vec::new(&env);
+    for record_type in [RecordType::Examination, RecordType::Prescription] {
+        inputs.push_back(BatchRecordInput {
+            patient: patient.clone(),
+            record_type,
+            data_hash: hash.clone(),
+        });
+    }
+    client.add_records(&provider, &inputs);
+
+    let counts = client.get_patient_record_type_counts(&provider, &patient);
+    assert_eq!(
+        counts,
+        Vec::from_array(
+            &env,
+            [(RecordType::Examination, 3), (RecordType::Prescription, 2)]
+        )
+    );
+    assert_eq!(
+        client.get_patient_record_type_counts(&patient, &patient),
+        counts
+    );
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_get_patient_record_type_counts(&stranger, &patient),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_record_type_counts_cover_two_phase_and_rebuild() {
+    let (env, client, admin) = setup();
+    let provider = register_provider(&env, &client, &admin);
+    let patient = register_patient(&env, &client, &admin, "Alice");
+    let hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+
+    client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &hash,
+    );
+    let id = client.prepare_add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Prescription,
+        &hash,
+    );
+    client.commit_add_record(&id);
+    let expected = Vec::from_array(
+        &env,
+        [(RecordType::Examination, 1), (RecordType::Prescription, 1)],
+    );
+    assert_eq!(
+        client.get_patient_record_type_counts(&patient, &patient),
+        expected
+    );
+
+    // Records from before counting existed have no counts until rebuilt.
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .remove(&(soroban_sdk::symbol_short!("REC_TCNT"), patient.clone()));
+    });
+    assert!(client
+        .get_patient_record_type_counts(&patient, &patient)
+        .is_empty());
+
+    assert_eq!(
+        client.try_rebuild_record_type_counts(&provider, &patient, &0, &10),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.rebuild_record_type_counts(&admin, &patient, &0, &1),
+        1
+    );
+    assert_eq!(
+        client.rebuild_record_type_counts(&admin, &patient, &1, &1),
+        1
+    );
+    assert_eq!(
+        client.rebuild_record_type_counts(&admin, &patient, &2, &1),
+        0
+    );
+    assert_eq!(
+        client.get_patient_record_type_counts(&patient, &patient),
+        expected
+    );
+
+    // Starting over from offset 0 does not double count.
+    client.rebuild_record_type_counts(&admin, &patient, &0, &10);
+    assert_eq!(
+        client.get_patient_record_type_counts(&patient, &patient),
+        expected
+    );
+}
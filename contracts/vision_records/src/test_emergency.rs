@@ -0,0 +1,939 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::arithmetic_side_effects
+)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Env;
+
+fn setup() -> (Env, VisionRecordsContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    (env, client, admin)
+}
+
+fn verified_provider(env: &Env, client: &VisionRecordsContractClient, admin: &Address) -> Address {
+    let provider = Address::generate(env);
+    client.register_user(
+        admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(env, "Dr. ER"),
+    );
+    client.register_provider(
+        admin,
+        &provider,
+        &String::from_str(env, "Dr. ER"),
+        &Vec::new(env),
+        &Vec::new(env),
+        &Vec::new(env),
+        &Vec::new(env),
+    );
+    client.verify_provider(admin, &provider, &VerificationStatus::Verified);
+    provider
+}
+
+#[test]
+fn test_grant_and_revoke_emergency_access() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+    let attestation = String::from_str(&env, "Patient found unconscious");
+
+    let access_id = client.grant_emergency_access(
+        &provider,
+        &patient,
+        &EmergencyCondition::Unconscious,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+    assert!(client.check_emergency_access(&patient, &provider).is_some());
+    client.access_record_via_emergency(&provider, &patient, &None);
+
+    client.revoke_emergency_access(&patient, &access_id);
+    assert!(client.check_emergency_access(&patient, &provider).is_none());
+    let res = client.try_access_record_via_emergency(&provider, &patient, &None);
+    assert_eq!(res, Err(Ok(ContractError::AccessDenied)));
+
+    let trail = client.get_emergency_audit_trail(&patient, &access_id);
+    assert_eq!(trail.len(), 3);
+    assert_eq!(
+        trail.get(2).unwrap().action,
+        String::from_str(&env, "REVOKED")
+    );
+}
+
+#[test]
+fn test_grant_emergency_access_validation() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+    let attestation = String::from_str(&env, "Emergency department admission");
+
+    let unverified = Address::generate(&env);
+    let res = client.try_grant_emergency_access(
+        &unverified,
+        &patient,
+        &EmergencyCondition::LifeThreatening,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    let res = client.try_grant_emergency_access(
+        &provider,
+        &patient,
+        &EmergencyCondition::LifeThreatening,
+        &String::from_str(&env, ""),
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidAttestation)));
+
+    let long = "x".repeat(crate::validation::MAX_ATTESTATION_LEN as usize + 1);
+    for weak in [
+        "Emergency",
+        "                         ",
+        "E                   ",
+        "   Too short, padded        ",
+        long.as_str(),
+    ] {
+        let res = client.try_grant_emergency_access(
+            &provider,
+            &patient,
+            &EmergencyCondition::LifeThreatening,
+            &String::from_str(&env, weak),
+            &3600,
+            &Vec::new(&env),
+            &false,
+        );
+        assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+    }
+    assert!(client
+        .try_grant_emergency_access(
+            &provider,
+            &patient,
+            &EmergencyCondition::LifeThreatening,
+            &attestation,
+            &3600,
+            &Vec::new(&env),
+            &false,
+        )
+        .is_ok());
+
+    let res = client.try_grant_emergency_access(
+        &provider,
+        &patient,
+        &EmergencyCondition::LifeThreatening,
+        &attestation,
+        &86401,
+        &Vec::new(&env),
+        &false,
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_mass_casualty_access_across_patients() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let attestation = String::from_str(&env, "Multi-vehicle collision");
+
+    let mut patients = Vec::new(&env);
+    for _ in 0..5 {
+        let patient = Address::generate(&env);
+        client.register_user(
+            &admin,
+            &patient,
+            &Role::Patient,
+            &String::from_str(&env, "Patient"),
+        );
+        patients.push_back(patient);
+    }
+
+    let ids = client.grant_mass_casualty_access(
+        &provider,
+        &patients,
+        &attestation,
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(ids.len(), 5);
+
+    for (i, patient) in patients.iter().enumerate() {
+        let access = client.check_emergency_access(&patient, &provider).unwrap();
+        assert_eq!(access.id, ids.get(i as u32).unwrap());
+        assert_eq!(access.condition, EmergencyCondition::Masscasualties);
+        assert_eq!(access.expires_at, env.ledger().timestamp() + 7200);
+    }
+
+    let res = client.try_grant_mass_casualty_access(
+        &provider,
+        &Vec::new(&env),
+        &attestation,
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+
+    let unverified = Address::generate(&env);
+    let res = client.try_grant_mass_casualty_access(
+        &unverified,
+        &patients,
+        &attestation,
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    // Unregistered patients are rejected and duplicates get one grant.
+    let mut listed = Vec::new(&env);
+    listed.push_back(patients.get(0).unwrap());
+    listed.push_back(Address::generate(&env));
+    let res = client.try_grant_mass_casualty_access(
+        &provider,
+        &listed,
+        &attestation,
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::UserNotFound)));
+
+    let mut repeated = Vec::new(&env);
+    repeated.push_back(patients.get(0).unwrap());
+    repeated.push_back(patients.get(0).unwrap());
+    let ids = client.grant_mass_casualty_access(
+        &provider,
+        &repeated,
+        &attestation,
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(ids.len(), 1);
+
+    // The patient list is bounded.
+    let mut too_many = Vec::new(&env);
+    for _ in 0..=emergency::MAX_MASS_CASUALTY_PATIENTS {
+        too_many.push_back(patients.get(0).unwrap());
+    }
+    let res = client.try_grant_mass_casualty_access(
+        &provider,
+        &too_many,
+        &attestation,
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+
+    // Grants lapse together.
+    env.ledger().set_timestamp(env.ledger().timestamp() + 7200);
+    assert!(client
+        .check_emergency_access(&patients.get(0).unwrap(), &provider)
+        .is_none());
+}
+
+#[test]
+fn test_mass_casualty_access_at_patient_limit() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+
+    let mut patients = Vec::new(&env);
+    for _ in 0..emergency::MAX_MASS_CASUALTY_PATIENTS {
+        let patient = Address::generate(&env);
+        client.register_user(
+            &admin,
+            &patient,
+            &Role::Patient,
+            &String::from_str(&env, "Patient"),
+        );
+        patients.push_back(patient);
+    }
+
+    let ids = client.grant_mass_casualty_access(
+        &provider,
+        &patients,
+        &String::from_str(&env, "Stadium crowd crush at the north gate"),
+        &7200,
+        &Vec::new(&env),
+    );
+    assert_eq!(ids.len(), emergency::MAX_MASS_CASUALTY_PATIENTS);
+}
+
+#[test]
+fn test_consent_override_is_logged_and_honored() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+    let attestation = String::from_str(&env, "Acute angle-closure glaucoma");
+
+    // An ABAC policy that requires patient consent for emergency reads.
+    client.create_access_policy(
+        &admin,
+        &String::from_str(&env, "emergency_access"),
+        &String::from_str(&env, "Consent-gated emergency"),
+        &Role::None,
+        &TimeRestriction::None,
+        &CredentialType::None,
+        &SensitivityLevel::Public,
+        &true,
+    );
+
+    // Without the override the consent requirement blocks the read.
+    client.grant_emergency_access(
+        &provider,
+        &patient,
+        &EmergencyCondition::LifeThreatening,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+    let res = client.try_access_record_via_emergency(&provider, &patient, &None);
+    assert_eq!(res, Err(Ok(ContractError::AccessDenied)));
+
+    // Overrides are only allowed for life-threatening conditions.
+    let other = Address::generate(&env);
+    let res = client.try_grant_emergency_access(
+        &provider,
+        &other,
+        &EmergencyCondition::Unconscious,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &true,
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidEmergencyCondition)));
+
+    let critical = Address::generate(&env);
+    let access_id = client.grant_emergency_access(
+        &provider,
+        &critical,
+        &EmergencyCondition::LifeThreatening,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &true,
+    );
+    assert!(client.get_emergency_access(&access_id).override_consent);
+    client.access_record_via_emergency(&provider, &critical, &None);
+
+    let trail = client.get_emergency_audit_trail(&critical, &access_id);
+    let override_action = String::from_str(&env, "CONSENT_OVERRIDE");
+    assert!(trail.iter().any(|e| e.action == override_action));
+
+    let log = env.as_contract(&client.address, || {
+        audit::get_patient_audit_log(&env, &critical)
+    });
+    let entry = log.get(log.len() - 1).unwrap();
+    assert_eq!(entry.actor, provider);
+    assert_eq!(entry.patient, critical);
+    assert_eq!(entry.action, AccessAction::EmergencyAccess);
+    assert_eq!(entry.reason, Some(override_action));
+}
+
+#[test]
+fn test_emergency_read_appears_in_central_audit_log() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let access_id = client.grant_emergency_access(
+        &provider,
+        &patient,
+        &EmergencyCondition::SurgicalEmergency,
+        &String::from_str(&env, "Pre-operative review"),
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+    let ids = client.access_record_via_emergency(&provider, &patient, &Some(record_id));
+    assert_eq!(ids.len(), 1);
+
+    let (record_log, user_log) = env.as_contract(&client.address, || {
+        (
+            audit::get_record_audit_log(&env, record_id),
+            audit::get_user_audit_log(&env, &provider),
+        )
+    });
+    let entry = record_log.get(record_log.len() - 1).unwrap();
+    assert_eq!(entry.actor, provider);
+    assert_eq!(entry.action, AccessAction::EmergencyAccess);
+    assert_eq!(entry.result, AccessResult::Success);
+    assert!(user_log
+        .iter()
+        .any(|e| e.action == AccessAction::EmergencyAccess && e.record_id == Some(record_id)));
+
+    // The emergency-specific trail is still written.
+    let trail = client.get_emergency_audit_trail(&patient, &access_id);
+    assert_eq!(
+        trail.get(trail.len() - 1).unwrap().action,
+        String::from_str(&env, "ACCESSED")
+    );
+}
+
+#[test]
+fn test_condition_specific_max_duration() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let attestation = String::from_str(&env, "Found unresponsive at scene");
+
+    assert_eq!(
+        client.get_emergency_max_duration(&EmergencyCondition::Unconscious),
+        emergency::DEFAULT_UNCONSCIOUS_DURATION
+    );
+    assert_eq!(
+        client.get_emergency_max_duration(&EmergencyCondition::SurgicalEmergency),
+        emergency::MAX_EMERGENCY_DURATION
+    );
+
+    client.set_emergency_max_duration(&admin, &EmergencyCondition::Unconscious, &21600);
+
+    let res = client.try_grant_emergency_access(
+        &provider,
+        &Address::generate(&env),
+        &EmergencyCondition::Unconscious,
+        &attestation,
+        &43200,
+        &Vec::new(&env),
+        &false,
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+
+    // Within the cap, and other conditions keep their own limits.
+    client.grant_emergency_access(
+        &provider,
+        &Address::generate(&env),
+        &EmergencyCondition::Unconscious,
+        &attestation,
+        &21600,
+        &Vec::new(&env),
+        &false,
+    );
+    client.grant_emergency_access(
+        &provider,
+        &Address::generate(&env),
+        &EmergencyCondition::SurgicalEmergency,
+        &attestation,
+        &43200,
+        &Vec::new(&env),
+        &false,
+    );
+
+    // Caps above the global ceiling are rejected.
+    assert_eq!(
+        client.try_set_emergency_max_duration(
+            &admin,
+            &EmergencyCondition::SurgicalEmergency,
+            &(emergency::MAX_EMERGENCY_DURATION + 1)
+        ),
+        Err(Ok(ContractError::InvalidInput))
+    );
+}
+
+#[test]
+fn test_patient_emergency_audit_merges_accesses_in_order() {
+    let (env, client, admin) = setup();
+    let first_provider = verified_provider(&env, &client, &admin);
+    let second_provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+    let attestation = String::from_str(&env, "Emergency department admission");
+
+    env.ledger().set_timestamp(100);
+    let first = client.grant_emergency_access(
+        &first_provider,
+        &patient,
+        &EmergencyCondition::LifeThreatening,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+
+    env.ledger().set_timestamp(200);
+    let second = client.grant_emergency_access(
+        &second_provider,
+        &patient,
+        &EmergencyCondition::SurgicalEmergency,
+        &attestation,
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+
+    env.ledger().set_timestamp(300);
+    client.access_record_via_emergency(&first_provider, &patient, &None);
+
+    env.ledger().set_timestamp(400);
+    client.revoke_emergency_access(&patient, &second);
+
+    let merged = client.get_patient_emergency_audit(&patient, &patient);
+    let first_trail = client.get_emergency_audit_trail(&patient, &first);
+    let second_trail = client.get_emergency_audit_trail(&patient, &second);
+    assert_eq!(merged.len(), first_trail.len() + second_trail.len());
+
+    let mut last = 0;
+    for entry in merged.iter() {
+        assert!(entry.timestamp >= last);
+        last = entry.timestamp;
+    }
+    assert_eq!(merged.get(0).unwrap().access_id, first);
+    let end = merged.get(merged.len() - 1).unwrap();
+    assert_eq!(end.access_id, second);
+    assert_eq!(end.action, String::from_str(&env, "REVOKED"));
+    assert!(merged
+        .iter()
+        .any(|e| e.access_id == first && e.timestamp == 300));
+
+    // Admins may export it too; unrelated callers may not.
+    assert_eq!(
+        client.get_patient_emergency_audit(&admin, &patient).len(),
+        merged.len()
+    );
+    assert_eq!(
+        client.try_get_patient_emergency_audit(&first_provider, &patient),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    // The per-grant trail and active-grant list are gated the same way.
+    assert_eq!(
+        client.try_get_emergency_audit_trail(&second_provider, &first),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_get_patient_emergency_accesses(&first_provider, &patient),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.get_emergency_audit_trail(&admin, &first).len(),
+        first_trail.len()
+    );
+}
+
+#[test]
+fn test_patient_emergency_history_includes_inactive_grants() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+    let attestation = String::from_str(&env, "Emergency department admission");
+
+    let grant = |duration: u64| {
+        client.grant_emergency_access(
+            &provider,
+            &patient,
+            &EmergencyCondition::LifeThreatening,
+            &attestation,
+            &duration,
+            &Vec::new(&env),
+            &false,
+        )
+    };
+
+    env.ledger().set_timestamp(100);
+    let expired = grant(600);
+    env.ledger().set_timestamp(200);
+    let revoked = grant(3600);
+    client.revoke_emergency_access(&patient, &revoked);
+    env.ledger().set_timestamp(1_000);
+    let active = grant(3600);
+
+    let history = client.get_patient_emergency_history(&patient, &patient);
+    assert_eq!(history.len(), 3);
+    let expected = [
+        (expired, emergency::EmergencyStatus::Expired),
+        (revoked, emergency::EmergencyStatus::Revoked),
+        (active, emergency::EmergencyStatus::Active),
+    ];
+    for (i, (id, status)) in expected.into_iter().enumerate() {
+        let access = history.get(i as u32).unwrap();
+        assert_eq!(access.id, id);
+        assert_eq!(access.status, status);
+    }
+
+    assert_eq!(
+        client.try_get_patient_emergency_history(&provider, &patient),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_emergency_trail_records_each_record_viewed() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let first = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let second = client.add_record(&admin, &patient, &admin, &RecordType::Surgery, &data_hash);
+    let untouched = client.add_record(&admin, &patient, &admin, &RecordType::LabResult, &data_hash);
+
+    let access_id = client.grant_emergency_access(
+        &provider,
+        &patient,
+        &EmergencyCondition::Unconscious,
+        &String::from_str(&env, "Patient found unconscious"),
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+    client.access_record_via_emergency(&provider, &patient, &Some(first));
+    client.access_record_via_emergency(&provider, &patient, &Some(second));
+    client.access_record_via_emergency(&provider, &patient, &Some(first));
+
+    let accessed = client.get_emergency_records_accessed(&patient, &access_id);
+    assert_eq!(accessed, Vec::from_array(&env, [first, second]));
+    assert!(!accessed.contains(untouched));
+
+    let trail = client.get_emergency_audit_trail(&patient, &access_id);
+    let last = trail.get(trail.len() - 1).unwrap();
+    assert_eq!(last.action, String::from_str(&env, "ACCESSED"));
+    assert_eq!(last.record_id, Some(first));
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_get_emergency_records_accessed(&stranger, &access_id),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_record_accessors_include_grantees_and_responders() {
+    let (env, client, admin) = setup();
+    let responder = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &admin,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let family_doctor = Address::generate(&env);
+    client.grant_consent(&patient, &family_doctor, &ConsentType::Treatment, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &family_doctor,
+        &AccessLevel::Write,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    client.grant_emergency_access(
+        &responder,
+        &patient,
+        &EmergencyCondition::Unconscious,
+        &String::from_str(&env, "Patient found unconscious"),
+        &3600,
+        &Vec::new(&env),
+        &false,
+    );
+
+    let accessors = client.get_record_accessors(&patient, &record_id);
+    assert_eq!(
+        accessors.users.get(0).unwrap(),
+        (patient.clone(), AccessLevel::Full)
+    );
+    assert!(accessors
+        .users
+        .contains((family_doctor, AccessLevel::Write)));
+    assert!(accessors.users.contains((responder, AccessLevel::Read)));
+    assert!(accessors.roles.contains(Role::Optometrist));
+    assert!(!accessors.roles.contains(Role::Staff));
+
+    // Role holders are paged rather than listed, and custom grants count.
+    let clerk = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &clerk,
+        &Role::Staff,
+        &String::from_str(&env, "Clerk"),
+    );
+    assert!(client
+        .get_record_role_accessors(&patient, &record_id, &Role::Staff, &0)
+        .is_empty());
+    client.grant_custom_permission(&admin, &clerk, &Permission::ReadAnyRecord);
+    assert!(client
+        .get_record_role_accessors(&patient, &record_id, &Role::Staff, &0)
+        .contains(clerk));
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_get_record_accessors(&stranger, &record_id),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_get_record_role_accessors(&stranger, &record_id, &Role::Staff, &0),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_break_glass_read_is_queued_for_review() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let author = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    let record_id = client.add_record(
+        &admin,
+        &patient,
+        &author,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    let justification = String::from_str(&env, "Trauma bay, patient unresponsive");
+
+    let (event_id, record) = client.break_glass_access(&provider, &record_id, &justification);
+    assert_eq!(record.id, record_id);
+    assert_eq!(record.data_hash, data_hash);
+
+    let pending = client.get_pending_break_glass(&admin);
+    assert_eq!(pending.len(), 1);
+    let event = pending.get(0).unwrap();
+    assert_eq!(event.id, event_id);
+    assert_eq!(event.provider, provider);
+    assert_eq!(event.patient, patient);
+    assert_eq!(event.justification, justification);
+    assert!(event.approved.is_none());
+
+    // Unverified callers and blank justifications are refused.
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client
+            .try_break_glass_access(&stranger, &record_id, &justification)
+            .map(|_| ()),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client
+            .try_break_glass_access(&provider, &record_id, &String::from_str(&env, ""))
+            .map(|_| ()),
+        Err(Ok(ContractError::InvalidAttestation))
+    );
+    assert_eq!(
+        client
+            .try_break_glass_access(&provider, &record_id, &String::from_str(&env, "   "))
+            .map(|_| ()),
+        Err(Ok(ContractError::InvalidInput))
+    );
+
+    // The justification is visible to the provider, patient and admins only.
+    assert_eq!(
+        client
+            .get_break_glass_event(&patient, &event_id)
+            .justification,
+        justification
+    );
+    assert_eq!(
+        client.try_get_break_glass_event(&stranger, &event_id),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    // Restricted records still need a professional credential.
+    client.set_record_sensitivity(&admin, &record_id, &SensitivityLevel::Restricted);
+    assert_eq!(
+        client
+            .try_break_glass_access(&provider, &record_id, &justification)
+            .map(|_| ()),
+        Err(Ok(ContractError::AccessDenied))
+    );
+    client.set_user_credential(&admin, &provider, &CredentialType::MedicalLicense);
+    let (second_id, _) = client.break_glass_access(&provider, &record_id, &justification);
+    client.review_break_glass(&admin, &second_id, &true);
+
+    assert_eq!(
+        client.try_review_break_glass(&provider, &event_id, &true),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    client.review_break_glass(&admin, &event_id, &false);
+    assert!(client.get_pending_break_glass(&admin).is_empty());
+    let reviewed = client.get_break_glass_event(&provider, &event_id);
+    assert_eq!(reviewed.approved, Some(false));
+    assert_eq!(reviewed.reviewed_by, Some(admin.clone()));
+    assert_eq!(
+        client.try_review_break_glass(&admin, &event_id, &true),
+        Err(Ok(ContractError::InvalidInput))
+    );
+}
+
+#[test]
+fn test_inactive_emergency_grants_leave_patient_index() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let patient = Address::generate(&env);
+    let attestation = String::from_str(&env, "Emergency department admission");
+
+    let grant = |duration: u64| {
+        client.grant_emergency_access(
+            &provider,
+            &patient,
+            &EmergencyCondition::LifeThreatening,
+            &attestation,
+            &duration,
+            &Vec::new(&env),
+            &false,
+        )
+    };
+    let active_ids = || {
+        env.as_contract(&client.address, || {
+            emergency::get_active_emergency_ids(&env, &patient)
+        })
+    };
+
+    let revoked = grant(3600);
+    let lapsing = grant(600);
+    assert_eq!(active_ids(), Vec::from_array(&env, [revoked, lapsing]));
+
+    client.revoke_emergency_access(&patient, &revoked);
+    assert_eq!(active_ids(), Vec::from_array(&env, [lapsing]));
+    let current = client.get_patient_emergency_accesses(&patient, &patient);
+    assert_eq!(current.len(), 1);
+    assert_eq!(current.get(0).unwrap().id, lapsing);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 600);
+    assert_eq!(client.expire_emergency_accesses(&None), 1);
+    assert!(active_ids().is_empty());
+    assert!(client
+        .get_patient_emergency_accesses(&patient, &patient)
+        .is_empty());
+
+    // History still covers both grants.
+    assert_eq!(
+        client
+            .get_patient_emergency_history(&patient, &patient)
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_rebuild_emergency_index_picks_up_unindexed_grants() {
+    let (env, client, admin) = setup();
+    let responder = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    // A grant stored before the patient indexes existed.
+    env.as_contract(&client.address, || {
+        let access = EmergencyAccess {
+            id: 1,
+            patient: patient.clone(),
+            requester: responder.clone(),
+            condition: EmergencyCondition::LifeThreatening,
+            attestation: String::from_str(&env, "Emergency department admission"),
+            granted_at: env.ledger().timestamp(),
+            expires_at: env.ledger().timestamp() + 3600,
+            status: emergency::EmergencyStatus::Active,
+            notified_contacts: Vec::new(&env),
+            override_consent: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("EMRG_ACC"), 1u64), &access);
+        env.storage().instance().set(&emergency::EMRG_CTR, &1u64);
+    });
+    let indexed = || {
+        env.as_contract(&client.address, || {
+            emergency::has_active_emergency_access(&env, &patient, &responder).is_some()
+        })
+    };
+    assert!(!indexed());
+
+    assert_eq!(
+        client.try_rebuild_emergency_index(&responder, &1, &10),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(client.rebuild_emergency_index(&admin, &1, &10), 1);
+    assert!(indexed());
+    assert_eq!(
+        client
+            .get_patient_emergency_accesses(&patient, &patient)
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_expiry_sweep_reaches_old_grants_in_pages() {
+    let (env, client, admin) = setup();
+    let provider = verified_provider(&env, &client, &admin);
+    let attestation = String::from_str(&env, "Emergency department admission");
+
+    // 120 grants: the first 60 lapse after an hour, the rest run for a day.
+    let mut short_lived = Vec::new(&env);
+    for i in 0..120u32 {
+        let patient = Address::generate(&env);
+        let duration = if i < 60 { 3600 } else { 86400 };
+        let id = client.grant_emergency_access(
+            &provider,
+            &patient,
+            &EmergencyCondition::LifeThreatening,
+            &attestation,
+            &duration,
+            &Vec::new(&env),
+            &false,
+        );
+        if i < 60 {
+            short_lived.push_back(id);
+        }
+    }
+    let active_count =
+        || env.as_contract(&client.address, || emergency::get_active_grant_count(&env));
+    assert_eq!(active_count(), 120);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+
+    // Each bounded call picks up where the previous one left off, starting
+    // from the oldest grants.
+    assert_eq!(client.expire_emergency_accesses(&Some(20)), 20);
+    assert_eq!(active_count(), 100);
+    assert_eq!(client.expire_emergency_accesses(&Some(20)), 20);
+    assert_eq!(client.expire_emergency_accesses(&Some(20)), 20);
+    assert_eq!(active_count(), 60);
+    assert_eq!(client.expire_emergency_accesses(&None), 0);
+    assert_eq!(active_count(), 60);
+
+    for id in short_lived.iter() {
+        let access = client.get_emergency_access(&id);
+        assert_eq!(access.status, emergency::EmergencyStatus::Expired);
+    }
+    // A day later the cursor walks the empty hours in bounded steps before
+    // reaching the long-lived grants.
+    env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+    let mut expired = 0;
+    for _ in 0..10 {
+        let swept = client.expire_emergency_accesses(&None);
+        assert!(swept <= emergency::MAX_EXPIRY_BATCH);
+        expired += swept;
+    }
+    assert_eq!(expired, 60);
+    assert_eq!(active_count(), 0);
+}
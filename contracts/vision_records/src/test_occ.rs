@@ -96,6 +96,7 @@ fn test_clean_versioned_examination_update() {
         &provider,
         &AccessLevel::Full,
         &157_680_000u64,
+        &ConsentType::Treatment,
     );
 
     let stamp = client.get_record_version_stamp(&record_id);
@@ -175,6 +176,7 @@ fn test_stale_version_triggers_conflict_manual_review() {
         &provider,
         &AccessLevel::Full,
         &157_680_000u64,
+        &ConsentType::Treatment,
     );
 
     // Set strategy to ManualReview.
@@ -280,6 +282,7 @@ fn test_last_writer_wins_strategy() {
         &provider,
         &AccessLevel::Full,
         &157_680_000u64,
+        &ConsentType::Treatment,
     );
 
     // Set LWW strategy.
@@ -368,6 +371,7 @@ fn test_resolve_conflict() {
         &provider,
         &AccessLevel::Full,
         &157_680_000u64,
+        &ConsentType::Treatment,
     );
 
     client.set_record_resolution_strategy(&provider, &record_id, &ResolutionStrategy::ManualReview);
@@ -461,6 +465,7 @@ fn test_merge_strategy_non_overlapping_fields() {
         &provider,
         &AccessLevel::Full,
         &157_680_000u64,
+        &ConsentType::Treatment,
     );
 
     client.set_record_resolution_strategy(&provider, &record_id, &ResolutionStrategy::Merge);
@@ -557,6 +562,7 @@ fn test_get_record_conflicts_returns_for_specific_record() {
         &provider,
         &AccessLevel::Full,
         &157_680_000u64,
+        &ConsentType::Treatment,
     );
 
     client.set_record_resolution_strategy(&provider, &record_id, &ResolutionStrategy::ManualReview);
@@ -621,3 +627,29 @@ fn test_get_record_conflicts_returns_for_specific_record() {
     assert_eq!(conflicts.len(), 1);
     assert_eq!(conflicts.get(0).unwrap().record_id, record_id);
 }
+
+#[test]
+fn test_update_record_with_stale_version_leaves_record_unchanged() {
+    let (env, admin, client) = setup_env();
+    let provider = register_provider(&client, &env, &admin);
+    let patient = register_patient(&client, &env, &admin);
+
+    let record_id = add_exam_record(&client, &env, &admin, &patient, &provider);
+    // Default strategy is ManualReview, so a stale write is queued.
+
+    let v1 = client.get_record_version_stamp(&record_id).version;
+    let first_hash = String::from_str(&env, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o");
+    let stale_hash = String::from_str(&env, "QmPChd2hVbrJ6bfo3WBcTW4iZnpHm8TEzWkLHmLpXhF68A");
+
+    let first = client.update_record(&provider, &record_id, &v1, &1u32, &first_hash);
+    assert!(matches!(first, UpdateOutcome::Applied(_)));
+    assert_eq!(client.get_record_version_stamp(&record_id).version, v1 + 1);
+
+    let second = client.update_record(&provider, &record_id, &v1, &2u32, &stale_hash);
+    assert!(matches!(second, UpdateOutcome::Conflicted(_)));
+    assert_eq!(
+        client.get_record(&patient, &record_id).data_hash,
+        first_hash
+    );
+    assert_eq!(client.get_record_versions(&patient, &record_id).len(), 2);
+}
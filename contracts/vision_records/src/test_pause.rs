@@ -83,6 +83,7 @@ fn test_granular_pause() {
         &doctor,
         &crate::AccessLevel::Read,
         &3600,
+        &crate::ConsentType::Treatment,
     );
 
     // Resume `ADD_REC`
@@ -0,0 +1,420 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::arithmetic_side_effects
+)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger};
+use soroban_sdk::xdr::{ContractEventBody, ScVal};
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+fn setup() -> (Env, VisionRecordsContractClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let doctor = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &doctor,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Eye"),
+    );
+
+    (env, client, admin, doctor)
+}
+
+fn eye_data(env: &Env) -> PrescriptionData {
+    PrescriptionData {
+        sphere: String::from_str(env, "-2.50"),
+        cylinder: String::from_str(env, "-1.25"),
+        axis: String::from_str(env, "180"),
+        add: String::from_str(env, "0.00"),
+        pd: String::from_str(env, "62"),
+    }
+}
+
+fn issue(
+    env: &Env,
+    client: &VisionRecordsContractClient,
+    patient: &Address,
+    doctor: &Address,
+) -> u64 {
+    client.add_prescription(
+        patient,
+        doctor,
+        &LensType::Glasses,
+        &eye_data(env),
+        &eye_data(env),
+        &OptionalContactLensData::None,
+        &31_536_000,
+        &String::from_str(env, "metadata_hash"),
+        &2,
+    )
+}
+
+#[test]
+fn test_revoke_prescription_sets_status_and_reason() {
+    let (env, client, _admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+
+    let reason = String::from_str(&env, "wrong sphere power");
+    client.revoke_prescription(&doctor, &rx_id, &reason);
+
+    let rx = client.get_prescription(&patient, &rx_id);
+    assert!(rx.revoked);
+    assert_eq!(rx.revoked_reason, Some(reason.clone()));
+
+    // A revoked prescription cannot be revoked again or renewed.
+    let again = client.try_revoke_prescription(&doctor, &rx_id, &reason);
+    assert_eq!(again, Err(Ok(ContractError::InvalidInput)));
+    let renew = client.try_renew_prescription(&doctor, &rx_id, &(env.ledger().timestamp() + 100));
+    assert_eq!(renew, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_revoke_prescription_requires_issuer_or_admin() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+
+    let other = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &other,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Other"),
+    );
+    let reason = String::from_str(&env, "not mine");
+    let res = client.try_revoke_prescription(&other, &rx_id, &reason);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    client.revoke_prescription(&admin, &rx_id, &reason);
+    assert!(client.get_prescription(&patient, &rx_id).revoked);
+}
+
+#[test]
+fn test_renew_prescription_links_to_prior() {
+    let (env, client, _admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+
+    env.ledger().set_timestamp(1_000);
+    let new_expiry = 1_000 + 31_536_000;
+    let renewed_id = client.renew_prescription(&doctor, &rx_id, &new_expiry);
+    assert_ne!(renewed_id, rx_id);
+
+    let renewed = client.get_prescription(&patient, &renewed_id);
+    assert_eq!(renewed.supersedes, Some(rx_id));
+    assert_eq!(renewed.expires_at, new_expiry);
+    assert_eq!(renewed.issued_at, 1_000);
+    assert!(!renewed.revoked);
+    assert!(!renewed.verified);
+
+    // The prior prescription is superseded and can no longer be filled.
+    let original = client.get_prescription(&patient, &rx_id);
+    assert_eq!(original.supersedes, None);
+    assert!(original.revoked);
+    assert_eq!(
+        original.revoked_reason,
+        Some(String::from_str(&env, "superseded"))
+    );
+    assert!(!client.is_prescription_valid(&rx_id));
+
+    let history = client.get_prescription_history(&patient);
+    assert_eq!(history.len(), 2);
+
+    let past = client.try_renew_prescription(&doctor, &renewed_id, &500);
+    assert_eq!(past, Err(Ok(ContractError::InvalidTimestamp)));
+}
+
+#[test]
+fn test_is_prescription_valid_cases() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+
+    // Unverified prescription is not yet fillable.
+    let rx_id = issue(&env, &client, &patient, &doctor);
+    assert!(!client.is_prescription_valid(&rx_id));
+    assert_eq!(client.get_active_prescriptions(&patient).len(), 0);
+
+    // Verified and unexpired.
+    client.verify_prescription(&rx_id, &admin);
+    assert!(client.is_prescription_valid(&rx_id));
+    let active = client.get_active_prescriptions(&patient);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap(), rx_id);
+
+    // Expired.
+    let rx = client.get_prescription(&patient, &rx_id);
+    env.ledger().set_timestamp(rx.expires_at);
+    assert!(!client.is_prescription_valid(&rx_id));
+    assert_eq!(client.get_active_prescriptions(&patient).len(), 0);
+
+    // Unknown prescription.
+    assert!(!client.is_prescription_valid(&999));
+}
+
+#[test]
+fn test_revoked_prescription_is_not_valid() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+    client.verify_prescription(&rx_id, &admin);
+    assert!(client.is_prescription_valid(&rx_id));
+
+    client.revoke_prescription(&doctor, &rx_id, &String::from_str(&env, "error"));
+    assert!(!client.is_prescription_valid(&rx_id));
+    assert_eq!(client.get_active_prescriptions(&patient).len(), 0);
+}
+
+#[test]
+fn test_verify_prescription_requires_dispense_permission() {
+    let (env, client, _admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+
+    // Neither the patient nor a prescriber without the permission may verify.
+    let res = client.try_verify_prescription(&rx_id, &patient);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+    let res = client.try_verify_prescription(&rx_id, &doctor);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+    assert!(!client.get_prescription(&patient, &rx_id).verified);
+}
+
+#[test]
+fn test_record_dispense_exhausts_refills() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let pharmacy = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+
+    // Dispensing requires the DispensePrescription permission.
+    let res = client.try_record_dispense(&pharmacy, &rx_id);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+    client.register_user(
+        &admin,
+        &pharmacy,
+        &Role::Staff,
+        &String::from_str(&env, "Pharmacy"),
+    );
+    client.grant_custom_permission(&admin, &pharmacy, &Permission::DispensePrescription);
+
+    // Unverified prescriptions cannot be dispensed.
+    let res = client.try_record_dispense(&pharmacy, &rx_id);
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+
+    client.verify_prescription(&rx_id, &admin);
+    assert_eq!(client.record_dispense(&pharmacy, &rx_id), 1);
+    assert_eq!(client.record_dispense(&pharmacy, &rx_id), 2);
+
+    let res = client.try_record_dispense(&pharmacy, &rx_id);
+    assert_eq!(res, Err(Ok(ContractError::RefillLimitReached)));
+
+    let rx = client.get_prescription(&patient, &rx_id);
+    assert_eq!(rx.refills_used, 2);
+    assert_eq!(rx.refills_allowed, 2);
+
+    let log = client.get_dispense_history(&rx_id);
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.get(0).unwrap().dispenser, pharmacy);
+    assert_eq!(log.get(1).unwrap().refill_number, 2);
+}
+
+#[test]
+fn test_two_phase_prescription_keeps_refills() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+
+    let rx_id = client.prepare_add_prescription(&patient, &doctor, &eye_data(&env), &3);
+    client.commit_add_prescription(&rx_id);
+
+    let rx = client.get_prescription(&patient, &rx_id);
+    assert_eq!(rx.refills_allowed, 3);
+
+    client.verify_prescription(&rx_id, &admin);
+    assert_eq!(client.record_dispense(&admin, &rx_id), 1);
+}
+
+#[test]
+fn test_two_phase_commit_accepts_prepare_from_before_refills() {
+    let (env, client, _admin, doctor) = setup();
+    let patient = Address::generate(&env);
+
+    let rx_id = 1u64;
+    env.as_contract(&client.address, || {
+        env.storage().temporary().set(
+            &(symbol_short!("P_ADD_RX"), rx_id),
+            &LegacyPrepareAddPrescription {
+                patient: patient.clone(),
+                provider: doctor.clone(),
+                prescription_data: eye_data(&env),
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    });
+    client.commit_add_prescription(&rx_id);
+
+    let rx = client.get_prescription(&patient, &rx_id);
+    assert_eq!(rx.refills_allowed, 1);
+    assert_eq!(rx.refills_used, 0);
+}
+
+#[test]
+fn test_add_prescription_rejects_out_of_range_axis() {
+    let (env, client, _admin, doctor) = setup();
+    let patient = Address::generate(&env);
+
+    let mut bad = eye_data(&env);
+    bad.axis = String::from_str(&env, "999");
+
+    let res = client.try_add_prescription(
+        &patient,
+        &doctor,
+        &LensType::Glasses,
+        &bad,
+        &eye_data(&env),
+        &OptionalContactLensData::None,
+        &31_536_000,
+        &String::from_str(&env, "metadata_hash"),
+        &0,
+    );
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+    assert_eq!(client.get_prescription_history(&patient).len(), 0);
+
+    // The shared fixture is clinically valid and is accepted.
+    let rx_id = issue(&env, &client, &patient, &doctor);
+    assert_eq!(
+        client.get_prescription(&patient, &rx_id).left_eye,
+        eye_data(&env)
+    );
+}
+
+fn last_event_data(env: &Env) -> ScVal {
+    let events = env.events().all();
+    let event = events.events().last().expect("no events published").clone();
+    let ContractEventBody::V0(body) = event.body;
+    body.data
+}
+
+#[test]
+fn test_prescription_issue_and_verify_emit_events() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+
+    let rx_id = issue(&env, &client, &patient, &doctor);
+    let issued: Val = events::PrescriptionIssuedEvent {
+        rx_id,
+        patient: patient.clone(),
+        provider: doctor.clone(),
+        lens_type: LensType::Glasses,
+        timestamp: env.ledger().timestamp(),
+    }
+    .into_val(&env);
+    assert_eq!(
+        last_event_data(&env),
+        ScVal::try_from_val(&env, &issued).unwrap()
+    );
+
+    let pharmacist = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &pharmacist,
+        &Role::Staff,
+        &String::from_str(&env, "Pharmacist"),
+    );
+    client.grant_custom_permission(&admin, &pharmacist, &Permission::DispensePrescription);
+    assert!(client.verify_prescription(&rx_id, &pharmacist));
+    let verified: Val = events::PrescriptionVerifiedEvent {
+        rx_id,
+        verifier: pharmacist,
+        timestamp: env.ledger().timestamp(),
+    }
+    .into_val(&env);
+    assert_eq!(
+        last_event_data(&env),
+        ScVal::try_from_val(&env, &verified).unwrap()
+    );
+}
+
+#[test]
+fn test_get_prescription_requires_access() {
+    let (env, client, _admin, doctor) = setup();
+    let patient = Address::generate(&env);
+    let rx_id = issue(&env, &client, &patient, &doctor);
+
+    assert_eq!(client.get_prescription(&doctor, &rx_id).id, rx_id);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_get_prescription(&stranger, &rx_id),
+        Err(Ok(ContractError::AccessDenied))
+    );
+
+    client.grant_consent(&patient, &stranger, &ConsentType::Treatment, &86400);
+    client.grant_access(
+        &patient,
+        &patient,
+        &stranger,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    assert_eq!(client.get_prescription(&stranger, &rx_id).id, rx_id);
+}
+
+#[test]
+fn test_prescription_stored_in_legacy_layout_is_upgraded() {
+    let (env, client, admin, doctor) = setup();
+    let patient = Address::generate(&env);
+
+    // Write an entry the way prescriptions were stored before revocation and
+    // refills were tracked.
+    let rx_id = 7u64;
+    let legacy = prescription::LegacyPrescription {
+        id: rx_id,
+        patient: patient.clone(),
+        provider: doctor.clone(),
+        lens_type: LensType::Glasses,
+        left_eye: eye_data(&env),
+        right_eye: eye_data(&env),
+        contact_data: OptionalContactLensData::None,
+        issued_at: 0,
+        expires_at: 31_536_000,
+        verified: false,
+        metadata_hash: String::from_str(&env, "metadata_hash"),
+    };
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("RX"), rx_id), &legacy);
+        env.storage().persistent().set(
+            &(symbol_short!("RX_HIST"), patient.clone()),
+            &Vec::from_array(&env, [rx_id]),
+        );
+    });
+
+    let rx = client.get_prescription(&patient, &rx_id);
+    assert!(!rx.revoked);
+    assert_eq!(rx.supersedes, None);
+    assert_eq!(rx.refills_allowed, 1);
+    assert_eq!(rx.refills_used, 0);
+
+    client.verify_prescription(&rx_id, &admin);
+    assert_eq!(client.get_active_prescriptions(&patient).len(), 1);
+    assert_eq!(client.record_dispense(&admin, &rx_id), 1);
+
+    client.revoke_prescription(&doctor, &rx_id, &String::from_str(&env, "error"));
+    let rx = client.get_prescription(&patient, &rx_id);
+    assert!(rx.revoked);
+    assert_eq!(rx.refills_used, 1);
+    assert_eq!(client.get_active_prescriptions(&patient).len(), 0);
+}
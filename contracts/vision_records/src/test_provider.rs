@@ -0,0 +1,369 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::arithmetic_side_effects
+)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::xdr::{ContractEventBody, ScVal};
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+fn setup() -> (Env, VisionRecordsContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    (env, client, admin)
+}
+
+fn register(env: &Env, client: &VisionRecordsContractClient, admin: &Address) -> Address {
+    let provider = Address::generate(env);
+    client.register_user(
+        admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(env, "Dr. Smith"),
+    );
+    let mut specialties = Vec::new(env);
+    specialties.push_back(String::from_str(env, "Optometry"));
+    client.register_provider(
+        admin,
+        &provider,
+        &String::from_str(env, "Dr. Smith"),
+        &Vec::new(env),
+        &specialties,
+        &Vec::new(env),
+        &Vec::new(env),
+    );
+    provider
+}
+
+#[test]
+fn test_register_provider_starts_pending() {
+    let (env, client, admin) = setup();
+    let provider = register(&env, &client, &admin);
+
+    let data = client.get_provider(&provider);
+    assert_eq!(data.verification_status, VerificationStatus::Pending);
+    assert!(data.verified_by.is_none());
+    assert!(client
+        .get_providers_by_status(&VerificationStatus::Pending)
+        .contains(&provider));
+
+    let res = client.try_register_provider(
+        &admin,
+        &provider,
+        &String::from_str(&env, "Dr. Smith"),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::ProviderAlreadyRegistered)));
+
+    let stranger = Address::generate(&env);
+    let res = client.try_register_provider(
+        &stranger,
+        &Address::generate(&env),
+        &String::from_str(&env, "Dr. Nobody"),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+}
+
+#[test]
+fn test_register_provider_requires_registered_user() {
+    let (env, client, admin) = setup();
+
+    let unknown = Address::generate(&env);
+    let res = client.try_register_provider(
+        &admin,
+        &unknown,
+        &String::from_str(&env, "Dr. Unknown"),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::UserNotFound)));
+    assert_eq!(
+        client.try_get_provider(&unknown).map(|_| ()),
+        Err(Ok(ContractError::ProviderNotFound))
+    );
+
+    client.register_user(
+        &admin,
+        &unknown,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Unknown"),
+    );
+    client.register_provider(
+        &admin,
+        &unknown,
+        &String::from_str(&env, "Dr. Unknown"),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(client.get_provider(&unknown).address, unknown);
+}
+
+#[test]
+fn test_verify_provider_event_carries_transition() {
+    let (env, client, admin) = setup();
+    let provider = register(&env, &client, &admin);
+
+    client.verify_provider(&admin, &provider, &VerificationStatus::Verified);
+    client.verify_provider(&admin, &provider, &VerificationStatus::Suspended);
+
+    let events = env.events().all();
+    let event = events.events().last().expect("no events published");
+    let ContractEventBody::V0(body) = &event.body;
+
+    let expected = events::ProviderVerifiedEvent {
+        provider: provider.clone(),
+        old_status: VerificationStatus::Verified,
+        new_status: VerificationStatus::Suspended,
+        verified_by: admin.clone(),
+        timestamp: env.ledger().timestamp(),
+    };
+    let expected_val: Val = expected.into_val(&env);
+    assert_eq!(body.data, ScVal::try_from_val(&env, &expected_val).unwrap());
+
+    let data = client.get_provider(&provider);
+    assert_eq!(data.verification_status, VerificationStatus::Suspended);
+    assert_eq!(data.verified_by, Some(admin));
+    assert!(!client
+        .get_providers_by_status(&VerificationStatus::Verified)
+        .contains(&provider));
+}
+
+#[test]
+fn test_verify_provider_requires_admin() {
+    let (env, client, admin) = setup();
+    let provider = register(&env, &client, &admin);
+
+    let res = client.try_verify_provider(&provider, &provider, &VerificationStatus::Verified);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    let missing = Address::generate(&env);
+    let res = client.try_verify_provider(&admin, &missing, &VerificationStatus::Verified);
+    assert_eq!(res, Err(Ok(ContractError::ProviderNotFound)));
+}
+
+#[test]
+fn test_get_providers_paged() {
+    let (env, client, admin) = setup();
+    let mut registered = Vec::new(&env);
+    for _ in 0..5 {
+        registered.push_back(register(&env, &client, &admin));
+    }
+
+    let first = client.get_providers_paged(&admin, &0, &2);
+    let second = client.get_providers_paged(&admin, &2, &2);
+    let last = client.get_providers_paged(&admin, &4, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(second.len(), 2);
+    assert_eq!(last.len(), 1);
+    assert_eq!(first.get(0).unwrap().address, registered.get(0).unwrap());
+    assert_eq!(second.get(1).unwrap().address, registered.get(3).unwrap());
+    assert_eq!(last.get(0).unwrap().address, registered.get(4).unwrap());
+
+    assert_eq!(client.get_providers_paged(&admin, &5, &2).len(), 0);
+    assert_eq!(client.get_providers_paged(&admin, &0, &u32::MAX).len(), 5);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_get_providers_paged(&outsider, &0, &2),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_record_type_requirement_blocks_unqualified_author() {
+    let (env, client, admin) = setup();
+    let optometrist = register(&env, &client, &admin);
+    client.register_user(
+        &admin,
+        &optometrist,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Smith"),
+    );
+    let surgeon = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &surgeon,
+        &Role::Ophthalmologist,
+        &String::from_str(&env, "Dr. Jones"),
+    );
+    let patient = Address::generate(&env);
+    let data_hash = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+
+    // Permissive by default.
+    assert!(client
+        .get_record_type_requirement(&RecordType::Surgery)
+        .is_none());
+    client.add_record(
+        &optometrist,
+        &patient,
+        &optometrist,
+        &RecordType::Surgery,
+        &data_hash,
+    );
+
+    client.set_record_type_requirement(
+        &admin,
+        &RecordType::Surgery,
+        &Vec::from_array(&env, [Role::Ophthalmologist]),
+        &Vec::from_array(&env, [String::from_str(&env, "Retina Surgery")]),
+    );
+
+    let res = client.try_add_record(
+        &optometrist,
+        &patient,
+        &optometrist,
+        &RecordType::Surgery,
+        &data_hash,
+    );
+    assert_eq!(res, Err(Ok(ContractError::AccessDenied)));
+
+    // The two-phase path is held to the same requirement.
+    let res = client.try_prepare_add_record(
+        &optometrist,
+        &patient,
+        &optometrist,
+        &RecordType::Surgery,
+        &data_hash,
+    );
+    assert_eq!(res, Err(Ok(ContractError::AccessDenied)));
+
+    // Other types and qualified providers are unaffected.
+    client.add_record(
+        &optometrist,
+        &patient,
+        &optometrist,
+        &RecordType::Examination,
+        &data_hash,
+    );
+    client.add_record(
+        &surgeon,
+        &patient,
+        &surgeon,
+        &RecordType::Surgery,
+        &data_hash,
+    );
+
+    let res = client.try_set_record_type_requirement(
+        &optometrist,
+        &RecordType::Surgery,
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+}
+
+#[test]
+fn test_provider_status_tracks_license_expiry() {
+    let (env, client, admin) = setup();
+    env.ledger().set_timestamp(1_000_000);
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Smith"),
+    );
+    let mut licenses = Vec::new(&env);
+    licenses.push_back(License {
+        number: String::from_str(&env, "OD-12345"),
+        issuing_authority: String::from_str(&env, "State Board"),
+        issued_date: 500_000,
+        expiry_date: 2_000_000,
+        license_type: String::from_str(&env, "Optometry"),
+    });
+    client.register_provider(
+        &admin,
+        &provider,
+        &String::from_str(&env, "Dr. Smith"),
+        &licenses,
+        &Vec::new(&env),
+        &Vec::new(&env),
+        &Vec::new(&env),
+    );
+
+    let status = client.get_provider_status(&provider);
+    assert!(status.has_active_license);
+    assert!(!status.can_practice);
+
+    client.verify_provider(&admin, &provider, &VerificationStatus::Verified);
+    assert!(client.get_provider_status(&provider).can_practice);
+
+    env.ledger().set_timestamp(2_000_000);
+    let status = client.get_provider_status(&provider);
+    assert_eq!(status.verification_status, VerificationStatus::Verified);
+    assert!(!status.has_active_license);
+    assert!(!status.can_practice);
+
+    let res = client.try_get_provider_status(&Address::generate(&env));
+    assert_eq!(res, Err(Ok(ContractError::ProviderNotFound)));
+}
+
+#[test]
+fn test_deactivated_provider_drops_out_of_searches() {
+    let (env, client, admin) = setup();
+    let provider = register(&env, &client, &admin);
+    let optometry = String::from_str(&env, "Optometry");
+    assert!(client
+        .get_providers_by_specialty(&optometry)
+        .contains(&provider));
+
+    let res = client.try_deactivate_provider(&provider, &provider);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    client.deactivate_provider(&admin, &provider);
+    let events = env.events().all();
+    let event = events.events().last().expect("no events published");
+    let ContractEventBody::V0(body) = &event.body;
+    let expected: Val = events::ProviderActivationEvent {
+        provider: provider.clone(),
+        is_active: false,
+        changed_by: admin.clone(),
+        timestamp: env.ledger().timestamp(),
+    }
+    .into_val(&env);
+    assert_eq!(body.data, ScVal::try_from_val(&env, &expected).unwrap());
+
+    assert!(!client
+        .get_providers_by_specialty(&optometry)
+        .contains(&provider));
+    assert!(!client
+        .get_providers_by_status(&VerificationStatus::Pending)
+        .contains(&provider));
+    // The profile itself is kept.
+    let data = client.get_provider(&provider);
+    assert!(!data.is_active);
+    assert_eq!(data.specialties.len(), 1);
+
+    client.activate_provider(&admin, &provider);
+    assert!(client
+        .get_providers_by_specialty(&optometry)
+        .contains(&provider));
+    assert!(client
+        .get_providers_by_status(&VerificationStatus::Pending)
+        .contains(&provider));
+
+    let res = client.try_deactivate_provider(&admin, &Address::generate(&env));
+    assert_eq!(res, Err(Ok(ContractError::ProviderNotFound)));
+}
@@ -0,0 +1,427 @@
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::arithmetic_side_effects
+)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Env;
+
+fn setup() -> (Env, VisionRecordsContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(VisionRecordsContract, ());
+    let client = VisionRecordsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    (env, client, admin)
+}
+
+fn add_record(
+    env: &Env,
+    client: &VisionRecordsContractClient,
+    caller: &Address,
+    patient: &Address,
+) -> Result<u64, ContractError> {
+    let data_hash = String::from_str(env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+    match client.try_add_record(
+        caller,
+        patient,
+        caller,
+        &RecordType::Examination,
+        &data_hash,
+    ) {
+        Ok(Ok(id)) => Ok(id),
+        Err(Ok(err)) => Err(err),
+        _ => panic!("unexpected host error"),
+    }
+}
+
+#[test]
+fn test_operation_rate_limit_config() {
+    let (env, client, admin) = setup();
+    let operation = String::from_str(&env, "add_record");
+
+    assert!(client.get_operation_rate_limit(&operation).is_none());
+
+    client.set_operation_rate_limit(&admin, &operation, &5, &3600);
+    let cfg = client.get_operation_rate_limit(&operation).unwrap();
+    assert_eq!(cfg.max_requests, 5);
+    assert_eq!(cfg.window_seconds, 3600);
+
+    let stranger = Address::generate(&env);
+    let res = client.try_set_operation_rate_limit(&stranger, &operation, &5, &3600);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    let res = client.try_set_operation_rate_limit(&admin, &operation, &0, &3600);
+    assert_eq!(res, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_sliding_window_throttles_across_old_boundary() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let operation = String::from_str(&env, "add_record");
+    client.set_operation_rate_limit(&admin, &operation, &2, &60);
+
+    env.ledger().set_timestamp(100);
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    env.ledger().set_timestamp(159);
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+
+    // A fixed window starting at t=100 would reset at t=160 and allow a
+    // burst of two more; the sliding window only frees the t=100 slot.
+    env.ledger().set_timestamp(160);
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    env.ledger().set_timestamp(161);
+    assert_eq!(
+        add_record(&env, &client, &admin, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+
+    let status = client.get_rate_limit_status(&admin, &operation).unwrap();
+    assert_eq!(status.current_count, 2);
+    assert_eq!(status.max_requests, 2);
+    assert_eq!(status.reset_at, 159 + 60);
+
+    env.ledger().set_timestamp(219);
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+}
+
+#[test]
+fn test_unconfigured_operation_is_unlimited() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+
+    for _ in 0..5 {
+        assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    }
+    let operation = String::from_str(&env, "add_record");
+    assert!(client.get_rate_limit_status(&admin, &operation).is_none());
+}
+
+#[test]
+fn test_operation_limit_replaces_legacy_limit() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+
+    // The legacy fixed window allows a single request per hour.
+    client.set_rate_limit_config(&admin, &1, &3600, &0u64);
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    assert_eq!(
+        add_record(&env, &client, &admin, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+
+    // Once the operation has its own limit, only that limit is charged.
+    let operation = String::from_str(&env, "add_record");
+    client.set_operation_rate_limit(&admin, &operation, &3, &3600);
+    for _ in 0..3 {
+        assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    }
+    assert_eq!(
+        add_record(&env, &client, &admin, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+}
+
+#[test]
+fn test_user_override_allows_larger_budget() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let clinic = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &clinic,
+        &Role::Optometrist,
+        &String::from_str(&env, "Busy Clinic"),
+    );
+
+    let operation = String::from_str(&env, "add_record");
+    client.set_operation_rate_limit(&admin, &operation, &1, &3600);
+    client.set_user_rate_limit(&admin, &clinic, &operation, &3, &3600);
+
+    let cfg = client.get_user_rate_limit(&clinic, &operation).unwrap();
+    assert_eq!(cfg.max_requests, 3);
+    assert!(client.get_user_rate_limit(&admin, &operation).is_none());
+
+    for _ in 0..3 {
+        assert!(add_record(&env, &client, &clinic, &patient).is_ok());
+    }
+    assert_eq!(
+        add_record(&env, &client, &clinic, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+
+    // Everyone else is still held to the global limit.
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    assert_eq!(
+        add_record(&env, &client, &admin, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+
+    let status = client.get_rate_limit_status(&clinic, &operation).unwrap();
+    assert_eq!(status.max_requests, 3);
+}
+
+#[test]
+fn test_grant_access_limit_is_independent_per_operation() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let doctor = Address::generate(&env);
+
+    let grant_op = String::from_str(&env, "grant_access");
+    client.set_operation_rate_limit(&admin, &grant_op, &2, &3600);
+
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    client.grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    let res = client.try_grant_access(
+        &patient,
+        &patient,
+        &doctor,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    assert_eq!(res, Err(Ok(ContractError::RateLimitExceeded)));
+
+    // Exhausting grant_access leaves other operations untouched.
+    let mut grants = Vec::new(&env);
+    grants.push_back(BatchGrantInput {
+        grantee: doctor.clone(),
+        level: AccessLevel::Read,
+        duration_seconds: 86400,
+    });
+    client.grant_access_batch(&patient, &grants);
+    let provider = Address::generate(&env);
+    client.schedule_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::Examination,
+        &(env.ledger().timestamp() + 86400),
+        &30,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn test_grant_access_batch_and_schedule_limits() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    client.set_operation_rate_limit(
+        &admin,
+        &String::from_str(&env, "grant_access_batch"),
+        &1,
+        &3600,
+    );
+    client.set_operation_rate_limit(
+        &admin,
+        &String::from_str(&env, "schedule_appointment"),
+        &1,
+        &3600,
+    );
+
+    let mut grants = Vec::new(&env);
+    grants.push_back(BatchGrantInput {
+        grantee: provider.clone(),
+        level: AccessLevel::Read,
+        duration_seconds: 86400,
+    });
+    client.grant_access_batch(&patient, &grants);
+    let res = client.try_grant_access_batch(&patient, &grants);
+    assert_eq!(res, Err(Ok(ContractError::RateLimitExceeded)));
+
+    let at = env.ledger().timestamp() + 86400;
+    client.schedule_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::Examination,
+        &at,
+        &30,
+        &None,
+        &None,
+    );
+    let res = client.try_schedule_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::FollowUp,
+        &at,
+        &30,
+        &None,
+        &None,
+    );
+    assert_eq!(res, Err(Ok(ContractError::RateLimitExceeded)));
+}
+
+#[test]
+fn test_recurring_booking_charges_each_appointment() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    client.set_operation_rate_limit(
+        &admin,
+        &String::from_str(&env, "schedule_appointment"),
+        &3,
+        &3600,
+    );
+
+    let first_at = env.ledger().timestamp() + 86400;
+    let week = 7 * 86400;
+    let res = client.try_schedule_recurring_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::FollowUp,
+        &first_at,
+        &30,
+        &week,
+        &4,
+    );
+    assert_eq!(res, Err(Ok(ContractError::RateLimitExceeded)));
+
+    let created = client.schedule_recurring_appointment(
+        &patient,
+        &patient,
+        &provider,
+        &AppointmentType::FollowUp,
+        &first_at,
+        &30,
+        &week,
+        &3,
+    );
+    assert_eq!(created.len(), 3);
+
+    let status = client
+        .get_rate_limit_status(&patient, &String::from_str(&env, "schedule_appointment"))
+        .unwrap();
+    assert_eq!(status.current_count, 3);
+}
+
+#[test]
+fn test_bypass_skips_operation_limits() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    let grant_op = String::from_str(&env, "grant_access");
+    client.set_operation_rate_limit(&admin, &grant_op, &1, &3600);
+
+    client.set_rate_limit_bypass(&admin, &patient, &true);
+    assert!(client.has_rate_limit_bypass(&patient));
+    for _ in 0..3 {
+        client.grant_access(
+            &patient,
+            &patient,
+            &provider,
+            &AccessLevel::Read,
+            &86400,
+            &ConsentType::Treatment,
+        );
+    }
+
+    client.set_rate_limit_bypass(&admin, &patient, &false);
+    assert!(!client.has_rate_limit_bypass(&patient));
+    client.grant_access(
+        &patient,
+        &patient,
+        &provider,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    let res = client.try_grant_access(
+        &patient,
+        &patient,
+        &provider,
+        &AccessLevel::Read,
+        &86400,
+        &ConsentType::Treatment,
+    );
+    assert_eq!(res, Err(Ok(ContractError::RateLimitExceeded)));
+}
+
+#[test]
+fn test_reset_rate_limit_starts_fresh_window() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let operation = String::from_str(&env, "add_record");
+    client.set_operation_rate_limit(&admin, &operation, &1, &3600);
+
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+    assert_eq!(
+        add_record(&env, &client, &admin, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+
+    let stranger = Address::generate(&env);
+    let res = client.try_reset_rate_limit(&stranger, &admin, &operation);
+    assert_eq!(res, Err(Ok(ContractError::Unauthorized)));
+
+    client.reset_rate_limit(&admin, &admin, &operation);
+    let status = client.get_rate_limit_status(&admin, &operation).unwrap();
+    assert_eq!(status.current_count, 0);
+    assert!(add_record(&env, &client, &admin, &patient).is_ok());
+}
+
+#[test]
+fn test_exempt_role_is_not_throttled() {
+    let (env, client, admin) = setup();
+    let patient = Address::generate(&env);
+    let surgeon = Address::generate(&env);
+    let optometrist = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &surgeon,
+        &Role::Ophthalmologist,
+        &String::from_str(&env, "Dr. Bulk"),
+    );
+    client.register_user(
+        &admin,
+        &optometrist,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Small"),
+    );
+
+    let operation = String::from_str(&env, "add_record");
+    client.set_operation_rate_limit(&admin, &operation, &1, &3600);
+
+    let mut roles = Vec::new(&env);
+    roles.push_back(Role::Ophthalmologist);
+    client.set_rate_limit_exempt_roles(&admin, &roles);
+    assert_eq!(client.get_rate_limit_exempt_roles(), roles);
+
+    for _ in 0..3 {
+        assert!(add_record(&env, &client, &surgeon, &patient).is_ok());
+    }
+
+    assert!(add_record(&env, &client, &optometrist, &patient).is_ok());
+    assert_eq!(
+        add_record(&env, &client, &optometrist, &patient),
+        Err(ContractError::RateLimitExceeded)
+    );
+}
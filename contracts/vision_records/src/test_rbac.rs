@@ -5,7 +5,10 @@
     clippy::arithmetic_side_effects
 )]
 
-use super::{ConsentType, Permission, Role, VisionRecordsContract, VisionRecordsContractClient};
+use super::{
+    ConsentType, CredentialType, Permission, RecordType, Role, SensitivityLevel, TimeRestriction,
+    VisionRecordsContract, VisionRecordsContractClient,
+};
 use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Address, Env, String, Vec};
 
 fn setup_test() -> (Env, VisionRecordsContractClient<'static>, Address) {
@@ -153,7 +156,14 @@ fn test_role_delegation() {
 
     // pt2 should be able to grant access acting for pt1
     // (caller: pt2, patient: pt1, grantee: doctor)
-    client.grant_access(&pt2, &pt1, &doctor, &super::AccessLevel::Read, &3600);
+    client.grant_access(
+        &pt2,
+        &pt1,
+        &doctor,
+        &super::AccessLevel::Read,
+        &3600,
+        &ConsentType::Treatment,
+    );
 
     assert_eq!(client.check_access(&pt1, &doctor), super::AccessLevel::Read);
 }
@@ -200,6 +210,7 @@ fn test_role_delegation_expiration() {
         &doctor,
         &super::AccessLevel::Read,
         &3600,
+        &ConsentType::Treatment,
     );
     assert!(result.is_err());
 }
@@ -297,3 +308,867 @@ fn test_acl_group_unauthorized_management() {
     let result = client.try_create_acl_group(&non_admin, &group_name, &perms);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_role_at_least_matrix() {
+    let (env, client, admin) = setup_test();
+
+    let roles = [
+        Role::Patient,
+        Role::Staff,
+        Role::Optometrist,
+        Role::Ophthalmologist,
+        Role::Admin,
+    ];
+
+    let mut users = Vec::new(&env);
+    for role in roles.iter() {
+        let user = Address::generate(&env);
+        client.register_user(&admin, &user, role, &String::from_str(&env, "User"));
+        users.push_back(user);
+    }
+
+    for (i, held) in roles.iter().enumerate() {
+        let user = users.get(i as u32).unwrap();
+        for min in roles.iter() {
+            let expected = *held as u32 >= *min as u32;
+            assert_eq!(
+                client.role_at_least(&user, min),
+                expected,
+                "{:?} >= {:?}",
+                held,
+                min
+            );
+        }
+        assert!(client.role_at_least(&user, &Role::None));
+    }
+
+    // Ophthalmologists satisfy optometrist-level checks, not the reverse.
+    assert!(client.role_at_least(&users.get(3).unwrap(), &Role::Optometrist));
+    assert!(!client.role_at_least(&users.get(2).unwrap(), &Role::Ophthalmologist));
+
+    // Unregistered users only clear the `None` floor.
+    let stranger = Address::generate(&env);
+    assert!(client.role_at_least(&stranger, &Role::None));
+    assert!(!client.role_at_least(&stranger, &Role::Patient));
+}
+
+#[test]
+fn test_get_users_by_role_buckets() {
+    let (env, client, admin) = setup_test();
+
+    let mut patients = Vec::new(&env);
+    for _ in 0..3 {
+        let patient = Address::generate(&env);
+        client.register_user(
+            &admin,
+            &patient,
+            &Role::Patient,
+            &String::from_str(&env, "Patient"),
+        );
+        patients.push_back(patient);
+    }
+    let optometrist = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &optometrist,
+        &Role::Optometrist,
+        &String::from_str(&env, "Opto"),
+    );
+
+    assert_eq!(
+        client.get_users_by_role(&admin, &Role::Patient, &0),
+        patients
+    );
+    let optos = client.get_users_by_role(&admin, &Role::Optometrist, &0);
+    assert_eq!(optos.len(), 1);
+    assert_eq!(optos.get(0).unwrap(), optometrist);
+    assert_eq!(client.get_users_by_role(&admin, &Role::Staff, &0).len(), 0);
+
+    let admins = client.get_users_by_role(&admin, &Role::Admin, &0);
+    assert_eq!(admins.len(), 1);
+    assert_eq!(admins.get(0).unwrap(), admin);
+
+    // Re-registering under a new role moves the user between buckets.
+    let moved = patients.get(0).unwrap();
+    client.register_user(
+        &admin,
+        &moved,
+        &Role::Staff,
+        &String::from_str(&env, "Patient"),
+    );
+    assert_eq!(
+        client.get_users_by_role(&admin, &Role::Patient, &0).len(),
+        2
+    );
+    assert!(client
+        .get_users_by_role(&admin, &Role::Staff, &0)
+        .contains(&moved));
+
+    let stranger = patients.get(1).unwrap();
+    let result = client.try_get_users_by_role(&stranger, &Role::Patient, &0);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_get_users_by_role_pages() {
+    let (env, client, admin) = setup_test();
+
+    let page_size = super::rbac::ROLE_INDEX_PAGE_SIZE;
+    let mut staff = Vec::new(&env);
+    for _ in 0..=page_size {
+        let user = Address::generate(&env);
+        client.register_user(
+            &admin,
+            &user,
+            &Role::Staff,
+            &String::from_str(&env, "Staff"),
+        );
+        staff.push_back(user);
+    }
+
+    assert_eq!(client.get_role_page_count(&Role::Staff), 2);
+    assert_eq!(
+        client.get_users_by_role(&admin, &Role::Staff, &0).len(),
+        page_size
+    );
+    let last = staff.get(page_size).unwrap();
+    let second = client.get_users_by_role(&admin, &Role::Staff, &1);
+    assert_eq!(second.len(), 1);
+    assert_eq!(second.get(0).unwrap(), last);
+
+    // Moving a user out of the role only rewrites their own page.
+    let first = staff.get(0).unwrap();
+    client.change_user_role(&admin, &first, &Role::Optometrist);
+    assert!(!client
+        .get_users_by_role(&admin, &Role::Staff, &0)
+        .contains(&first));
+    assert_eq!(client.get_users_by_role(&admin, &Role::Staff, &1), second);
+}
+
+#[test]
+fn test_change_user_role_promotes_staff() {
+    let (env, client, admin) = setup_test();
+
+    let staff = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &staff,
+        &Role::Staff,
+        &String::from_str(&env, "Staff"),
+    );
+    let registered_at = client.get_user(&staff).registered_at;
+    assert!(!client.check_permission(&staff, &Permission::WriteRecord));
+
+    env.ledger().set_timestamp(registered_at + 1000);
+    client.change_user_role(&admin, &staff, &Role::Optometrist);
+
+    let user = client.get_user(&staff);
+    assert_eq!(user.role, Role::Optometrist);
+    assert_eq!(user.registered_at, registered_at);
+    assert!(client.check_permission(&staff, &Permission::WriteRecord));
+
+    assert_eq!(client.get_users_by_role(&admin, &Role::Staff, &0).len(), 0);
+    assert!(client
+        .get_users_by_role(&admin, &Role::Optometrist, &0)
+        .contains(&staff));
+
+    // A bulk revoke survives a later role change.
+    client.revoke_all_custom_permissions(&admin, &staff);
+    client.change_user_role(&admin, &staff, &Role::Ophthalmologist);
+    assert!(!client.check_permission(&staff, &Permission::WriteRecord));
+
+    let missing = Address::generate(&env);
+    let result = client.try_change_user_role(&admin, &missing, &Role::Staff);
+    assert_eq!(result, Err(Ok(super::ContractError::UserNotFound)));
+}
+
+#[test]
+fn test_emergency_access_policy_grants_record_read() {
+    let (env, client, admin) = setup_test();
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Opto"),
+    );
+    let patient = Address::generate(&env);
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let responder = Address::generate(&env);
+    assert!(client.try_get_record(&responder, &record_id).is_err());
+
+    let policy_id = String::from_str(&env, "emergency_access");
+    client.create_access_policy(
+        &admin,
+        &policy_id,
+        &String::from_str(&env, "Emergency responders"),
+        &Role::None,
+        &TimeRestriction::None,
+        &CredentialType::EmergencyCredentials,
+        &SensitivityLevel::Public,
+        &false,
+    );
+    let policy = client.get_access_policy(&policy_id).unwrap();
+    assert!(policy.enabled);
+
+    // The policy alone grants nothing until the credential is held.
+    assert!(client.try_get_record(&responder, &record_id).is_err());
+
+    client.set_user_credential(&admin, &responder, &CredentialType::EmergencyCredentials);
+    assert_eq!(client.get_record(&responder, &record_id).id, record_id);
+
+    client.set_policy_enabled(&admin, &policy_id, &false);
+    assert!(!client.get_access_policy(&policy_id).unwrap().enabled);
+    assert!(client.try_get_record(&responder, &record_id).is_err());
+
+    let result = client.try_set_policy_enabled(&responder, &policy_id, &true);
+    assert!(result.is_err());
+    let missing = String::from_str(&env, "research_access");
+    assert!(client
+        .try_set_policy_enabled(&admin, &missing, &true)
+        .is_err());
+    assert!(client.get_access_policy(&missing).is_none());
+}
+
+#[test]
+fn test_credential_and_sensitivity_gate_policy_access() {
+    let (env, client, admin) = setup_test();
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Opto"),
+    );
+    let patient = Address::generate(&env);
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let researcher = Address::generate(&env);
+    assert_eq!(
+        client.get_user_credential(&researcher),
+        CredentialType::None
+    );
+    assert_eq!(
+        client.get_record_sensitivity(&record_id),
+        SensitivityLevel::Standard
+    );
+
+    client.create_access_policy(
+        &admin,
+        &String::from_str(&env, "research_access"),
+        &String::from_str(&env, "Confidential research"),
+        &Role::None,
+        &TimeRestriction::None,
+        &CredentialType::ResearchCredentials,
+        &SensitivityLevel::Confidential,
+        &false,
+    );
+
+    // Only admins may assign credentials.
+    let res = client.try_set_user_credential(
+        &researcher,
+        &researcher,
+        &CredentialType::ResearchCredentials,
+    );
+    assert!(res.is_err());
+
+    // A different credential does not satisfy the policy.
+    client.set_user_credential(&admin, &researcher, &CredentialType::MedicalLicense);
+    assert_eq!(
+        client.get_user_credential(&researcher),
+        CredentialType::MedicalLicense
+    );
+    assert!(client.try_get_record(&researcher, &record_id).is_err());
+
+    // Right credential, but the record sits below the policy's minimum level.
+    client.set_user_credential(&admin, &researcher, &CredentialType::ResearchCredentials);
+    assert!(client.try_get_record(&researcher, &record_id).is_err());
+
+    // Records at or above the minimum level are reachable.
+    client.set_record_sensitivity(&provider, &record_id, &SensitivityLevel::Confidential);
+    let reason = String::from_str(&env, "cohort study enrolment");
+    assert_eq!(
+        client
+            .get_record_with_reason(&researcher, &record_id, &reason)
+            .id,
+        record_id
+    );
+    // Sensitive records are refused on paths that carry no reason.
+    assert_eq!(
+        client.try_get_record(&researcher, &record_id).map(|_| ()),
+        Err(Ok(super::ContractError::AccessDenied))
+    );
+
+    // Restricted records also demand a medical or admin credential, which
+    // research credentials are not.
+    client.set_record_sensitivity(&provider, &record_id, &SensitivityLevel::Restricted);
+    assert_eq!(
+        client.get_record_sensitivity(&record_id),
+        SensitivityLevel::Restricted
+    );
+    assert!(client.try_get_record(&researcher, &record_id).is_err());
+
+    // Only the record's provider or an admin may change sensitivity.
+    let res = client.try_set_record_sensitivity(&researcher, &record_id, &SensitivityLevel::Public);
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_grant_custom_permission_emits_event() {
+    use soroban_sdk::testutils::Events as _;
+    use soroban_sdk::xdr::{ContractEventBody, ScVal};
+    use soroban_sdk::{symbol_short, IntoVal, TryFromVal, Val};
+
+    let (env, client, admin) = setup_test();
+
+    let user = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &user,
+        &Role::Staff,
+        &String::from_str(&env, "Staff"),
+    );
+    client.grant_custom_permission(&admin, &user, &Permission::WriteRecord);
+
+    let events = env.events().all();
+    let event = events.events().last().expect("no events published");
+    let ContractEventBody::V0(body) = &event.body;
+
+    let topics: Vec<Val> = (symbol_short!("PERM_GRT"), user.clone()).into_val(&env);
+    let expected_topics: alloc::vec::Vec<ScVal> = topics
+        .iter()
+        .map(|t| ScVal::try_from_val(&env, &t).unwrap())
+        .collect();
+    assert_eq!(body.topics.as_slice(), expected_topics.as_slice());
+
+    let expected = crate::events::PermissionGrantedEvent {
+        user,
+        permission: Permission::WriteRecord,
+        granted_by: admin,
+        timestamp: env.ledger().timestamp(),
+    };
+    let expected_val: Val = expected.into_val(&env);
+    assert_eq!(body.data, ScVal::try_from_val(&env, &expected_val).unwrap());
+}
+
+#[test]
+fn test_revoke_all_custom_permissions_offboards_user() {
+    let (env, client, admin) = setup_test();
+
+    let optom = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &optom,
+        &Role::Optometrist,
+        &String::from_str(&env, "Leaving Optom"),
+    );
+    client.grant_custom_permission(&admin, &optom, &Permission::SystemAdmin);
+    assert!(client.check_permission(&optom, &Permission::WriteRecord));
+    assert!(client.check_permission(&optom, &Permission::SystemAdmin));
+
+    client.revoke_all_custom_permissions(&admin, &optom);
+
+    assert!(!client.check_permission(&optom, &Permission::WriteRecord));
+    assert!(!client.check_permission(&optom, &Permission::ManageUsers));
+    assert!(!client.check_permission(&optom, &Permission::SystemAdmin));
+
+    // A neutered user can no longer write records.
+    let res = client.try_add_record(
+        &optom,
+        &Address::generate(&env),
+        &optom,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_get_role_assignment_shows_custom_grants() {
+    let (env, client, admin) = setup_test();
+
+    let staff = Address::generate(&env);
+    assert!(client.get_role_assignment(&staff).is_none());
+
+    client.register_user(
+        &admin,
+        &staff,
+        &Role::Staff,
+        &String::from_str(&env, "Front Desk"),
+    );
+    client.grant_custom_permission(&admin, &staff, &Permission::ReadAnyRecord);
+    client.revoke_custom_permission(&admin, &staff, &Permission::ManageUsers);
+
+    let assignment = client.get_role_assignment(&staff).unwrap();
+    assert_eq!(assignment.role, Role::Staff);
+    assert!(assignment
+        .custom_grants
+        .contains(&Permission::ReadAnyRecord));
+    assert!(assignment.custom_revokes.contains(&Permission::ManageUsers));
+    assert_eq!(assignment.expires_at, 0);
+}
+
+#[test]
+fn test_cleanup_and_renew_expired_assignments() {
+    let (env, client, admin) = setup_test();
+
+    let temp = Address::generate(&env);
+    let renewed = Address::generate(&env);
+    let permanent = Address::generate(&env);
+    env.ledger().set_timestamp(1_000);
+    env.as_contract(&client.address, || {
+        super::rbac::assign_role(&env, temp.clone(), Role::Staff, 2_000);
+        super::rbac::assign_role(&env, renewed.clone(), Role::Staff, 2_000);
+        super::rbac::assign_role(&env, permanent.clone(), Role::Staff, 0);
+    });
+
+    env.ledger().set_timestamp(3_000);
+    assert!(client.get_role_assignment(&temp).is_none());
+
+    // A lapsed assignment can be renewed before cleanup.
+    client.renew_assignment(&admin, &renewed, &10_000);
+    assert_eq!(
+        client.get_role_assignment(&renewed).unwrap().expires_at,
+        10_000
+    );
+
+    let mut users = Vec::new(&env);
+    users.push_back(temp.clone());
+    users.push_back(renewed.clone());
+    users.push_back(permanent.clone());
+    assert_eq!(client.cleanup_expired_assignments(&admin, &users), 1);
+
+    let stored = env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .has(&super::rbac::user_assignment_key(&temp))
+    });
+    assert!(!stored);
+    assert!(!client
+        .get_users_by_role(&admin, &Role::Staff, &0)
+        .contains(&temp));
+
+    // Nothing left to clean.
+    assert_eq!(client.cleanup_expired_assignments(&admin, &users), 0);
+}
+
+#[test]
+fn test_user_renews_own_assignment_within_policy() {
+    use super::events::AssignmentRenewedEvent;
+    use soroban_sdk::testutils::Events;
+    use soroban_sdk::xdr::{ContractEventBody, ScVal};
+    use soroban_sdk::{IntoVal, TryFromVal, Val};
+
+    let (env, client, _admin) = setup_test();
+
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    env.ledger().set_timestamp(1_000);
+    env.as_contract(&client.address, || {
+        super::rbac::assign_role(&env, user.clone(), Role::Patient, 2_000);
+        super::rbac::assign_role(&env, other.clone(), Role::Patient, 2_000);
+    });
+
+    let limit = 1_000 + super::MAX_SELF_RENEWAL_SECONDS;
+    let event: Val = AssignmentRenewedEvent {
+        user: user.clone(),
+        role: Role::Patient,
+        expires_at: limit,
+        renewed_by: user.clone(),
+        timestamp: 1_000,
+    }
+    .into_val(&env);
+    let expected = ScVal::try_from_val(&env, &event).unwrap();
+    client.renew_assignment(&user, &user, &limit);
+    assert!(env.events().all().events().iter().any(|event| {
+        let ContractEventBody::V0(body) = &event.body;
+        body.data == expected
+    }));
+    assert_eq!(client.get_role_assignment(&user).unwrap().expires_at, limit);
+
+    // Past the policy window, permanent, or on someone else's behalf.
+    assert_eq!(
+        client.try_renew_assignment(&user, &user, &(limit + 1)),
+        Err(Ok(super::ContractError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_renew_assignment(&user, &user, &0),
+        Err(Ok(super::ContractError::InvalidInput))
+    );
+    assert_eq!(
+        client.try_renew_assignment(&user, &other, &5_000),
+        Err(Ok(super::ContractError::Unauthorized))
+    );
+
+    // A lapsed assignment needs an administrator.
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(
+        client.try_renew_assignment(&other, &other, &5_000),
+        Err(Ok(super::ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_transitive_delegation_depth_cap() {
+    let (env, client, admin) = setup_test();
+
+    let chain: alloc::vec::Vec<Address> = (0..5).map(|_| Address::generate(&env)).collect();
+    for pair in chain.windows(2) {
+        client.delegate_role(&pair[0], &pair[1], &Role::Optometrist, &10_000);
+    }
+
+    let delegated = |from: &Address, to: &Address| {
+        env.as_contract(&client.address, || {
+            super::rbac::has_delegated_permission(&env, from, to, &Permission::ManageAccess)
+        })
+    };
+
+    // Off by default: only direct links count.
+    assert!(delegated(&chain[0], &chain[1]));
+    assert!(!delegated(&chain[0], &chain[2]));
+
+    client.set_transitive_delegation(&admin, &true);
+
+    // 2-hop chain A→B→C resolves.
+    assert!(delegated(&chain[0], &chain[2]));
+    // 4-hop chain exceeds the cap.
+    assert!(!delegated(&chain[0], &chain[4]));
+
+    // Closing the chain into a cycle still terminates and respects the cap.
+    client.delegate_role(&chain[4], &chain[0], &Role::Optometrist, &10_000);
+    assert!(delegated(&chain[3], &chain[0]));
+    assert!(!delegated(&chain[1], &chain[0]));
+}
+
+#[test]
+fn test_transitive_delegation_revisits_node_reached_by_weaker_link() {
+    let (env, client, admin) = setup_test();
+    client.set_transitive_delegation(&admin, &true);
+
+    let a = Address::generate(&env);
+    let n = Address::generate(&env);
+    let c = Address::generate(&env);
+    let d = Address::generate(&env);
+
+    // N reaches D first, but through a link without ManageAccess.
+    client.delegate_role(&n, &d, &Role::Patient, &10_000);
+    client.delegate_role(&c, &d, &Role::Optometrist, &10_000);
+    client.delegate_role(&n, &c, &Role::Optometrist, &10_000);
+    client.delegate_role(&a, &n, &Role::Optometrist, &10_000);
+
+    // A→N→C→D is valid and must still be found.
+    assert!(env.as_contract(&client.address, || {
+        super::rbac::has_delegated_permission(&env, &a, &d, &Permission::ManageAccess)
+    }));
+}
+
+#[test]
+fn test_time_restriction_utc_boundaries() {
+    use super::rbac::time_restriction_allows;
+
+    // 2024-01-04T00:00:00Z, a Thursday.
+    let thursday = 1_704_326_400u64;
+    let hour = 3600u64;
+
+    assert!(!time_restriction_allows(
+        &TimeRestriction::BusinessHours,
+        thursday + 8 * hour
+    ));
+    assert!(time_restriction_allows(
+        &TimeRestriction::BusinessHours,
+        thursday + 9 * hour
+    ));
+    assert!(time_restriction_allows(
+        &TimeRestriction::BusinessHours,
+        thursday + 17 * hour - 1
+    ));
+    assert!(!time_restriction_allows(
+        &TimeRestriction::BusinessHours,
+        thursday + 17 * hour
+    ));
+
+    // Sunday is bit 0, so Thursday is bit 4.
+    assert!(time_restriction_allows(
+        &TimeRestriction::DaysOfWeek(1 << 4),
+        thursday
+    ));
+    assert!(!time_restriction_allows(
+        &TimeRestriction::DaysOfWeek(1 << 3),
+        thursday
+    ));
+    assert!(time_restriction_allows(
+        &TimeRestriction::DaysOfWeek(1),
+        thursday + 3 * 86400
+    ));
+}
+
+#[test]
+fn test_user_profile_includes_groups_and_delegations() {
+    let (env, client, admin) = setup_test();
+
+    let user = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &user,
+        &Role::Staff,
+        &String::from_str(&env, "Front Desk"),
+    );
+
+    let group_name = String::from_str(&env, "Records Clerks");
+    let mut perms = Vec::new(&env);
+    perms.push_back(Permission::ReadAnyRecord);
+    client.create_acl_group(&admin, &group_name, &perms);
+    client.add_user_to_group(&admin, &user, &group_name);
+
+    let delegator = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &delegator,
+        &Role::Optometrist,
+        &String::from_str(&env, "Dr. Lens"),
+    );
+    let expires_at = env.ledger().timestamp() + 86400;
+    client.delegate_role(&delegator, &user, &Role::Optometrist, &expires_at);
+
+    let profile = client.get_user_profile(&user);
+    assert_eq!(profile.user.address, user);
+    assert_eq!(profile.active_role, Role::Staff);
+    assert_eq!(profile.groups.len(), 1);
+    assert_eq!(profile.groups.get(0).unwrap(), group_name);
+    assert_eq!(profile.incoming_delegations.len(), 1);
+    let delegation = profile.incoming_delegations.get(0).unwrap();
+    assert_eq!(delegation.delegator, delegator);
+    assert_eq!(delegation.role, Role::Optometrist);
+    assert_eq!(delegation.expires_at, expires_at);
+    assert!(profile.incoming_scoped_delegations.is_empty());
+
+    // Expired delegations drop out of the profile
+    env.ledger().set_timestamp(expires_at + 1);
+    let profile = client.get_user_profile(&user);
+    assert!(profile.incoming_delegations.is_empty());
+}
+
+#[test]
+fn test_custom_policy_evaluated_once_activated() {
+    let (env, client, admin) = setup_test();
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Opto"),
+    );
+    let patient = Address::generate(&env);
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    let defaults = client.get_active_policies();
+    assert_eq!(defaults.len(), 3);
+    assert!(defaults.contains(String::from_str(&env, "emergency_access")));
+
+    let responder = Address::generate(&env);
+    client.set_user_credential(&admin, &responder, &CredentialType::EmergencyCredentials);
+
+    let policy_id = String::from_str(&env, "ambulance_crew");
+    client.create_access_policy(
+        &admin,
+        &policy_id,
+        &String::from_str(&env, "Ambulance crew"),
+        &Role::None,
+        &TimeRestriction::None,
+        &CredentialType::EmergencyCredentials,
+        &SensitivityLevel::Public,
+        &false,
+    );
+
+    // Not in the active list yet, so the policy is never consulted.
+    assert!(client.try_get_record(&responder, &record_id).is_err());
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(policy_id.clone());
+    let result = client.try_set_active_policies(&responder, &ids);
+    assert_eq!(result, Err(Ok(super::ContractError::Unauthorized)));
+
+    client.set_active_policies(&admin, &ids);
+    assert_eq!(client.get_active_policies(), ids);
+    assert_eq!(client.get_record(&responder, &record_id).id, record_id);
+
+    let mut too_many = Vec::new(&env);
+    for _ in 0..21 {
+        too_many.push_back(policy_id.clone());
+    }
+    let result = client.try_set_active_policies(&admin, &too_many);
+    assert_eq!(result, Err(Ok(super::ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_sensitive_read_requires_logged_reason() {
+    let (env, client, admin) = setup_test();
+
+    let provider = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &provider,
+        &Role::Optometrist,
+        &String::from_str(&env, "Opto"),
+    );
+    let reader = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &reader,
+        &Role::Optometrist,
+        &String::from_str(&env, "Consultant"),
+    );
+    let patient = Address::generate(&env);
+    let record_id = client.add_record(
+        &provider,
+        &patient,
+        &provider,
+        &RecordType::Examination,
+        &String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+    );
+
+    // Standard records can be read without a reason.
+    let empty = String::from_str(&env, "");
+    assert_eq!(
+        client
+            .get_record_with_reason(&reader, &record_id, &empty)
+            .id,
+        record_id
+    );
+
+    client.set_record_sensitivity(&provider, &record_id, &SensitivityLevel::Confidential);
+    // Read paths without a reason refuse the record to anyone but the
+    // patient and author.
+    assert_eq!(
+        client.try_get_record(&reader, &record_id).map(|_| ()),
+        Err(Ok(super::ContractError::AccessDenied))
+    );
+    assert_eq!(
+        client
+            .try_get_record_versions(&reader, &record_id)
+            .map(|_| ()),
+        Err(Ok(super::ContractError::AccessDenied))
+    );
+    assert!(matches!(
+        client
+            .get_records_checked(&reader, &Vec::from_array(&env, [record_id]))
+            .get(0)
+            .unwrap(),
+        super::RecordFetchResult::AccessDenied(id) if id == record_id
+    ));
+    assert_eq!(client.get_record(&patient, &record_id).id, record_id);
+    let result = client.try_get_record_with_reason(&reader, &record_id, &empty);
+    assert_eq!(
+        result.map(|_| ()),
+        Err(Ok(super::ContractError::InvalidInput))
+    );
+    let blank = String::from_str(&env, "   ");
+    let result = client.try_get_record_with_reason(&reader, &record_id, &blank);
+    assert_eq!(
+        result.map(|_| ()),
+        Err(Ok(super::ContractError::InvalidInput))
+    );
+
+    let reason = String::from_str(&env, "second opinion on retinal scan");
+    let record = client.get_record_with_reason(&reader, &record_id, &reason);
+    assert_eq!(record.id, record_id);
+
+    let log = env.as_contract(&client.address, || {
+        super::audit::get_record_audit_log(&env, record_id)
+    });
+    let entry = log
+        .iter()
+        .filter(|e| e.actor == reader && e.result == super::AccessResult::Success)
+        .last();
+    assert_eq!(entry.unwrap().reason, Some(reason));
+}
+
+#[test]
+fn test_list_and_revoke_delegations_from_delegator() {
+    let (env, client, admin) = setup_test();
+
+    let patient = Address::generate(&env);
+    client.register_user(
+        &admin,
+        &patient,
+        &Role::Patient,
+        &String::from_str(&env, "Patient"),
+    );
+    let spouse = Address::generate(&env);
+    let caretaker = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 86400;
+    client.delegate_role(&patient, &spouse, &Role::Patient, &expires_at);
+    client.delegate_role(&patient, &caretaker, &Role::Patient, &0);
+
+    let outgoing = client.get_delegations_from(&patient);
+    assert_eq!(outgoing.len(), 2);
+    assert_eq!(outgoing.get(0).unwrap().delegatee, spouse);
+    assert_eq!(outgoing.get(1).unwrap().delegatee, caretaker);
+    assert!(client.get_delegations_from(&spouse).is_empty());
+
+    client.revoke_delegation(&patient, &caretaker);
+    let outgoing = client.get_delegations_from(&patient);
+    assert_eq!(outgoing.len(), 1);
+    assert_eq!(outgoing.get(0).unwrap().delegatee, spouse);
+
+    let res = client.try_revoke_delegation(&patient, &caretaker);
+    assert_eq!(res, Err(Ok(super::ContractError::DelegationNotFound)));
+
+    // Expired delegations are not listed.
+    env.ledger().set_timestamp(expires_at);
+    assert!(client.get_delegations_from(&patient).is_empty());
+}
+
+#[test]
+fn test_list_delegations_received_by_delegatee() {
+    let (env, client, _admin) = setup_test();
+
+    let parent = Address::generate(&env);
+    let clinic = Address::generate(&env);
+    let caretaker = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+    client.delegate_role(&parent, &caretaker, &Role::Patient, &expires_at);
+
+    let mut perms = Vec::new(&env);
+    perms.push_back(Permission::ManageAccess);
+    env.as_contract(&client.address, || {
+        super::rbac::delegate_permissions(&env, clinic.clone(), caretaker.clone(), perms, 0);
+    });
+
+    let full = client.get_delegations_to(&caretaker);
+    assert_eq!(full.len(), 1);
+    assert_eq!(full.get(0).unwrap().delegator, parent);
+    let scoped = client.get_scoped_delegations_to(&caretaker);
+    assert_eq!(scoped.len(), 1);
+    let scoped = scoped.get(0).unwrap();
+    assert_eq!(scoped.delegator, clinic);
+    assert!(scoped.permissions.contains(Permission::ManageAccess));
+
+    // The full delegation lapses; the open-ended scoped one remains.
+    env.ledger().set_timestamp(expires_at);
+    assert!(client.get_delegations_to(&caretaker).is_empty());
+    assert_eq!(client.get_scoped_delegations_to(&caretaker).len(), 1);
+}
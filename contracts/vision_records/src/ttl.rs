@@ -0,0 +1,31 @@
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol};
+
+/// Default number of ledgers below which a storage entry's TTL is bumped.
+pub const DEFAULT_TTL_THRESHOLD: u32 = 5184000;
+/// Default number of ledgers a storage entry's TTL is extended to.
+pub const DEFAULT_TTL_EXTEND_TO: u32 = 10368000;
+
+const TTL_POLICY: Symbol = symbol_short!("TTL_POL");
+
+/// Storage TTL settings applied by every `extend_ttl_*` helper in the contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlPolicy {
+    pub threshold: u32,
+    pub extend_to: u32,
+}
+
+/// Returns the configured TTL policy, falling back to the defaults.
+pub fn get_policy(env: &Env) -> TtlPolicy {
+    env.storage()
+        .instance()
+        .get(&TTL_POLICY)
+        .unwrap_or(TtlPolicy {
+            threshold: DEFAULT_TTL_THRESHOLD,
+            extend_to: DEFAULT_TTL_EXTEND_TO,
+        })
+}
+
+pub fn set_policy(env: &Env, policy: &TtlPolicy) {
+    env.storage().instance().set(&TTL_POLICY, policy);
+}
@@ -6,11 +6,32 @@ use crate::ContractError;
 const MIN_NAME_LEN: u32 = 2;
 const MAX_NAME_LEN: u32 = 64;
 
+/// Shortest emergency attestation accepted, ignoring surrounding
+/// whitespace, so it carries a real clinical justification rather than a
+/// placeholder.
+pub const MIN_ATTESTATION_LEN: u32 = 20;
+
+/// Longest emergency attestation accepted.
+pub const MAX_ATTESTATION_LEN: u32 = 512;
+
+/// Longest access reason kept in the audit log.
+pub const MAX_ACCESS_REASON_LEN: u32 = 256;
+
 const MIN_HASH_LEN: u32 = 32;
 const MAX_HASH_LEN: u32 = 64;
 
-const MIN_DURATION_SECONDS: u64 = 3600; // 1 hour
-const MAX_DURATION_SECONDS: u64 = 157_680_000; // 5 years
+pub const MIN_DURATION_SECONDS: u64 = 3600; // 1 hour
+pub const MAX_DURATION_SECONDS: u64 = 157_680_000; // 5 years
+/// Grant duration used when a patient hasn't set their own default.
+pub const DEFAULT_GRANT_DURATION_SECONDS: u64 = 2_592_000; // 30 days
+
+// Prescription values are compared in hundredths (e.g. "-2.25" => -225).
+const MAX_NUMERIC_LEN: u32 = 16;
+const MAX_POWER: i64 = 2000; // ±20.00 D
+const POWER_STEP: i64 = 25; // 0.25 D
+const MAX_AXIS: i64 = 18000; // 180°
+const MIN_PD: i64 = 4000; // 40 mm
+const MAX_PD: i64 = 8000; // 80 mm
 
 /// Validate a user's name.
 /// Names must be between MIN_NAME_LEN and MAX_NAME_LEN bytes.
@@ -72,16 +93,150 @@ pub fn validate_data_hash(hash: &String) -> Result<(), ContractError> {
     Ok(())
 }
 
+/// Validate an emergency access attestation.
+/// An empty attestation is `InvalidAttestation`. Otherwise it must be at most
+/// MAX_ATTESTATION_LEN bytes and, once leading and trailing whitespace is
+/// trimmed, at least MIN_ATTESTATION_LEN bytes, or it is `InvalidInput`.
+pub fn validate_attestation(attestation: &String) -> Result<(), ContractError> {
+    let len = attestation.len();
+    if len == 0 {
+        return Err(ContractError::InvalidAttestation);
+    }
+    if len > MAX_ATTESTATION_LEN {
+        return Err(ContractError::InvalidInput);
+    }
+
+    let mut buf = [0u8; MAX_ATTESTATION_LEN as usize];
+    let text = &mut buf[..len as usize];
+    attestation.copy_into_slice(text);
+    let start = text
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(text.len());
+    let end = text
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i.saturating_add(1));
+    if end.saturating_sub(start) < MIN_ATTESTATION_LEN as usize {
+        return Err(ContractError::InvalidInput);
+    }
+
+    Ok(())
+}
+
+/// Validate the reason given for reading a sensitive record.
+/// It must be non-empty, not just whitespace, and at most MAX_ACCESS_REASON_LEN bytes.
+pub fn validate_access_reason(reason: &String) -> Result<(), ContractError> {
+    let len = reason.len();
+    if len == 0 || len > MAX_ACCESS_REASON_LEN {
+        return Err(ContractError::InvalidInput);
+    }
+
+    let mut buf = [0u8; MAX_ACCESS_REASON_LEN as usize];
+    reason.copy_into_slice(&mut buf[..len as usize]);
+    if buf[..len as usize].iter().all(|b| b.is_ascii_whitespace()) {
+        return Err(ContractError::InvalidInput);
+    }
+
+    Ok(())
+}
+
 /// Validate a grant access duration.
 /// Prevent extremely short durations (e.g., 0) or extremely long ones (overflow risk).
 pub fn validate_duration(duration_seconds: u64) -> Result<(), ContractError> {
-    if !(MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(&duration_seconds) {
+    validate_duration_between(duration_seconds, MIN_DURATION_SECONDS, MAX_DURATION_SECONDS)
+}
+
+/// Validate a grant duration against admin-configured bounds (inclusive).
+pub fn validate_duration_between(
+    duration_seconds: u64,
+    min_seconds: u64,
+    max_seconds: u64,
+) -> Result<(), ContractError> {
+    if !(min_seconds..=max_seconds).contains(&duration_seconds) {
+        return Err(ContractError::InvalidInput);
+    }
+    Ok(())
+}
+
+/// Parse a decimal string such as "-2.50" or "+1.25" into hundredths.
+/// At most two fractional digits are accepted.
+fn parse_hundredths(value: &String) -> Option<i64> {
+    let len = value.len();
+    if len == 0 || len > MAX_NUMERIC_LEN {
+        return None;
+    }
+    let mut buf = [0u8; MAX_NUMERIC_LEN as usize];
+    value.copy_into_slice(&mut buf[..len as usize]);
+    let bytes = &buf[..len as usize];
+
+    let (negative, digits) = match bytes[0] {
+        b'-' => (true, &bytes[1..]),
+        b'+' => (false, &bytes[1..]),
+        _ => (false, bytes),
+    };
+
+    let mut whole: i64 = 0;
+    let mut frac: i64 = 0;
+    let mut frac_digits = 0u32;
+    let mut int_digits = 0u32;
+    let mut seen_dot = false;
+    for &b in digits {
+        match b {
+            b'.' if !seen_dot => seen_dot = true,
+            b'0'..=b'9' if seen_dot => {
+                if frac_digits == 2 {
+                    return None;
+                }
+                frac = frac * 10 + i64::from(b - b'0');
+                frac_digits += 1;
+            }
+            b'0'..=b'9' => {
+                whole = whole.checked_mul(10)?.checked_add(i64::from(b - b'0'))?;
+                int_digits += 1;
+            }
+            _ => return None,
+        }
+    }
+    if int_digits == 0 {
+        return None;
+    }
+    if frac_digits == 1 {
+        frac *= 10;
+    }
+
+    let total = whole.checked_mul(100)?.checked_add(frac)?;
+    Some(if negative { -total } else { total })
+}
+
+/// Validate a lens power (sphere or cylinder): within ±20.00 D in 0.25 steps.
+fn validate_power(value: &String) -> Result<(), ContractError> {
+    let v = parse_hundredths(value).ok_or(ContractError::InvalidInput)?;
+    if v.abs() > MAX_POWER || v % POWER_STEP != 0 {
         return Err(ContractError::InvalidInput);
     }
     Ok(())
 }
 
-pub fn validate_prescription_data(_data: &PrescriptionData) {}
+/// Validate the clinical ranges of one eye's prescription.
+/// Sphere and cylinder must be within ±20.00 D in 0.25 D steps, the axis a
+/// whole degree between 0 and 180, and the pupillary distance 40–80 mm.
+pub fn validate_prescription_data(data: &PrescriptionData) -> Result<(), ContractError> {
+    validate_power(&data.sphere)?;
+    validate_power(&data.cylinder)?;
+
+    let axis = parse_hundredths(&data.axis).ok_or(ContractError::InvalidInput)?;
+    if !(0..=MAX_AXIS).contains(&axis) || axis % 100 != 0 {
+        return Err(ContractError::InvalidInput);
+    }
+
+    let pd = parse_hundredths(&data.pd).ok_or(ContractError::InvalidInput)?;
+    if !(MIN_PD..=MAX_PD).contains(&pd) {
+        return Err(ContractError::InvalidInput);
+    }
+
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
@@ -182,4 +337,57 @@ mod tests {
             Err(ContractError::InvalidInput)
         );
     }
+
+    fn rx(env: &Env, sphere: &str, cylinder: &str, axis: &str, pd: &str) -> PrescriptionData {
+        PrescriptionData {
+            sphere: String::from_str(env, sphere),
+            cylinder: String::from_str(env, cylinder),
+            axis: String::from_str(env, axis),
+            add: String::from_str(env, "0.00"),
+            pd: String::from_str(env, pd),
+        }
+    }
+
+    #[test]
+    fn test_validate_prescription_data() {
+        let env = Env::default();
+
+        // Valid
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "-2.50", "-1.25", "180", "62")),
+            Ok(())
+        );
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "+20.00", "0", "0", "62.5")),
+            Ok(())
+        );
+
+        // Axis out of range
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "-2.50", "-1.25", "999", "62")),
+            Err(ContractError::InvalidInput)
+        );
+
+        // Sphere not in 0.25 steps / beyond ±20
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "-2.30", "-1.25", "90", "62")),
+            Err(ContractError::InvalidInput)
+        );
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "-20.25", "-1.25", "90", "62")),
+            Err(ContractError::InvalidInput)
+        );
+
+        // PD out of range
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "-2.50", "-1.25", "90", "95")),
+            Err(ContractError::InvalidInput)
+        );
+
+        // Non-numeric
+        assert_eq!(
+            validate_prescription_data(&rx(&env, "abc", "-1.25", "90", "62")),
+            Err(ContractError::InvalidInput)
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, contracttype, BytesN};
+use soroban_sdk::{contracterror, contracttype, Bytes, BytesN, Env};
 
 pub type OptionIndex = u32;
 
@@ -12,6 +12,33 @@ pub enum DataKey {
     Closed,
     MerkleRoot,
     VerificationKey,
+    /// Set once the first vote is accepted; freezes the verification key.
+    HasVotes,
+    /// Commit/reveal window boundaries, when the ballot runs in that mode.
+    Phases,
+    /// Sealed vote submitted during the commit phase, keyed by nullifier.
+    Commitment(BytesN<32>),
+    /// Minimum number of counted votes for the ballot to be valid.
+    Quorum,
+    TotalVotes,
+    Outcome,
+}
+
+/// Final state of a ballot, recorded by `finalize_ballot`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BallotOutcome {
+    Passed,
+    QuorumFailed,
+}
+
+/// Timestamps delimiting a commit-reveal ballot. Commitments are accepted
+/// while `now < commit_end`; reveals while `commit_end <= now < reveal_end`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VotePhases {
+    pub commit_end: u64,
+    pub reveal_end: u64,
 }
 
 #[contracterror]
@@ -24,4 +51,21 @@ pub enum VoteError {
     InvalidOption = 4,
     Unauthorized = 5,
     MerkleRootNotSet = 6,
+    /// The verification key can no longer change because votes were cast.
+    VotingStarted = 7,
+    InvalidPhases = 8,
+    NotInCommitPhase = 9,
+    NotInRevealPhase = 10,
+    CommitmentNotFound = 11,
+    CommitmentMismatch = 12,
+    AlreadyFinalized = 13,
+    /// Open votes are not accepted once commit-reveal phases are set.
+    CommitRevealOnly = 14,
+}
+
+/// Compute the commitment for a sealed vote: `sha256(option_be || salt)`.
+pub fn vote_commitment(env: &Env, option_index: OptionIndex, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &option_index.to_be_bytes());
+    preimage.extend_from_array(&salt.to_array());
+    env.crypto().sha256(&preimage).into()
 }
@@ -8,7 +8,7 @@
 pub mod ballot;
 pub mod merkle;
 
-use ballot::{DataKey, OptionIndex, VoteError};
+use ballot::{vote_commitment, BallotOutcome, DataKey, OptionIndex, VoteError, VotePhases};
 use soroban_sdk::{
     contract, contractimpl, contracttype, panic_with_error, Address, BytesN, Env, Vec,
 };
@@ -20,6 +20,9 @@ pub struct BallotResults {
     pub option_count: u32,
     pub tallies: Vec<u64>,
     pub closed: bool,
+    pub total_votes: u64,
+    pub quorum: u32,
+    pub quorum_met: bool,
 }
 
 #[contract]
@@ -58,12 +61,27 @@ impl ZkVoting {
     }
 
     /// Set the Verification key for ZK proof validation. Admin only.
+    ///
+    /// The key may be rotated (e.g. after a new trusted setup) until the
+    /// first vote is cast; after that, changing it would invalidate the
+    /// proofs already accepted.
     pub fn set_verification_key(env: Env, caller: Address, vk: VerificationKey) {
         caller.require_auth();
         Self::require_admin(&env, &caller);
+        if Self::has_votes(env.clone()) {
+            panic_with_error!(&env, VoteError::VotingStarted);
+        }
         env.storage().instance().set(&DataKey::VerificationKey, &vk);
     }
 
+    /// Whether any vote has been cast on this ballot.
+    pub fn has_votes(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::HasVotes)
+            .unwrap_or(false)
+    }
+
     /// Return the current Verification key.
     pub fn get_verification_key(env: Env) -> Option<VerificationKey> {
         env.storage().instance().get(&DataKey::VerificationKey)
@@ -88,8 +106,11 @@ impl ZkVoting {
         proof: Proof,
         public_inputs: Vec<BytesN<32>>,
     ) -> Result<(), VoteError> {
-        // 1. Ballot must be open
+        // 1. Ballot must be open, and not in commit-reveal mode
         Self::require_open(&env);
+        if env.storage().instance().has(&DataKey::Phases) {
+            return Err(VoteError::CommitRevealOnly);
+        }
 
         // 2. Option must be valid
         let option_count: u32 = env.storage().instance().get(&DataKey::OptionCount).unwrap();
@@ -106,39 +127,181 @@ impl ZkVoting {
             return Err(VoteError::NullifierAlreadyUsed);
         }
 
-        // 4. Merkle root must be set
-        let _root: BytesN<32> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::MerkleRoot)
-            .ok_or(VoteError::MerkleRootNotSet)?;
-
-        // 5. Verify the ZK proof
-        let vk_opt: Option<VerificationKey> =
-            env.storage().instance().get(&DataKey::VerificationKey);
-        let vk = vk_opt.ok_or(VoteError::InvalidProof)?;
-        if !Bn254Verifier::verify_proof(&env, &vk, &proof, &public_inputs) {
-            return Err(VoteError::InvalidProof);
-        }
+        // 4-5. Merkle root must be set and the ZK proof must verify
+        Self::require_membership(&env, &proof, &public_inputs)?;
 
         // 6. Spend the nullifier
         env.storage()
             .persistent()
             .set(&DataKey::Nullifier(nullifier), &true);
+        env.storage().instance().set(&DataKey::HasVotes, &true);
 
         // 7. Increment tally
-        let current: u64 = env
+        Self::count_vote(&env, option_index);
+
+        Ok(())
+    }
+
+    /// Switch the ballot to commit-reveal mode. Admin only.
+    ///
+    /// Sealed votes are accepted until `commit_end`; they can then be
+    /// revealed until `reveal_end`. Phases cannot change once voting started.
+    pub fn set_vote_phases(env: Env, caller: Address, commit_end: u64, reveal_end: u64) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::require_open(&env);
+        if Self::has_votes(env.clone()) {
+            panic_with_error!(&env, VoteError::VotingStarted);
+        }
+        if commit_end <= env.ledger().timestamp() || reveal_end <= commit_end {
+            panic_with_error!(&env, VoteError::InvalidPhases);
+        }
+        env.storage().instance().set(
+            &DataKey::Phases,
+            &VotePhases {
+                commit_end,
+                reveal_end,
+            },
+        );
+    }
+
+    /// Return the commit-reveal phases, if the ballot uses them.
+    pub fn get_vote_phases(env: Env) -> Option<VotePhases> {
+        env.storage().instance().get(&DataKey::Phases)
+    }
+
+    /// Submit a sealed vote during the commit phase.
+    /// - `nullifier`    : 32-byte one-time tag to prevent double-voting
+    /// - `commitment`   : `sha256(option_index_be || salt)`
+    /// - `proof`        : Groth16 ZK proof of Merkle membership, as for `cast_vote`
+    /// - `public_inputs`: public signals (first element must encode the root)
+    pub fn commit_vote(
+        env: Env,
+        nullifier: BytesN<32>,
+        commitment: BytesN<32>,
+        proof: Proof,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        Self::require_open(&env);
+
+        let phases: VotePhases = env
+            .storage()
+            .instance()
+            .get(&DataKey::Phases)
+            .ok_or(VoteError::NotInCommitPhase)?;
+        if env.ledger().timestamp() >= phases.commit_end {
+            return Err(VoteError::NotInCommitPhase);
+        }
+
+        let commitment_key = DataKey::Commitment(nullifier.clone());
+        if env.storage().persistent().has(&commitment_key)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::Nullifier(nullifier))
+        {
+            return Err(VoteError::NullifierAlreadyUsed);
+        }
+
+        Self::require_membership(&env, &proof, &public_inputs)?;
+
+        env.storage().persistent().set(&commitment_key, &commitment);
+        env.storage().instance().set(&DataKey::HasVotes, &true);
+        Ok(())
+    }
+
+    /// Open a sealed vote during the reveal phase and count it.
+    pub fn reveal_vote(
+        env: Env,
+        nullifier: BytesN<32>,
+        option_index: OptionIndex,
+        salt: BytesN<32>,
+    ) -> Result<(), VoteError> {
+        Self::require_open(&env);
+
+        let phases: VotePhases = env
+            .storage()
+            .instance()
+            .get(&DataKey::Phases)
+            .ok_or(VoteError::NotInRevealPhase)?;
+        let now = env.ledger().timestamp();
+        if now < phases.commit_end || now >= phases.reveal_end {
+            return Err(VoteError::NotInRevealPhase);
+        }
+
+        if env
             .storage()
             .persistent()
-            .get(&DataKey::Tally(option_index))
-            .unwrap_or(0);
+            .has(&DataKey::Nullifier(nullifier.clone()))
+        {
+            return Err(VoteError::NullifierAlreadyUsed);
+        }
+        let commitment_key = DataKey::Commitment(nullifier.clone());
+        let commitment: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&commitment_key)
+            .ok_or(VoteError::CommitmentNotFound)?;
+
+        let option_count: u32 = env.storage().instance().get(&DataKey::OptionCount).unwrap();
+        if option_index >= option_count {
+            return Err(VoteError::InvalidOption);
+        }
+        if vote_commitment(&env, option_index, &salt) != commitment {
+            return Err(VoteError::CommitmentMismatch);
+        }
+
+        env.storage().persistent().remove(&commitment_key);
         env.storage()
             .persistent()
-            .set(&DataKey::Tally(option_index), &(current + 1));
+            .set(&DataKey::Nullifier(nullifier), &true);
+
+        Self::count_vote(&env, option_index);
 
         Ok(())
     }
 
+    /// Set the minimum number of votes required for the ballot to pass.
+    /// Admin only; must be fixed before voting starts.
+    pub fn set_quorum(env: Env, caller: Address, quorum: u32) {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        Self::require_open(&env);
+        if Self::has_votes(env.clone()) {
+            panic_with_error!(&env, VoteError::VotingStarted);
+        }
+        env.storage().instance().set(&DataKey::Quorum, &quorum);
+    }
+
+    /// Whether the number of counted votes has reached the quorum.
+    pub fn is_quorum_met(env: Env) -> bool {
+        Self::total_votes(&env) >= u64::from(Self::quorum(&env))
+    }
+
+    /// Close the ballot and record whether it passed or failed quorum.
+    /// Admin only; can be called once.
+    pub fn finalize_ballot(env: Env, caller: Address) -> BallotOutcome {
+        caller.require_auth();
+        Self::require_admin(&env, &caller);
+        if env.storage().instance().has(&DataKey::Outcome) {
+            panic_with_error!(&env, VoteError::AlreadyFinalized);
+        }
+
+        let outcome = if Self::is_quorum_met(env.clone()) {
+            BallotOutcome::Passed
+        } else {
+            BallotOutcome::QuorumFailed
+        };
+        env.storage().instance().set(&DataKey::Closed, &true);
+        env.storage().instance().set(&DataKey::Outcome, &outcome);
+        outcome
+    }
+
+    /// Return the recorded outcome, once the ballot is finalized.
+    pub fn get_outcome(env: Env) -> Option<BallotOutcome> {
+        env.storage().instance().get(&DataKey::Outcome)
+    }
+
     /// Return tallies for all options. Publicly verifiable.
     pub fn get_results(env: Env) -> BallotResults {
         let option_count: u32 = env
@@ -161,10 +324,15 @@ impl ZkVoting {
                 .unwrap_or(0);
             tallies.push_back(t);
         }
+        let total_votes = Self::total_votes(&env);
+        let quorum = Self::quorum(&env);
         BallotResults {
             option_count,
             tallies,
             closed,
+            total_votes,
+            quorum,
+            quorum_met: total_votes >= u64::from(quorum),
         }
     }
 
@@ -189,6 +357,31 @@ impl ZkVoting {
         }
     }
 
+    fn count_vote(env: &Env, option_index: OptionIndex) {
+        let current: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Tally(option_index))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Tally(option_index), &(current + 1));
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalVotes, &(Self::total_votes(env) + 1));
+    }
+
+    fn total_votes(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalVotes)
+            .unwrap_or(0)
+    }
+
+    fn quorum(env: &Env) -> u32 {
+        env.storage().instance().get(&DataKey::Quorum).unwrap_or(0)
+    }
+
     fn require_open(env: &Env) {
         let closed: bool = env
             .storage()
@@ -199,4 +392,25 @@ impl ZkVoting {
             panic_with_error!(env, VoteError::BallotNotOpen);
         }
     }
+
+    /// Check that the Merkle root is set and the proof of membership verifies.
+    fn require_membership(
+        env: &Env,
+        proof: &Proof,
+        public_inputs: &Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        let _root: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MerkleRoot)
+            .ok_or(VoteError::MerkleRootNotSet)?;
+
+        let vk_opt: Option<VerificationKey> =
+            env.storage().instance().get(&DataKey::VerificationKey);
+        let vk = vk_opt.ok_or(VoteError::InvalidProof)?;
+        if !Bn254Verifier::verify_proof(env, &vk, proof, public_inputs) {
+            return Err(VoteError::InvalidProof);
+        }
+        Ok(())
+    }
 }
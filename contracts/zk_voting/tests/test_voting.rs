@@ -1,9 +1,13 @@
 #![cfg(test)]
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, Vec,
+};
 use zk_verifier::verifier::{G1Point, G2Point};
 use zk_verifier::Proof;
+use zk_voting::ballot::{vote_commitment, BallotOutcome, VoteError};
 use zk_voting::merkle::{make_leaf, MerkleTree};
 use zk_voting::{ZkVoting, ZkVotingClient};
 
@@ -272,3 +276,122 @@ fn test_tampered_merkle_leaf_fails() {
 
     assert!(!MerkleTree::verify_proof(&env, &root, &bogus, 0, &proof));
 }
+
+#[test]
+fn test_verification_key_rotation_locked_after_first_vote() {
+    let (env, admin, client, _root) = setup();
+
+    // Rotating before any vote is allowed.
+    let mut vk = client.get_verification_key().unwrap();
+    let mut alpha_x = [0u8; 32];
+    alpha_x[0] = 7;
+    vk.alpha_g1.x = BytesN::from_array(&env, &alpha_x);
+    client.set_verification_key(&admin, &vk);
+    assert_eq!(client.get_verification_key(), Some(vk.clone()));
+    assert!(!client.has_votes());
+
+    let (proof, inputs) = valid_proof(&env);
+    client.cast_vote(&nullifier(&env, 9), &0u32, &proof, &inputs);
+    assert!(client.has_votes());
+
+    // Once a vote is cast, the key is frozen.
+    let mut rotated = vk.clone();
+    rotated.alpha_g1.x = BytesN::from_array(&env, &[0u8; 32]);
+    let result = client.try_set_verification_key(&admin, &rotated);
+    assert!(result.is_err());
+    assert_eq!(client.get_verification_key(), Some(vk));
+}
+
+#[test]
+fn test_commit_reveal_flow() {
+    let (env, admin, client, _root) = setup();
+    env.ledger().set_timestamp(1_000);
+    client.set_vote_phases(&admin, &2_000u64, &3_000u64);
+
+    let n = nullifier(&env, 20);
+    let salt = BytesN::from_array(&env, &[42u8; 32]);
+    let commitment = vote_commitment(&env, 2, &salt);
+    let (proof, inputs) = valid_proof(&env);
+    let (bad_proof, bad_inputs) = invalid_proof(&env);
+
+    // Commit phase: open votes are refused, and sealed votes need a valid
+    // membership proof.
+    assert_eq!(
+        client.try_cast_vote(&nullifier(&env, 22), &0u32, &proof, &inputs),
+        Err(Ok(VoteError::CommitRevealOnly))
+    );
+    assert_eq!(
+        client.try_commit_vote(&nullifier(&env, 23), &commitment, &bad_proof, &bad_inputs),
+        Err(Ok(VoteError::InvalidProof))
+    );
+
+    // Sealed vote accepted, reveal not yet possible.
+    client.commit_vote(&n, &commitment, &proof, &inputs);
+    assert_eq!(
+        client.try_commit_vote(&n, &commitment, &proof, &inputs),
+        Err(Ok(VoteError::NullifierAlreadyUsed))
+    );
+    assert_eq!(
+        client.try_reveal_vote(&n, &2u32, &salt),
+        Err(Ok(VoteError::NotInRevealPhase))
+    );
+    assert_eq!(client.get_results().tallies.get(2).unwrap(), 0u64);
+
+    // Reveal phase: commitments are closed and must match.
+    env.ledger().set_timestamp(2_500);
+    assert_eq!(
+        client.try_commit_vote(&nullifier(&env, 21), &commitment, &proof, &inputs),
+        Err(Ok(VoteError::NotInCommitPhase))
+    );
+    assert_eq!(
+        client.try_reveal_vote(&n, &1u32, &salt),
+        Err(Ok(VoteError::CommitmentMismatch))
+    );
+    client.reveal_vote(&n, &2u32, &salt);
+    assert_eq!(client.get_results().tallies.get(2).unwrap(), 1u64);
+    assert!(client.is_nullifier_used(&n));
+    assert!(client.try_reveal_vote(&n, &2u32, &salt).is_err());
+
+    // After the reveal window nothing more is counted.
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(
+        client.try_reveal_vote(&nullifier(&env, 21), &0u32, &salt),
+        Err(Ok(VoteError::NotInRevealPhase))
+    );
+}
+
+#[test]
+fn test_finalize_below_quorum_fails() {
+    let (env, admin, client, _root) = setup();
+    client.set_quorum(&admin, &2u32);
+
+    let (proof, inputs) = valid_proof(&env);
+    client.cast_vote(&nullifier(&env, 30), &1u32, &proof, &inputs);
+
+    let results = client.get_results();
+    assert_eq!(results.total_votes, 1);
+    assert!(!results.quorum_met);
+    assert!(!client.is_quorum_met());
+
+    assert_eq!(client.finalize_ballot(&admin), BallotOutcome::QuorumFailed);
+    assert_eq!(client.get_outcome(), Some(BallotOutcome::QuorumFailed));
+    assert!(client.get_results().closed);
+    assert!(client.try_finalize_ballot(&admin).is_err());
+}
+
+#[test]
+fn test_finalize_at_quorum_passes() {
+    let (env, admin, client, _root) = setup();
+    client.set_quorum(&admin, &2u32);
+
+    let (proof, inputs) = valid_proof(&env);
+    client.cast_vote(&nullifier(&env, 31), &0u32, &proof, &inputs);
+    client.cast_vote(&nullifier(&env, 32), &2u32, &proof, &inputs);
+    assert!(client.is_quorum_met());
+
+    // Quorum is fixed once voting has started.
+    assert!(client.try_set_quorum(&admin, &5u32).is_err());
+
+    assert_eq!(client.finalize_ballot(&admin), BallotOutcome::Passed);
+    assert_eq!(client.get_results().total_votes, 2);
+}